@@ -0,0 +1,44 @@
+use std::env;
+use std::fs;
+use std::path::Path;
+
+/// Lists the migrations embedded for the chosen backend, so the api can
+/// tell which ones have not been applied yet
+fn main() {
+    let dir = if env::var_os("CARGO_FEATURE_SQLITE").is_some() {
+        "../migrations_sqlite"
+    } else {
+        "../migrations"
+    };
+    println!("cargo:rerun-if-changed={}", dir);
+
+    let mut names: Vec<String> = fs::read_dir(dir)
+        .unwrap_or_else(|_| panic!("Cannot read migrations in {}", dir))
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.path().is_dir())
+        .map(|entry| entry.file_name().to_string_lossy().into_owned())
+        .filter(|name| !name.starts_with('.'))
+        .collect();
+    names.sort();
+
+    // Versions are derived the same way diesel does: the part of the
+    // directory name before the first underscore, without dashes
+    let entries: String = names
+        .iter()
+        .map(|name| {
+            let version = name.split('_').next().unwrap_or_default().replace('-', "");
+            format!("    ({:?}, {:?}),\n", version, name)
+        })
+        .collect();
+
+    let out = Path::new(&env::var("OUT_DIR").unwrap()).join("migrations.rs");
+    fs::write(
+        out,
+        format!(
+            "/// Version and name of every embedded migration, oldest first\n\
+             pub const MIGRATIONS: &[(&str, &str)] = &[\n{}];\n",
+            entries
+        ),
+    )
+    .expect("Cannot write migration list");
+}
@@ -0,0 +1,178 @@
+use chrono::{Duration, NaiveDate};
+use rocket::Route;
+use rocket_contrib::json::Json;
+use std::str::FromStr;
+
+use crate::db::Resource;
+use crate::error::Error;
+use crate::pool::Db;
+use diet_database::composition::{self, Composition, MAX_GAP_DAYS};
+use diet_database::exercise::{self, DailyBurn, Exercise};
+use diet_database::food_log::{self, DailyTotal, FoodLog};
+use diet_database::listing::{ListQuery, Listing, Order};
+use diet_database::metric::Metric;
+use diet_database::sleep::{self, Sleep, WeeklySleep};
+use diet_database::trend::{self, Trend};
+use diet_database::units::UnitSystem;
+use diet_database::weight::Weight;
+
+/// Routes for series derived from the logged rows, relative to the root
+pub fn routes() -> Vec<Route> {
+    routes![
+        weight_trend,
+        body_composition,
+        food_daily_totals,
+        exercise_daily_totals,
+        sleep_weekly_averages
+    ]
+}
+
+/// Moving averages, smoothed trend and rate of change of every weight from
+/// `from` to `to`, in `units` or else metric.  With a `target` weight, also
+/// projects the date it will be reached.
+#[get("/weight/trend?<target>&<units>&<from>&<to>")]
+fn weight_trend(
+    conn: Db,
+    target: Option<f32>,
+    units: Option<String>,
+    from: Option<String>,
+    to: Option<String>,
+) -> Result<Json<Trend>, Error> {
+    let units: UnitSystem = parse("units", units)?.unwrap_or_default();
+    let query = ListQuery {
+        from: parse("from", from)?,
+        to: parse("to", to)?,
+        order: Order::Asc,
+        ..Default::default()
+    };
+    let weights = Weight::select_all(&conn, query)?.items;
+    Ok(Json(trend::trend(&weights, units.weight(), target)))
+}
+
+/// Every body fat reading from `from` to `to` split into fat and lean mass
+/// using the nearest weight, in `units` or else metric
+#[get("/composition?<units>&<from>&<to>&<order>")]
+fn body_composition(
+    conn: Db,
+    units: Option<String>,
+    from: Option<String>,
+    to: Option<String>,
+    order: Option<String>,
+) -> Result<Json<Listing<Composition>>, Error> {
+    let units: UnitSystem = parse("units", units)?.unwrap_or_default();
+    let from: Option<NaiveDate> = parse("from", from)?;
+    let to: Option<NaiveDate> = parse("to", to)?;
+    let order: Order = parse("order", order)?.unwrap_or_default();
+
+    let query = ListQuery {
+        from,
+        to,
+        ..Default::default()
+    };
+    let metrics = Metric::select_all(&conn, query)?.items;
+    // A weight just outside the range can still be the nearest to a reading
+    let margin = Duration::days(MAX_GAP_DAYS);
+    let query = ListQuery {
+        from: from.map(|x| x - margin),
+        to: to.map(|x| x + margin),
+        ..Default::default()
+    };
+    let weights = Weight::select_all(&conn, query)?.items;
+
+    let mut items = composition::compose(&metrics, &weights, units.weight());
+    if order == Order::Desc {
+        items.reverse();
+    }
+    Ok(Json(Listing {
+        total: items.len() as i64,
+        items,
+    }))
+}
+
+/// Calories and macros eaten on each day from `from` to `to`
+#[get("/food_log/daily?<from>&<to>&<order>")]
+fn food_daily_totals(
+    conn: Db,
+    from: Option<String>,
+    to: Option<String>,
+    order: Option<String>,
+) -> Result<Json<Listing<DailyTotal>>, Error> {
+    let query = ListQuery {
+        from: parse("from", from)?,
+        to: parse("to", to)?,
+        ..Default::default()
+    };
+    let order: Order = parse("order", order)?.unwrap_or_default();
+    let entries = FoodLog::select_all(&conn, query)?.items;
+
+    let mut items = food_log::daily_totals(&entries);
+    if order == Order::Desc {
+        items.reverse();
+    }
+    Ok(Json(Listing {
+        total: items.len() as i64,
+        items,
+    }))
+}
+
+/// Calories burned by workouts on each day from `from` to `to`, next to the
+/// calories eaten
+#[get("/exercise/daily?<from>&<to>&<order>")]
+fn exercise_daily_totals(
+    conn: Db,
+    from: Option<String>,
+    to: Option<String>,
+    order: Option<String>,
+) -> Result<Json<Listing<DailyBurn>>, Error> {
+    let query = ListQuery {
+        from: parse("from", from)?,
+        to: parse("to", to)?,
+        ..Default::default()
+    };
+    let order: Order = parse("order", order)?.unwrap_or_default();
+    let exercises = Exercise::select_all(&conn, query)?.items;
+    let entries = FoodLog::select_all(&conn, query)?.items;
+
+    let intake = food_log::daily_totals(&entries);
+    let mut items = exercise::daily_totals(&exercises, &intake);
+    if order == Order::Desc {
+        items.reverse();
+    }
+    Ok(Json(Listing {
+        total: items.len() as i64,
+        items,
+    }))
+}
+
+/// Average sleep of each week, from Monday, with nights from `from` to `to`
+#[get("/sleep/weekly?<from>&<to>&<order>")]
+fn sleep_weekly_averages(
+    conn: Db,
+    from: Option<String>,
+    to: Option<String>,
+    order: Option<String>,
+) -> Result<Json<Listing<WeeklySleep>>, Error> {
+    let query = ListQuery {
+        from: parse("from", from)?,
+        to: parse("to", to)?,
+        ..Default::default()
+    };
+    let order: Order = parse("order", order)?.unwrap_or_default();
+    let nights = Sleep::select_all(&conn, query)?.items;
+
+    let mut items = sleep::weekly_averages(&nights);
+    if order == Order::Desc {
+        items.reverse();
+    }
+    Ok(Json(Listing {
+        total: items.len() as i64,
+        items,
+    }))
+}
+
+fn parse<T: FromStr>(key: &str, value: Option<String>) -> Result<Option<T>, Error> {
+    value
+        .map(|s| s.parse())
+        .transpose()
+        .map_err(|_| Error::bad_request(&format!("Invalid value for {}", key)))
+}
@@ -6,202 +6,79 @@ extern crate chrono;
 extern crate rocket_contrib;
 extern crate rocket_cors;
 
+use rocket::fairing::AdHoc;
 use rocket::http::Status;
+use rocket::Rocket;
 use rocket_contrib::json::Json;
 use rocket_cors::{AllowedOrigins, Error};
 
-use api::db;
-
-mod grocery_item {
-    use super::*;
-    use diet_database::grocery_item::*;
-
-    #[get("/grocery_item")]
-    pub fn get_all() -> Json<Vec<GroceryItem>> {
-        let conn = db::create_connection();
-        let items = db::grocery_item::select_all(&conn).unwrap_or_default();
-        Json(items)
-    }
-
-    #[post("/grocery_item", data = "<item>")]
-    pub fn add(item: Json<NewGroceryItem>) -> Status {
-        let item = item.into_inner();
-        let conn = db::create_connection();
-        match db::grocery_item::insert(&conn, item) {
-            Ok(_) => Status::Ok,
-            Err(_) => Status::InternalServerError,
-        }
-    }
-
-    #[delete("/grocery_item", data = "<item>")]
-    pub fn delete(item: Json<GroceryItem>) -> Status {
-        let item = item.into_inner();
-        let conn = db::create_connection();
-        match db::grocery_item::delete(&conn, item) {
-            Ok(_) => Status::Ok,
-            Err(_) => Status::InternalServerError,
-        }
-    }
+use api::error::Error as ApiError;
+use api::pool::{Db, DietPool};
+use api::resource::MountResource;
+use diet_database::api_error::ErrorCode;
+use diet_database::schema_status::SchemaStatus;
+
+#[get("/schema")]
+fn schema(conn: Db) -> Result<Json<SchemaStatus>, ApiError> {
+    api::migrations::status(&conn).map(Json).map_err(|e| {
+        ApiError::new(
+            Status::InternalServerError,
+            ErrorCode::Internal,
+            &e.to_string(),
+        )
+    })
 }
 
-mod weight {
-    use super::*;
-    use diet_database::weight::*;
-
-    #[get("/weight")]
-    pub fn get_all() -> Json<Vec<Weight>> {
-        let conn = db::create_connection();
-        let items = db::weight::select_all(&conn).unwrap_or_default();
-        Json(items)
-    }
-
-    #[post("/weight", data = "<item>")]
-    pub fn add(item: Json<NewWeight>) -> Status {
-        let item = item.into_inner();
-        let conn = db::create_connection();
-        match db::weight::insert(&conn, item) {
-            Ok(_) => Status::Ok,
-            Err(_) => Status::InternalServerError,
-        }
-    }
-
-    #[delete("/weight", data = "<item>")]
-    pub fn delete(item: Json<Weight>) -> Status {
-        let item = item.into_inner();
-        let conn = db::create_connection();
-        match db::weight::delete(&conn, item) {
-            Ok(_) => Status::Ok,
-            Err(_) => Status::InternalServerError,
+/// Brings the database up to date before any request is served
+fn run_migrations(rocket: Rocket) -> Result<Rocket, Rocket> {
+    let conn = match DietPool::get_one(&rocket) {
+        Some(conn) => conn,
+        None => return Err(rocket),
+    };
+    match api::migrations::run(&conn) {
+        Ok(()) => Ok(rocket),
+        Err(e) => {
+            eprintln!("Failed to run database migrations: {}", e);
+            Err(rocket)
         }
     }
 }
 
-mod metric {
-    use super::*;
-    use diet_database::metric::*;
-
-    #[get("/metric")]
-    pub fn get_all() -> Json<Vec<Metric>> {
-        let conn = db::create_connection();
-        let bowels = db::metric::select_all(&conn).unwrap_or_default();
-        Json(bowels)
-    }
-
-    #[post("/metric", data = "<bowel>")]
-    pub fn add(bowel: Json<NewMetric>) -> Status {
-        let bowel = bowel.into_inner();
-        let conn = db::create_connection();
-        match db::metric::insert(&conn, bowel) {
-            Ok(_) => Status::Ok,
-            Err(_) => Status::InternalServerError,
-        }
-    }
-
-    #[delete("/metric", data = "<bowel>")]
-    pub fn delete(bowel: Json<Metric>) -> Status {
-        let bowel = bowel.into_inner();
-        let conn = db::create_connection();
-        match db::metric::delete(&conn, bowel) {
-            Ok(_) => Status::Ok,
-            Err(_) => Status::InternalServerError,
-        }
-    }
+#[catch(400)]
+fn bad_request() -> ApiError {
+    ApiError::bad_request("The request could not be understood")
 }
 
-mod bowel {
-    use super::*;
-    use diet_database::bowel::*;
-
-    #[get("/bowel")]
-    pub fn get_all() -> Json<Vec<Bowel>> {
-        let conn = db::create_connection();
-        let bowels = db::bowel::select_all(&conn).unwrap_or_default();
-        Json(bowels)
-    }
-
-    #[post("/bowel", data = "<bowel>")]
-    pub fn add(bowel: Json<NewBowel>) -> Status {
-        let bowel = bowel.into_inner();
-        let conn = db::create_connection();
-        match db::bowel::insert(&conn, bowel) {
-            Ok(_) => Status::Ok,
-            Err(_) => Status::InternalServerError,
-        }
-    }
-
-    #[delete("/bowel", data = "<bowel>")]
-    pub fn delete(bowel: Json<Bowel>) -> Status {
-        let bowel = bowel.into_inner();
-        let conn = db::create_connection();
-        match db::bowel::delete(&conn, bowel) {
-            Ok(_) => Status::Ok,
-            Err(_) => Status::InternalServerError,
-        }
-    }
+#[catch(404)]
+fn not_found() -> ApiError {
+    ApiError::not_found("No such route")
 }
 
-mod store {
-    use super::*;
-    use diet_database::store::*;
-
-    #[get("/store")]
-    pub fn get_all() -> Json<Vec<Store>> {
-        let conn = db::create_connection();
-        let stores = db::store::select_all(&conn).unwrap_or_default();
-        Json(stores)
-    }
-
-    #[post("/store", data = "<store>")]
-    pub fn add(store: Json<NewStore>) -> Status {
-        let store = store.into_inner();
-        let conn = db::create_connection();
-        match db::store::insert(&conn, store) {
-            Ok(_) => Status::Ok,
-            Err(_) => Status::InternalServerError,
-        }
-    }
-
-    #[delete("/store", data = "<store>")]
-    pub fn delete(store: Json<Store>) -> Status {
-        let store = store.into_inner();
-        let conn = db::create_connection();
-        match db::store::delete(&conn, store) {
-            Ok(_) => Status::Ok,
-            Err(_) => Status::InternalServerError,
-        }
-    }
+#[catch(422)]
+fn unprocessable_entity() -> ApiError {
+    ApiError::new(
+        Status::UnprocessableEntity,
+        ErrorCode::InvalidInput,
+        "The body does not describe a valid row",
+    )
 }
 
-mod grocery_trip {
-    use super::*;
-    use diet_database::grocery_trip::*;
-
-    #[get("/grocery_trip")]
-    pub fn get_all() -> Json<Vec<GroceryTrip>> {
-        let conn = db::create_connection();
-        let grocery_trips = db::grocery_trip::select_all(&conn).unwrap_or_default();
-        Json(grocery_trips)
-    }
-
-    #[post("/grocery_trip", data = "<grocery_trip>")]
-    pub fn add(grocery_trip: Json<NewGroceryTrip>) -> Status {
-        let grocery_trip = grocery_trip.into_inner();
-        let conn = db::create_connection();
-        match db::grocery_trip::insert(&conn, grocery_trip) {
-            Ok(_) => Status::Ok,
-            Err(_) => Status::InternalServerError,
-        }
-    }
+#[catch(500)]
+fn internal_error() -> ApiError {
+    ApiError::new(
+        Status::InternalServerError,
+        ErrorCode::Internal,
+        "Something went wrong",
+    )
+}
 
-    #[delete("/grocery_trip", data = "<grocery_trip>")]
-    pub fn delete(grocery_trip: Json<GroceryTrip>) -> Status {
-        let grocery_trip = grocery_trip.into_inner();
-        let conn = db::create_connection();
-        match db::grocery_trip::delete(&conn, grocery_trip) {
-            Ok(_) => Status::Ok,
-            Err(_) => Status::InternalServerError,
-        }
-    }
+#[catch(503)]
+fn service_unavailable() -> ApiError {
+    ApiError::new(
+        Status::ServiceUnavailable,
+        ErrorCode::Unavailable,
+        "The database cannot be reached",
+    )
 }
 
 fn main() -> Result<(), Error> {
@@ -211,30 +88,26 @@ fn main() -> Result<(), Error> {
     }
     .to_cors()?;
 
-    rocket::ignite()
-        .mount(
-            "/",
-            routes![
-                bowel::get_all,
-                bowel::add,
-                bowel::delete,
-                store::get_all,
-                store::add,
-                store::delete,
-                grocery_trip::get_all,
-                grocery_trip::add,
-                grocery_trip::delete,
-                metric::get_all,
-                metric::add,
-                metric::delete,
-                weight::get_all,
-                weight::add,
-                weight::delete,
-                grocery_item::get_all,
-                grocery_item::add,
-                grocery_item::delete,
-            ],
-        )
+    // Pass --no-migrate to leave the schema alone, e.g. when it is managed
+    // with the diesel cli instead
+    let migrate = !std::env::args().any(|arg| arg == "--no-migrate");
+
+    let mut rocket = rocket::ignite().attach(DietPool::fairing());
+    if migrate {
+        rocket = rocket.attach(AdHoc::on_attach("Database Migrations", run_migrations));
+    }
+
+    rocket
+        .mount("/", routes![schema])
+        .mount("/", api::analytics::routes())
+        .mount_tables()
+        .register(catchers![
+            bad_request,
+            not_found,
+            unprocessable_entity,
+            internal_error,
+            service_unavailable,
+        ])
         .attach(cors)
         .launch();
 
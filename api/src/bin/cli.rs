@@ -1,4 +1,13 @@
 use std::env;
+use std::fs;
+
+use api::db::{self, DbConnection, Resource, TableVisitor};
+use api::migrations;
+use diet_database::export::Format;
+use diet_database::import::ImportReport;
+use diet_database::listing::{ListQuery, Order};
+use diet_database::units::UnitSystem;
+use diet_database::{TableType, Tabular};
 
 fn main() {
     let args: Vec<String> = env::args().collect();
@@ -11,6 +20,11 @@ fn main() {
     let table = &args[1];
     match table.as_ref() {
         "bowel" => manage_bowel(&args[2..]),
+        "schema" => schema(),
+        "migrate" => migrate(),
+        "export" => export(&args[2..]),
+        "import" => import(&args[2..]),
+        "tables" => tables(),
         _ => help(),
     }
 }
@@ -23,6 +37,192 @@ fn manage_bowel(args: &[String]) {
     }
 }
 
+fn schema() {
+    let conn = db::create_connection();
+    match migrations::status(&conn) {
+        Ok(status) => {
+            println!(
+                "Schema version: {}",
+                status.version.as_deref().unwrap_or("none")
+            );
+            if status.pending.is_empty() {
+                println!("No pending migrations");
+            } else {
+                println!("Pending migrations:");
+                status.pending.iter().for_each(|name| println!("  {}", name));
+            }
+        }
+        Err(e) => println!("Cannot read schema version: {}", e),
+    }
+}
+
+fn migrate() {
+    let conn = db::create_connection();
+    if let Err(e) = migrations::run(&conn) {
+        println!("Failed to run migrations: {}", e);
+    }
+}
+
+/// `export <table> <csv|md|jsonl> [metric|imperial]` prints every row of
+/// the table, oldest first
+fn export(args: &[String]) {
+    let (table, format) = match args {
+        [table, format, ..] => (table, format),
+        _ => {
+            println!("usage: export <table> <csv|md|jsonl> [metric|imperial]");
+            return;
+        }
+    };
+    let format: Format = match format.parse() {
+        Ok(format) => format,
+        Err(e) => {
+            println!("{}", e);
+            return;
+        }
+    };
+    let units: UnitSystem = match args.get(2).map(|x| x.parse()).transpose() {
+        Ok(units) => units.unwrap_or_default(),
+        Err(e) => {
+            println!("{}", e);
+            return;
+        }
+    };
+
+    let table: TableType = match table.parse() {
+        Ok(table) => table,
+        Err(e) => {
+            println!("{}", e);
+            return;
+        }
+    };
+    let conn = db::create_connection();
+    let export = Export {
+        conn: &conn,
+        units,
+        format,
+    };
+    match db::visit(table, export) {
+        Ok(out) => print!("{}", out),
+        Err(e) => println!("Cannot export {}: {}", table, e),
+    }
+}
+
+/// Exports the visited table oldest first
+struct Export<'a> {
+    conn: &'a DbConnection,
+    units: UnitSystem,
+    format: Format,
+}
+
+impl TableVisitor for Export<'_> {
+    type Output = diesel::QueryResult<String>;
+
+    fn visit<R: Resource + 'static>(self) -> Self::Output
+    where
+        Vec<R>: Tabular,
+    {
+        let query = ListQuery {
+            order: Order::Asc,
+            ..Default::default()
+        };
+        db::export::<R>(self.conn, query, self.units, self.format)
+    }
+}
+
+/// `import <table> <file.csv> [--dry-run]` adds the rows of a CSV file,
+/// skipping rows that are invalid or already in the table
+fn import(args: &[String]) {
+    let (table, path) = match args {
+        [table, path, ..] => (table, path),
+        _ => {
+            println!("usage: import <table> <file.csv> [--dry-run]");
+            return;
+        }
+    };
+    let dry_run = args[2..].iter().any(|arg| arg == "--dry-run");
+    let text = match fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(e) => {
+            println!("Cannot read {}: {}", path, e);
+            return;
+        }
+    };
+
+    let table: TableType = match table.parse() {
+        Ok(table) => table,
+        Err(e) => {
+            println!("{}", e);
+            return;
+        }
+    };
+    let conn = db::create_connection();
+    let import = Import {
+        conn: &conn,
+        text: &text,
+        dry_run,
+    };
+    match db::visit(table, import) {
+        Ok(report) => print_report(&report),
+        Err(e) => println!("Cannot import {}: {}", table, e),
+    }
+}
+
+/// Imports a CSV file into the visited table
+struct Import<'a> {
+    conn: &'a DbConnection,
+    text: &'a str,
+    dry_run: bool,
+}
+
+impl TableVisitor for Import<'_> {
+    type Output = diesel::QueryResult<ImportReport>;
+
+    fn visit<R: Resource + 'static>(self) -> Self::Output
+    where
+        Vec<R>: Tabular,
+    {
+        db::import::<R>(self.conn, self.text, self.dry_run)
+    }
+}
+
+/// `tables` lists every table with its columns
+fn tables() {
+    for table in TableType::ALL.iter() {
+        let columns = table
+            .columns()
+            .into_iter()
+            .map(|x| x.key)
+            .collect::<Vec<String>>();
+        println!(
+            "{} ({}): {}",
+            table,
+            table.display_name(),
+            columns.join(", ")
+        );
+    }
+}
+
+fn print_report(report: &ImportReport) {
+    let verb = if report.dry_run { "Would add" } else { "Added" };
+    println!("{} {} row(s)", verb, report.added.len());
+    if !report.duplicates.is_empty() {
+        let lines = report
+            .duplicates
+            .iter()
+            .map(|x| x.to_string())
+            .collect::<Vec<String>>();
+        println!("Skipped duplicates on line(s) {}", lines.join(", "));
+    }
+    for row in &report.errors {
+        let errors = row
+            .errors
+            .iter()
+            .map(|x| x.to_string())
+            .collect::<Vec<String>>();
+        println!("Line {}: {}", row.line, errors.join(", "));
+    }
+}
+
 fn help() {
     println!("Bad, try again.");
 }
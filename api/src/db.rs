@@ -1,145 +1,1262 @@
-use diesel::{prelude::*, MysqlConnection};
+use diesel::associations::HasTable;
+use diesel::dsl::{Find, Limit};
+use diesel::prelude::*;
+use diesel::query_builder::{
+    AsChangeset, AsQuery, DeleteStatement, InsertStatement, IntoUpdateTarget, QueryFragment,
+    QueryId, UpdateStatement,
+};
+use diesel::query_dsl::{methods, LoadQuery};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
 use diet_database::db::schema;
+use diet_database::dependents::Dependents;
+use diet_database::export::{self, Format};
+use diet_database::import::{self, ImportReport, Importable, RowError};
+use diet_database::listing::{ListQuery, Listing, Order};
+use diet_database::units::UnitSystem;
+use diet_database::validate::FieldError;
+use diet_database::{TableType, Tabular};
+
+#[cfg(all(feature = "mysql", feature = "sqlite"))]
+compile_error!("features `mysql` and `sqlite` are mutually exclusive");
+
+#[cfg(not(any(feature = "mysql", feature = "sqlite")))]
+compile_error!("one of the features `mysql` or `sqlite` must be enabled");
+
+#[cfg(feature = "mysql")]
+pub type DbConnection = diesel::MysqlConnection;
+
+#[cfg(feature = "sqlite")]
+pub type DbConnection = diesel::SqliteConnection;
+
+type DbBackend = <DbConnection as Connection>::Backend;
 
 type Result<T> = std::result::Result<T, diesel::result::Error>;
 
-pub fn create_connection() -> MysqlConnection {
+pub fn create_connection() -> DbConnection {
     let database_url: String =
         std::env::var("DATABASE_URL").expect("DATABASE_URL environment variable not set");
 
-    MysqlConnection::establish(&database_url)
-        .unwrap_or_else(|_| panic!("Cannot connect to database at {}", database_url))
+    let conn = DbConnection::establish(&database_url)
+        .unwrap_or_else(|_| panic!("Cannot connect to database at {}", database_url));
+
+    // SQLite leaves foreign keys unchecked unless asked per connection
+    #[cfg(feature = "sqlite")]
+    conn.execute("PRAGMA foreign_keys = ON")
+        .expect("Cannot enable foreign keys");
+
+    conn
+}
+
+/// A row type the api serves, tied to the `New*` type it is written with
+/// and the table it lives in.  Implementing this is all it takes to get the
+/// routes in `api::resource`.
+pub trait Resource: Serialize + Sized {
+    type New: Serialize + DeserializeOwned + Importable + From<Self>;
+
+    const TABLE: TableType;
+
+    fn id(&self) -> i32;
+
+    fn insert(conn: &DbConnection, item: Self::New) -> Result<Self>;
+    fn select(conn: &DbConnection, item_id: i32) -> Result<Self>;
+    fn select_all(conn: &DbConnection, query: ListQuery) -> Result<Listing<Self>>;
+    fn update(conn: &DbConnection, item_id: i32, item: Self::New) -> Result<usize>;
+    fn delete(conn: &DbConnection, item_id: i32) -> Result<usize>;
+
+    /// The rules `item` breaks that take the database to check, such as
+    /// referring to a row that does not exist.  Checked after `Validate`.
+    fn check_references(_conn: &DbConnection, _item: &Self::New) -> Result<Vec<FieldError>> {
+        Ok(Vec::new())
+    }
+
+    /// The row with any measurements converted to `units`
+    fn in_units(self, _units: UnitSystem) -> Self {
+        self
+    }
+
+    /// Rows in other tables that refer to this one
+    fn dependents(_conn: &DbConnection, _item_id: i32) -> Result<Dependents> {
+        Ok(Dependents::default())
+    }
+
+    /// Deletes the row along with its dependents
+    fn delete_cascade(conn: &DbConnection, item_id: i32) -> Result<Dependents> {
+        match Self::delete(conn, item_id)? {
+            0 => Err(diesel::result::Error::NotFound),
+            _ => Ok(Dependents::default()),
+        }
+    }
+
+    /// Moves the row's dependents to another row, then deletes it
+    fn delete_reassign(conn: &DbConnection, item_id: i32, _other_id: i32) -> Result<Dependents> {
+        Self::delete_cascade(conn, item_id)
+    }
+}
+
+/// Something done with the `Resource` of a table whose type is only known at
+/// run time, see `visit`
+pub trait TableVisitor {
+    type Output;
+
+    fn visit<R: Resource + 'static>(self) -> Self::Output
+    where
+        Vec<R>: Tabular;
+}
+
+/// Calls `visitor` with the `Resource` that serves `table`.  This is the one
+/// place a `TableType` is matched to its row type.
+pub fn visit<V: TableVisitor>(table: TableType, visitor: V) -> V::Output {
+    use diet_database as dd;
+    match table {
+        TableType::Bowel => visitor.visit::<dd::bowel::Bowel>(),
+        TableType::Exercise => visitor.visit::<dd::exercise::Exercise>(),
+        TableType::Food => visitor.visit::<dd::food::Food>(),
+        TableType::FoodLog => visitor.visit::<dd::food_log::FoodLog>(),
+        TableType::GroceryItem => visitor.visit::<dd::grocery_item::GroceryItem>(),
+        TableType::GroceryTrip => visitor.visit::<dd::grocery_trip::GroceryTrip>(),
+        TableType::Metric => visitor.visit::<dd::metric::Metric>(),
+        TableType::Recipe => visitor.visit::<dd::recipe::Recipe>(),
+        TableType::RecipeIngredient => visitor.visit::<dd::recipe_ingredient::RecipeIngredient>(),
+        TableType::Sleep => visitor.visit::<dd::sleep::Sleep>(),
+        TableType::Store => visitor.visit::<dd::store::Store>(),
+        TableType::Water => visitor.visit::<dd::water::Water>(),
+        TableType::Weight => visitor.visit::<dd::weight::Weight>(),
+    }
+}
+
+/// Every row matching `query` in `format`, converted to `units`
+pub fn export<R: Resource>(
+    conn: &DbConnection,
+    query: ListQuery,
+    units: UnitSystem,
+    format: Format,
+) -> Result<String>
+where
+    Vec<R>: Tabular,
+{
+    let items = R::select_all(conn, query)?
+        .items
+        .into_iter()
+        .map(|x| x.in_units(units))
+        .collect::<Vec<R>>();
+    Ok(export::export(&items, format))
+}
+
+/// Adds the rows of the CSV `text`, skipping rows that are invalid or
+/// already in the table.  With `dry_run` nothing is written.
+pub fn import<R: Resource>(conn: &DbConnection, text: &str, dry_run: bool) -> Result<ImportReport> {
+    let existing = R::select_all(conn, ListQuery::default())?.items;
+    let import::Import { rows, mut report } =
+        import::prepare(text, existing.into_iter().map(R::New::from));
+    report.dry_run = dry_run;
+    for (line, item) in rows {
+        let errors = R::check_references(conn, &item)?;
+        if !errors.is_empty() {
+            report.errors.push(RowError { line, errors });
+            continue;
+        }
+        if dry_run {
+            report.added.push(line);
+            continue;
+        }
+        // Each row is added on its own, so a failed row can be fixed and
+        // the file imported again without duplicating the rest
+        match R::insert(conn, item) {
+            Ok(_) => report.added.push(line),
+            Err(e) => report.error(line, "row", &e.to_string()),
+        }
+    }
+    Ok(report)
+}
+
+/// Id of the last row inserted over this connection
+fn last_insert_id(conn: &DbConnection) -> Result<i32> {
+    use diesel::dsl::sql;
+    use diesel::sql_types::BigInt;
+
+    #[cfg(feature = "mysql")]
+    let function = "LAST_INSERT_ID()";
+    #[cfg(feature = "sqlite")]
+    let function = "last_insert_rowid()";
+
+    let id: i64 = diesel::select(sql::<BigInt>(function)).get_result(conn)?;
+    Ok(id as i32)
+}
+
+// Generic bodies for the `Resource` methods of tables read without joins
+
+/// Inserts `item` and reads it back as a `R`
+fn insert_row<R, T, N>(conn: &DbConnection, table: T, item: N) -> Result<R>
+where
+    R: Resource,
+    T: Table,
+    N: Insertable<T>,
+    InsertStatement<T, N::Values>: QueryFragment<DbBackend> + QueryId,
+{
+    conn.transaction(|| {
+        diesel::insert_into(table).values(item).execute(conn)?;
+        R::select(conn, last_insert_id(conn)?)
+    })
+}
+
+fn select_row<T, R>(conn: &DbConnection, table: T, item_id: i32) -> Result<R>
+where
+    T: methods::FindDsl<i32>,
+    Find<T, i32>: methods::LimitDsl + RunQueryDsl<DbConnection>,
+    Limit<Find<T, i32>>: LoadQuery<DbConnection, R>,
+{
+    table.find(item_id).first(conn)
+}
+
+fn update_row<T, N>(conn: &DbConnection, table: T, item_id: i32, item: N) -> Result<usize>
+where
+    T: methods::FindDsl<i32>,
+    Find<T, i32>: IntoUpdateTarget,
+    N: AsChangeset<Target = <Find<T, i32> as HasTable>::Table>,
+    UpdateStatement<
+        <Find<T, i32> as HasTable>::Table,
+        <Find<T, i32> as IntoUpdateTarget>::WhereClause,
+        N::Changeset,
+    >: AsQuery + QueryFragment<DbBackend> + QueryId,
+{
+    diesel::update(table.find(item_id)).set(item).execute(conn)
+}
+
+fn delete_row<T>(conn: &DbConnection, table: T, item_id: i32) -> Result<usize>
+where
+    T: methods::FindDsl<i32>,
+    Find<T, i32>: IntoUpdateTarget,
+    DeleteStatement<
+        <Find<T, i32> as HasTable>::Table,
+        <Find<T, i32> as IntoUpdateTarget>::WhereClause,
+    >: QueryFragment<DbBackend> + QueryId,
+{
+    diesel::delete(table.find(item_id)).execute(conn)
+}
+
+/// Filters `source` to the rows dated from `query.from` to `query.to`, orders
+/// them by the `(date, time, id)` columns and pages them.  Evaluates to how
+/// many rows match before paging and the boxed, paged query, so callers can
+/// still pick the columns they load.
+macro_rules! dated_page {
+    ($conn:expr, $query:expr, $source:expr, ($date:expr, $time:expr, $id:expr)) => {{
+        let query: ListQuery = $query;
+        let filtered = || {
+            let mut q = $source.into_boxed::<DbBackend>();
+            if let Some(from) = query.from {
+                q = q.filter($date.ge(from));
+            }
+            if let Some(to) = query.to {
+                q = q.filter($date.le(to));
+            }
+            q
+        };
+
+        let total: i64 = filtered().count().get_result($conn)?;
+        let mut q = match query.order {
+            Order::Asc => filtered().order(($date.asc(), $time.asc(), $id.asc())),
+            Order::Desc => filtered().order(($date.desc(), $time.desc(), $id.desc())),
+        };
+        if let Some((limit, offset)) = query.page() {
+            q = q.limit(limit).offset(offset);
+        }
+        (total, q)
+    }};
 }
 
 pub mod grocery_item {
     use super::*;
     use diet_database::grocery_item::*;
+    use diet_database::timestamp::Timestamp;
+
+    /// Columns joined from the trip and store to describe an item
+    type Row = (
+        i32,
+        i32,
+        String,
+        chrono::NaiveDate,
+        Option<chrono::NaiveTime>,
+        String,
+        Option<f32>,
+        Option<String>,
+        Option<i32>,
+    );
+
+    // Built here rather than with CONCAT so it works on every backend
+    fn from_row(row: Row) -> GroceryItem {
+        let (id, trip_id, store_name, trip_date, trip_time, name, amount, measure, food_id) = row;
+        GroceryItem {
+            id,
+            trip_id,
+            trip_desc: format!("{} on {}", store_name, Timestamp::new(trip_date, trip_time)),
+            name,
+            amount,
+            measure,
+            food_id,
+        }
+    }
 
-    pub fn insert(conn: &MysqlConnection, item: NewGroceryItem) -> Result<usize> {
-        diesel::insert_into(schema::grocery_item::table)
-            .values(&item)
-            .execute(conn)
+    impl Resource for GroceryItem {
+        type New = NewGroceryItem;
+
+        const TABLE: TableType = TableType::GroceryItem;
+
+        fn id(&self) -> i32 {
+            self.id
+        }
+
+        fn insert(conn: &DbConnection, item: NewGroceryItem) -> Result<GroceryItem> {
+            insert_row(conn, schema::grocery_item::table, &item)
+        }
+
+        fn select(conn: &DbConnection, item_id: i32) -> Result<GroceryItem> {
+            use schema::grocery_item::dsl::*;
+            use schema::grocery_trip::{self, date, time};
+            use schema::store;
+            let row = grocery_item
+                .inner_join(grocery_trip::table.inner_join(store::table))
+                .filter(id.eq(item_id))
+                .select((
+                    id,
+                    trip_id,
+                    store::name,
+                    date,
+                    time,
+                    name,
+                    amount,
+                    measure,
+                    food_id,
+                ))
+                .first::<Row>(conn)?;
+            Ok(from_row(row))
+        }
+
+        fn select_all(conn: &DbConnection, query: ListQuery) -> Result<Listing<GroceryItem>> {
+            use schema::grocery_item::dsl::*;
+            use schema::grocery_trip::{self, date, time};
+            use schema::store;
+            let (total, q) = dated_page!(
+                conn,
+                query,
+                grocery_item.inner_join(grocery_trip::table.inner_join(store::table)),
+                (date, time, id)
+            );
+            let rows = q
+                .select((
+                    id,
+                    trip_id,
+                    store::name,
+                    date,
+                    time,
+                    name,
+                    amount,
+                    measure,
+                    food_id,
+                ))
+                .load::<Row>(conn)?;
+            let items = rows.into_iter().map(from_row).collect();
+            Ok(Listing { total, items })
+        }
+
+        fn update(conn: &DbConnection, item_id: i32, item: NewGroceryItem) -> Result<usize> {
+            update_row(conn, schema::grocery_item::table, item_id, &item)
+        }
+
+        fn delete(conn: &DbConnection, item_id: i32) -> Result<usize> {
+            delete_row(conn, schema::grocery_item::table, item_id)
+        }
     }
+}
+
+pub mod food {
+    use super::*;
+    use diet_database::food::*;
+    use std::collections::HashMap;
 
-    pub fn select_all(conn: &MysqlConnection) -> Result<Vec<GroceryItem>> {
-        use diesel::dsl::sql;
-        use schema::grocery_item::dsl::*;
-        use schema::grocery_trip::{self, date};
-        use schema::store;
-        grocery_item
-            .inner_join(grocery_trip::table.inner_join(store::table))
-            .select((id, sql("CONCAT(store.name, ' on ', grocery_trip.date)"), name, amount, measure))
-            .load(conn)
+    /// The foods with the given ids, keyed by id
+    pub fn by_id(conn: &DbConnection, ids: Vec<i32>) -> Result<HashMap<i32, Food>> {
+        use schema::food::dsl::*;
+        let foods: Vec<Food> = food.filter(id.eq_any(ids)).load(conn)?;
+        Ok(foods.into_iter().map(|x| (x.id, x)).collect())
     }
 
-    pub fn delete(conn: &MysqlConnection, item: GroceryItem) -> Result<usize> {
-        use schema::grocery_item::dsl::*;
-        diesel::delete(grocery_item.filter(id.eq(item.id))).execute(conn)
+    impl Resource for Food {
+        type New = NewFood;
+
+        const TABLE: TableType = TableType::Food;
+
+        fn id(&self) -> i32 {
+            self.id
+        }
+
+        fn insert(conn: &DbConnection, item: NewFood) -> Result<Food> {
+            insert_row(conn, schema::food::table, &item)
+        }
+
+        fn select(conn: &DbConnection, item_id: i32) -> Result<Food> {
+            select_row(conn, schema::food::table, item_id)
+        }
+
+        fn select_all(conn: &DbConnection, query: ListQuery) -> Result<Listing<Food>> {
+            use schema::food::dsl::*;
+            // Foods have no date, so only ordering and paging apply
+            let total = food.count().get_result(conn)?;
+            let mut q = match query.order {
+                Order::Asc => food.order((name.asc(), id.asc())).into_boxed::<DbBackend>(),
+                Order::Desc => food.order((name.desc(), id.desc())).into_boxed(),
+            };
+            if let Some((limit, offset)) = query.page() {
+                q = q.limit(limit).offset(offset);
+            }
+            let items = q.load(conn)?;
+            Ok(Listing { total, items })
+        }
+
+        fn update(conn: &DbConnection, item_id: i32, item: NewFood) -> Result<usize> {
+            update_row(conn, schema::food::table, item_id, &item)
+        }
+
+        // Log entries and grocery items for the food keep their own copy of
+        // its name and nutrition, and the database unlinks them
+        fn delete(conn: &DbConnection, item_id: i32) -> Result<usize> {
+            delete_row(conn, schema::food::table, item_id)
+        }
+
+        fn dependents(conn: &DbConnection, item_id: i32) -> Result<Dependents> {
+            use schema::recipe_ingredient::{self, food_id};
+            let recipe_ingredients = recipe_ingredient::table
+                .filter(food_id.eq(item_id))
+                .count()
+                .get_result(conn)?;
+            Ok(Dependents {
+                recipe_ingredients,
+                ..Default::default()
+            })
+        }
+
+        /// Deletes the food along with the recipe ingredients made of it
+        fn delete_cascade(conn: &DbConnection, item_id: i32) -> Result<Dependents> {
+            use schema::recipe_ingredient::{self, food_id};
+            conn.transaction(|| {
+                let removed = Self::dependents(conn, item_id)?;
+                diesel::delete(recipe_ingredient::table.filter(food_id.eq(item_id)))
+                    .execute(conn)?;
+                match Self::delete(conn, item_id)? {
+                    0 => Err(diesel::result::Error::NotFound),
+                    _ => Ok(removed),
+                }
+            })
+        }
+
+        /// Makes recipes use another food instead, then deletes it
+        fn delete_reassign(
+            conn: &DbConnection,
+            item_id: i32,
+            new_food_id: i32,
+        ) -> Result<Dependents> {
+            use schema::recipe_ingredient::{self, food_id};
+            conn.transaction(|| {
+                let moved = Self::dependents(conn, item_id)?;
+                diesel::update(recipe_ingredient::table.filter(food_id.eq(item_id)))
+                    .set(food_id.eq(new_food_id))
+                    .execute(conn)?;
+                match Self::delete(conn, item_id)? {
+                    0 => Err(diesel::result::Error::NotFound),
+                    _ => Ok(moved),
+                }
+            })
+        }
     }
 }
 
-pub mod weight {
+pub mod recipe {
     use super::*;
-    use diet_database::weight::*;
+    use diet_database::recipe::*;
+    use diet_database::recipe_ingredient::RecipeIngredient;
+    use std::collections::HashMap;
+
+    type Row = (i32, String, f32);
 
-    pub fn insert(conn: &MysqlConnection, item: NewWeight) -> Result<usize> {
-        diesel::insert_into(schema::weight::table)
-            .values(&item)
-            .execute(conn)
+    /// Works out the nutrition of each recipe from its ingredients
+    fn with_ingredients(conn: &DbConnection, rows: Vec<Row>) -> Result<Vec<Recipe>> {
+        let ids = rows.iter().map(|(id, _, _)| *id).collect();
+        let mut ingredients: HashMap<i32, Vec<RecipeIngredient>> = HashMap::new();
+        for ingredient in recipe_ingredient::of_recipes(conn, ids)? {
+            ingredients
+                .entry(ingredient.recipe_id)
+                .or_default()
+                .push(ingredient);
+        }
+        let recipes = rows
+            .into_iter()
+            .map(|(id, name, servings)| {
+                let own = ingredients.remove(&id).unwrap_or_default();
+                Recipe::new(id, NewRecipe { name, servings }, &own)
+            })
+            .collect();
+        Ok(recipes)
     }
 
-    pub fn select_all(conn: &MysqlConnection) -> Result<Vec<Weight>> {
-        schema::weight::table.load::<Weight>(conn)
+    /// The recipes with the given ids, keyed by id
+    pub fn by_id(conn: &DbConnection, ids: Vec<i32>) -> Result<HashMap<i32, Recipe>> {
+        use schema::recipe::dsl::*;
+        let rows = recipe.filter(id.eq_any(ids)).load::<Row>(conn)?;
+        let recipes = with_ingredients(conn, rows)?;
+        Ok(recipes.into_iter().map(|x| (x.id, x)).collect())
     }
 
-    pub fn delete(conn: &MysqlConnection, item: Weight) -> Result<usize> {
-        use schema::weight::dsl::*;
-        diesel::delete(weight.filter(id.eq(item.id))).execute(conn)
+    impl Resource for Recipe {
+        type New = NewRecipe;
+
+        const TABLE: TableType = TableType::Recipe;
+
+        fn id(&self) -> i32 {
+            self.id
+        }
+
+        fn insert(conn: &DbConnection, item: NewRecipe) -> Result<Recipe> {
+            insert_row(conn, schema::recipe::table, &item)
+        }
+
+        fn select(conn: &DbConnection, item_id: i32) -> Result<Recipe> {
+            let row = select_row(conn, schema::recipe::table, item_id)?;
+            let mut recipes = with_ingredients(conn, vec![row])?;
+            Ok(recipes.remove(0))
+        }
+
+        fn select_all(conn: &DbConnection, query: ListQuery) -> Result<Listing<Recipe>> {
+            use schema::recipe::dsl::*;
+            // Recipes have no date, so only ordering and paging apply
+            let total = recipe.count().get_result(conn)?;
+            let mut q = match query.order {
+                Order::Asc => recipe
+                    .order((name.asc(), id.asc()))
+                    .into_boxed::<DbBackend>(),
+                Order::Desc => recipe.order((name.desc(), id.desc())).into_boxed(),
+            };
+            if let Some((limit, offset)) = query.page() {
+                q = q.limit(limit).offset(offset);
+            }
+            let items = with_ingredients(conn, q.load::<Row>(conn)?)?;
+            Ok(Listing { total, items })
+        }
+
+        fn update(conn: &DbConnection, item_id: i32, item: NewRecipe) -> Result<usize> {
+            update_row(conn, schema::recipe::table, item_id, &item)
+        }
+
+        // Log entries for the recipe keep their own copy of its name and
+        // nutrition, and the database unlinks them
+        fn delete(conn: &DbConnection, item_id: i32) -> Result<usize> {
+            delete_row(conn, schema::recipe::table, item_id)
+        }
+
+        fn dependents(conn: &DbConnection, item_id: i32) -> Result<Dependents> {
+            use schema::recipe_ingredient::{self, recipe_id};
+            let recipe_ingredients = recipe_ingredient::table
+                .filter(recipe_id.eq(item_id))
+                .count()
+                .get_result(conn)?;
+            Ok(Dependents {
+                recipe_ingredients,
+                ..Default::default()
+            })
+        }
+
+        /// Deletes the recipe along with its ingredients
+        fn delete_cascade(conn: &DbConnection, item_id: i32) -> Result<Dependents> {
+            use schema::recipe_ingredient::{self, recipe_id};
+            conn.transaction(|| {
+                let removed = Self::dependents(conn, item_id)?;
+                diesel::delete(recipe_ingredient::table.filter(recipe_id.eq(item_id)))
+                    .execute(conn)?;
+                match Self::delete(conn, item_id)? {
+                    0 => Err(diesel::result::Error::NotFound),
+                    _ => Ok(removed),
+                }
+            })
+        }
+
+        /// Moves the recipe's ingredients to another recipe, then deletes it
+        fn delete_reassign(
+            conn: &DbConnection,
+            item_id: i32,
+            new_recipe_id: i32,
+        ) -> Result<Dependents> {
+            use schema::recipe_ingredient::{self, recipe_id};
+            conn.transaction(|| {
+                let moved = Self::dependents(conn, item_id)?;
+                diesel::update(recipe_ingredient::table.filter(recipe_id.eq(item_id)))
+                    .set(recipe_id.eq(new_recipe_id))
+                    .execute(conn)?;
+                match Self::delete(conn, item_id)? {
+                    0 => Err(diesel::result::Error::NotFound),
+                    _ => Ok(moved),
+                }
+            })
+        }
     }
 }
 
-pub mod metric {
+pub mod recipe_ingredient {
     use super::*;
-    use diet_database::metric::*;
+    use diet_database::food::Food;
+    use diet_database::recipe_ingredient::*;
+
+    /// Columns joined from the recipe and food to describe an ingredient
+    type Row = (i32, i32, String, f32, String, Food);
 
-    pub fn insert(conn: &MysqlConnection, metric: NewMetric) -> Result<usize> {
-        diesel::insert_into(schema::metric::table)
-            .values(&metric)
-            .execute(conn)
+    fn from_row(row: Row) -> RecipeIngredient {
+        let (id, recipe_id, recipe_name, amount, unit, food) = row;
+        let ingredient = NewRecipeIngredient {
+            recipe_id,
+            food_id: food.id,
+            amount,
+            unit,
+        };
+        RecipeIngredient::new(id, ingredient, recipe_name, &food)
     }
 
-    pub fn select_all(conn: &MysqlConnection) -> Result<Vec<Metric>> {
-        schema::metric::table.load::<Metric>(conn)
+    /// The ingredients of the given recipes, in the order they were added
+    pub fn of_recipes(conn: &DbConnection, ids: Vec<i32>) -> Result<Vec<RecipeIngredient>> {
+        use schema::recipe_ingredient::dsl::*;
+        use schema::{food, recipe};
+        let rows = recipe_ingredient
+            .inner_join(recipe::table)
+            .inner_join(food::table)
+            .filter(recipe_id.eq_any(ids))
+            .order(id.asc())
+            .select((id, recipe_id, recipe::name, amount, unit, food::all_columns))
+            .load::<Row>(conn)?;
+        Ok(rows.into_iter().map(from_row).collect())
     }
 
-    pub fn delete(conn: &MysqlConnection, del_metric: Metric) -> Result<usize> {
-        use schema::metric::dsl::*;
-        diesel::delete(metric.filter(id.eq(del_metric.id))).execute(conn)
+    impl Resource for RecipeIngredient {
+        type New = NewRecipeIngredient;
+
+        const TABLE: TableType = TableType::RecipeIngredient;
+
+        fn id(&self) -> i32 {
+            self.id
+        }
+
+        fn insert(conn: &DbConnection, item: NewRecipeIngredient) -> Result<RecipeIngredient> {
+            insert_row(conn, schema::recipe_ingredient::table, &item)
+        }
+
+        fn select(conn: &DbConnection, item_id: i32) -> Result<RecipeIngredient> {
+            use schema::recipe_ingredient::dsl::*;
+            use schema::{food, recipe};
+            let row = recipe_ingredient
+                .inner_join(recipe::table)
+                .inner_join(food::table)
+                .filter(id.eq(item_id))
+                .select((id, recipe_id, recipe::name, amount, unit, food::all_columns))
+                .first::<Row>(conn)?;
+            Ok(from_row(row))
+        }
+
+        fn select_all(conn: &DbConnection, query: ListQuery) -> Result<Listing<RecipeIngredient>> {
+            use schema::recipe_ingredient::dsl::*;
+            use schema::{food, recipe};
+            // Ingredients have no date, so only ordering and paging apply
+            let total = recipe_ingredient.count().get_result(conn)?;
+            let joined = recipe_ingredient
+                .inner_join(recipe::table)
+                .inner_join(food::table);
+            let mut q = match query.order {
+                Order::Asc => joined
+                    .order((recipe::name.asc(), id.asc()))
+                    .into_boxed::<DbBackend>(),
+                Order::Desc => joined.order((recipe::name.desc(), id.desc())).into_boxed(),
+            };
+            if let Some((limit, offset)) = query.page() {
+                q = q.limit(limit).offset(offset);
+            }
+            let rows = q
+                .select((id, recipe_id, recipe::name, amount, unit, food::all_columns))
+                .load::<Row>(conn)?;
+            let items = rows.into_iter().map(from_row).collect();
+            Ok(Listing { total, items })
+        }
+
+        fn update(conn: &DbConnection, item_id: i32, item: NewRecipeIngredient) -> Result<usize> {
+            update_row(conn, schema::recipe_ingredient::table, item_id, &item)
+        }
+
+        fn delete(conn: &DbConnection, item_id: i32) -> Result<usize> {
+            delete_row(conn, schema::recipe_ingredient::table, item_id)
+        }
     }
 }
 
-pub mod bowel {
+pub mod food_log {
     use super::*;
-    use diet_database::bowel::*;
+    use diet_database::food::Food;
+    use diet_database::food_log::*;
+    use diet_database::recipe::Recipe;
 
-    pub fn insert(conn: &MysqlConnection, bowel: NewBowel) -> Result<usize> {
-        diesel::insert_into(schema::bowel::table)
-            .values(&bowel)
-            .execute(conn)
+    /// Copies the name and nutrition of the entry's food or recipe, if it
+    /// has one, so the entry still reads the same if that is deleted
+    fn with_catalog(conn: &DbConnection, item: NewFoodLog) -> Result<NewFoodLog> {
+        match (item.food_id, item.recipe_id) {
+            (Some(food_id), _) => Ok(item.with_food(&Food::select(conn, food_id)?)),
+            (None, Some(recipe_id)) => Ok(item.with_recipe(&Recipe::select(conn, recipe_id)?)),
+            (None, None) => Ok(item),
+        }
     }
 
-    pub fn select_all(conn: &MysqlConnection) -> Result<Vec<Bowel>> {
-        use schema::bowel::dsl::*;
-        bowel.order(date.desc()).load(conn)
+    /// Reads the name and nutrition of entries for catalog foods and recipes
+    /// from the catalog, so edits to a food carry over to everything logged
+    /// with it
+    fn from_catalog(conn: &DbConnection, entries: Vec<FoodLog>) -> Result<Vec<FoodLog>> {
+        let food_ids = entries.iter().filter_map(|x| x.food_id).collect();
+        let recipe_ids = entries.iter().filter_map(|x| x.recipe_id).collect();
+        let foods = food::by_id(conn, food_ids)?;
+        let recipes = recipe::by_id(conn, recipe_ids)?;
+        let entries = entries
+            .into_iter()
+            .map(|entry| {
+                let food = entry.food_id.and_then(|x| foods.get(&x));
+                let recipe = entry.recipe_id.and_then(|x| recipes.get(&x));
+                match (food, recipe) {
+                    (Some(food), _) => entry.with_food(food),
+                    (None, Some(recipe)) => entry.with_recipe(recipe),
+                    (None, None) => entry,
+                }
+            })
+            .collect();
+        Ok(entries)
     }
 
-    pub fn delete(conn: &MysqlConnection, del_bowel: Bowel) -> Result<usize> {
-        use schema::bowel::dsl::*;
-        diesel::delete(bowel.filter(id.eq(del_bowel.id))).execute(conn)
+    impl Resource for FoodLog {
+        type New = NewFoodLog;
+
+        const TABLE: TableType = TableType::FoodLog;
+
+        fn id(&self) -> i32 {
+            self.id
+        }
+
+        fn insert(conn: &DbConnection, item: NewFoodLog) -> Result<FoodLog> {
+            let item = with_catalog(conn, item)?;
+            insert_row(conn, schema::food_log::table, &item)
+        }
+
+        fn check_references(conn: &DbConnection, item: &NewFoodLog) -> Result<Vec<FieldError>> {
+            let mut errors = Vec::new();
+            if let Some(food_id) = item.food_id {
+                if food::by_id(conn, vec![food_id])?.is_empty() {
+                    errors.push(FieldError::new("food_id", "is not a food in the catalog"));
+                }
+            }
+            if let Some(recipe_id) = item.recipe_id {
+                if recipe::by_id(conn, vec![recipe_id])?.is_empty() {
+                    errors.push(FieldError::new("recipe_id", "is not a recipe"));
+                }
+            }
+            Ok(errors)
+        }
+
+        fn select(conn: &DbConnection, item_id: i32) -> Result<FoodLog> {
+            let entry = select_row(conn, schema::food_log::table, item_id)?;
+            let mut entries = from_catalog(conn, vec![entry])?;
+            Ok(entries.remove(0))
+        }
+
+        fn select_all(conn: &DbConnection, query: ListQuery) -> Result<Listing<FoodLog>> {
+            use schema::food_log::dsl::*;
+            let (total, q) = dated_page!(conn, query, food_log, (date, time, id));
+            let items = from_catalog(conn, q.load(conn)?)?;
+            Ok(Listing { total, items })
+        }
+
+        fn update(conn: &DbConnection, item_id: i32, item: NewFoodLog) -> Result<usize> {
+            let item = with_catalog(conn, item)?;
+            update_row(conn, schema::food_log::table, item_id, &item)
+        }
+
+        fn delete(conn: &DbConnection, item_id: i32) -> Result<usize> {
+            delete_row(conn, schema::food_log::table, item_id)
+        }
     }
 }
 
-pub mod store {
+pub mod exercise {
     use super::*;
-    use diet_database::store::*;
+    use diet_database::exercise::*;
+    use diet_database::units::DistanceUnit;
+    use diet_database::weight::Weight;
 
-    pub fn insert(conn: &MysqlConnection, store: NewStore) -> Result<usize> {
-        diesel::insert_into(schema::store::table)
-            .values(&store)
-            .execute(conn)
+    type Row = (
+        i32,
+        chrono::NaiveDate,
+        Option<chrono::NaiveTime>,
+        Activity,
+        i32,
+        Intensity,
+        Option<f32>,
+        DistanceUnit,
+    );
+
+    /// Estimates the calories each workout burned from the weights logged
+    fn with_weights(conn: &DbConnection, rows: Vec<Row>) -> Result<Vec<Exercise>> {
+        let weights = Weight::select_all(conn, ListQuery::default())?.items;
+        let exercises = rows
+            .into_iter()
+            .map(|row| {
+                let (id, date, time, activity, duration, intensity, distance, distance_unit) = row;
+                let exercise = NewExercise {
+                    date,
+                    time,
+                    activity,
+                    duration,
+                    intensity,
+                    distance,
+                    distance_unit,
+                };
+                Exercise::new(id, exercise, &weights)
+            })
+            .collect();
+        Ok(exercises)
     }
 
-    pub fn select_all(conn: &MysqlConnection) -> Result<Vec<Store>> {
-        schema::store::table.load::<Store>(conn)
+    impl Resource for Exercise {
+        type New = NewExercise;
+
+        const TABLE: TableType = TableType::Exercise;
+
+        fn id(&self) -> i32 {
+            self.id
+        }
+
+        fn insert(conn: &DbConnection, item: NewExercise) -> Result<Exercise> {
+            insert_row(conn, schema::exercise::table, &item)
+        }
+
+        fn select(conn: &DbConnection, item_id: i32) -> Result<Exercise> {
+            let row = select_row(conn, schema::exercise::table, item_id)?;
+            let mut exercises = with_weights(conn, vec![row])?;
+            Ok(exercises.remove(0))
+        }
+
+        fn select_all(conn: &DbConnection, query: ListQuery) -> Result<Listing<Exercise>> {
+            use schema::exercise::dsl::*;
+            let (total, q) = dated_page!(conn, query, exercise, (date, time, id));
+            let items = with_weights(conn, q.load::<Row>(conn)?)?;
+            Ok(Listing { total, items })
+        }
+
+        fn update(conn: &DbConnection, item_id: i32, item: NewExercise) -> Result<usize> {
+            update_row(conn, schema::exercise::table, item_id, &item)
+        }
+
+        fn delete(conn: &DbConnection, item_id: i32) -> Result<usize> {
+            delete_row(conn, schema::exercise::table, item_id)
+        }
+
+        fn in_units(self, units: UnitSystem) -> Self {
+            Exercise::in_units(self, units)
+        }
     }
+}
+
+pub mod sleep {
+    use super::*;
+    use diet_database::sleep::*;
 
-    pub fn delete(conn: &MysqlConnection, del_store: Store) -> Result<usize> {
-        use schema::store::dsl::*;
-        diesel::delete(store.filter(id.eq(del_store.id))).execute(conn)
+    impl Resource for Sleep {
+        type New = NewSleep;
+
+        const TABLE: TableType = TableType::Sleep;
+
+        fn id(&self) -> i32 {
+            self.id
+        }
+
+        fn insert(conn: &DbConnection, item: NewSleep) -> Result<Sleep> {
+            insert_row(conn, schema::sleep::table, &item)
+        }
+
+        fn select(conn: &DbConnection, item_id: i32) -> Result<Sleep> {
+            select_row(conn, schema::sleep::table, item_id)
+        }
+
+        fn select_all(conn: &DbConnection, query: ListQuery) -> Result<Listing<Sleep>> {
+            use schema::sleep::dsl::*;
+            let (total, q) = dated_page!(conn, query, sleep, (date, bed_time, id));
+            let items = q.load(conn)?;
+            Ok(Listing { total, items })
+        }
+
+        fn update(conn: &DbConnection, item_id: i32, item: NewSleep) -> Result<usize> {
+            update_row(conn, schema::sleep::table, item_id, &item)
+        }
+
+        fn delete(conn: &DbConnection, item_id: i32) -> Result<usize> {
+            delete_row(conn, schema::sleep::table, item_id)
+        }
     }
 }
 
-pub mod grocery_trip {
+pub mod water {
     use super::*;
-    use diet_database::grocery_trip::*;
+    use diet_database::water::*;
+
+    impl Resource for Water {
+        type New = NewWater;
+
+        const TABLE: TableType = TableType::Water;
+
+        fn id(&self) -> i32 {
+            self.id
+        }
+
+        fn insert(conn: &DbConnection, item: NewWater) -> Result<Water> {
+            insert_row(conn, schema::water::table, &item)
+        }
 
-    pub fn insert(conn: &MysqlConnection, trip: NewGroceryTrip) -> Result<usize> {
-        diesel::insert_into(schema::grocery_trip::table)
-            .values(&trip)
-            .execute(conn)
+        fn select(conn: &DbConnection, item_id: i32) -> Result<Water> {
+            select_row(conn, schema::water::table, item_id)
+        }
+
+        fn select_all(conn: &DbConnection, query: ListQuery) -> Result<Listing<Water>> {
+            use schema::water::dsl::*;
+            let (total, q) = dated_page!(conn, query, water, (date, time, id));
+            let items = q.load(conn)?;
+            Ok(Listing { total, items })
+        }
+
+        fn update(conn: &DbConnection, item_id: i32, item: NewWater) -> Result<usize> {
+            update_row(conn, schema::water::table, item_id, &item)
+        }
+
+        fn delete(conn: &DbConnection, item_id: i32) -> Result<usize> {
+            delete_row(conn, schema::water::table, item_id)
+        }
+
+        fn in_units(self, units: UnitSystem) -> Self {
+            Water::in_units(self, units)
+        }
     }
+}
+
+pub mod weight {
+    use super::*;
+    use diet_database::weight::*;
+
+    impl Resource for Weight {
+        type New = NewWeight;
+
+        const TABLE: TableType = TableType::Weight;
+
+        fn id(&self) -> i32 {
+            self.id
+        }
+
+        fn insert(conn: &DbConnection, item: NewWeight) -> Result<Weight> {
+            insert_row(conn, schema::weight::table, &item)
+        }
 
-    pub fn select_all(conn: &MysqlConnection) -> Result<Vec<GroceryTrip>> {
-        use schema::grocery_trip::dsl::*;
-        use schema::store::{self, name};
-        grocery_trip
-            .inner_join(store::table)
-            .select((id, date, time, name))
-            .load(conn)
+        fn select(conn: &DbConnection, item_id: i32) -> Result<Weight> {
+            select_row(conn, schema::weight::table, item_id)
+        }
+
+        fn select_all(conn: &DbConnection, query: ListQuery) -> Result<Listing<Weight>> {
+            use schema::weight::dsl::*;
+            let (total, q) = dated_page!(conn, query, weight, (date, time, id));
+            let items = q.load(conn)?;
+            Ok(Listing { total, items })
+        }
+
+        fn update(conn: &DbConnection, item_id: i32, item: NewWeight) -> Result<usize> {
+            update_row(conn, schema::weight::table, item_id, &item)
+        }
+
+        fn delete(conn: &DbConnection, item_id: i32) -> Result<usize> {
+            delete_row(conn, schema::weight::table, item_id)
+        }
+
+        fn in_units(self, units: UnitSystem) -> Self {
+            Weight::in_units(self, units)
+        }
     }
+}
+
+pub mod metric {
+    use super::*;
+    use diet_database::metric::*;
+
+    impl Resource for Metric {
+        type New = NewMetric;
+
+        const TABLE: TableType = TableType::Metric;
+
+        fn id(&self) -> i32 {
+            self.id
+        }
+
+        fn insert(conn: &DbConnection, item: NewMetric) -> Result<Metric> {
+            insert_row(conn, schema::metric::table, &item)
+        }
+
+        fn select(conn: &DbConnection, item_id: i32) -> Result<Metric> {
+            select_row(conn, schema::metric::table, item_id)
+        }
+
+        fn select_all(conn: &DbConnection, query: ListQuery) -> Result<Listing<Metric>> {
+            use schema::metric::dsl::*;
+            let (total, q) = dated_page!(conn, query, metric, (date, time, id));
+            let items = q.load(conn)?;
+            Ok(Listing { total, items })
+        }
+
+        fn update(conn: &DbConnection, item_id: i32, item: NewMetric) -> Result<usize> {
+            update_row(conn, schema::metric::table, item_id, &item)
+        }
+
+        fn delete(conn: &DbConnection, item_id: i32) -> Result<usize> {
+            delete_row(conn, schema::metric::table, item_id)
+        }
+
+        fn in_units(self, units: UnitSystem) -> Self {
+            Metric::in_units(self, units)
+        }
+    }
+}
+
+pub mod bowel {
+    use super::*;
+    use diet_database::bowel::*;
+
+    impl Resource for Bowel {
+        type New = NewBowel;
+
+        const TABLE: TableType = TableType::Bowel;
+
+        fn id(&self) -> i32 {
+            self.id
+        }
+
+        fn insert(conn: &DbConnection, item: NewBowel) -> Result<Bowel> {
+            insert_row(conn, schema::bowel::table, item)
+        }
+
+        fn select(conn: &DbConnection, item_id: i32) -> Result<Bowel> {
+            select_row(conn, schema::bowel::table, item_id)
+        }
+
+        fn select_all(conn: &DbConnection, query: ListQuery) -> Result<Listing<Bowel>> {
+            use schema::bowel::dsl::*;
+            let (total, q) = dated_page!(conn, query, bowel, (date, time, id));
+            let items = q.load(conn)?;
+            Ok(Listing { total, items })
+        }
+
+        fn update(conn: &DbConnection, item_id: i32, item: NewBowel) -> Result<usize> {
+            update_row(conn, schema::bowel::table, item_id, item)
+        }
+
+        fn delete(conn: &DbConnection, item_id: i32) -> Result<usize> {
+            delete_row(conn, schema::bowel::table, item_id)
+        }
+    }
+}
+
+pub mod store {
+    use super::*;
+    use diet_database::store::*;
+
+    impl Resource for Store {
+        type New = NewStore;
+
+        const TABLE: TableType = TableType::Store;
+
+        fn id(&self) -> i32 {
+            self.id
+        }
+
+        fn insert(conn: &DbConnection, item: NewStore) -> Result<Store> {
+            insert_row(conn, schema::store::table, &item)
+        }
+
+        fn select(conn: &DbConnection, item_id: i32) -> Result<Store> {
+            select_row(conn, schema::store::table, item_id)
+        }
+
+        fn select_all(conn: &DbConnection, query: ListQuery) -> Result<Listing<Store>> {
+            use schema::store::dsl::*;
+            // Stores have no date, so only ordering and paging apply
+            let total = store.count().get_result(conn)?;
+            let mut q = match query.order {
+                Order::Asc => store.order(name.asc()).into_boxed::<DbBackend>(),
+                Order::Desc => store.order(name.desc()).into_boxed(),
+            };
+            if let Some((limit, offset)) = query.page() {
+                q = q.limit(limit).offset(offset);
+            }
+            let items = q.load(conn)?;
+            Ok(Listing { total, items })
+        }
+
+        fn update(conn: &DbConnection, item_id: i32, item: NewStore) -> Result<usize> {
+            update_row(conn, schema::store::table, item_id, &item)
+        }
+
+        fn delete(conn: &DbConnection, item_id: i32) -> Result<usize> {
+            delete_row(conn, schema::store::table, item_id)
+        }
+
+        fn dependents(conn: &DbConnection, item_id: i32) -> Result<Dependents> {
+            use schema::grocery_item;
+            use schema::grocery_trip::{self, store_id};
+            let grocery_trips = grocery_trip::table
+                .filter(store_id.eq(item_id))
+                .count()
+                .get_result(conn)?;
+            let grocery_items = grocery_item::table
+                .inner_join(grocery_trip::table)
+                .filter(store_id.eq(item_id))
+                .count()
+                .get_result(conn)?;
+            Ok(Dependents {
+                grocery_trips,
+                grocery_items,
+                ..Default::default()
+            })
+        }
+
+        /// Deletes the store along with its trips and their items
+        fn delete_cascade(conn: &DbConnection, item_id: i32) -> Result<Dependents> {
+            use schema::grocery_item::{self, trip_id};
+            use schema::grocery_trip::{self, store_id};
+            conn.transaction(|| {
+                let removed = Self::dependents(conn, item_id)?;
+                let trips = grocery_trip::table
+                    .filter(store_id.eq(item_id))
+                    .select(grocery_trip::id);
+                diesel::delete(grocery_item::table.filter(trip_id.eq_any(trips))).execute(conn)?;
+                diesel::delete(grocery_trip::table.filter(store_id.eq(item_id))).execute(conn)?;
+                match Self::delete(conn, item_id)? {
+                    0 => Err(diesel::result::Error::NotFound),
+                    _ => Ok(removed),
+                }
+            })
+        }
+
+        /// Moves the store's trips to another store, then deletes it
+        fn delete_reassign(
+            conn: &DbConnection,
+            item_id: i32,
+            new_store_id: i32,
+        ) -> Result<Dependents> {
+            use schema::grocery_trip::{self, store_id};
+            conn.transaction(|| {
+                let moved = Self::dependents(conn, item_id)?;
+                diesel::update(grocery_trip::table.filter(store_id.eq(item_id)))
+                    .set(store_id.eq(new_store_id))
+                    .execute(conn)?;
+                match Self::delete(conn, item_id)? {
+                    0 => Err(diesel::result::Error::NotFound),
+                    _ => Ok(moved),
+                }
+            })
+        }
+    }
+}
+
+pub mod grocery_trip {
+    use super::*;
+    use diet_database::grocery_trip::*;
+
+    impl Resource for GroceryTrip {
+        type New = NewGroceryTrip;
+
+        const TABLE: TableType = TableType::GroceryTrip;
+
+        fn id(&self) -> i32 {
+            self.id
+        }
+
+        fn insert(conn: &DbConnection, item: NewGroceryTrip) -> Result<GroceryTrip> {
+            insert_row(conn, schema::grocery_trip::table, &item)
+        }
+
+        fn select(conn: &DbConnection, item_id: i32) -> Result<GroceryTrip> {
+            use schema::grocery_trip::dsl::*;
+            use schema::store::{self, name};
+            grocery_trip
+                .inner_join(store::table)
+                .filter(id.eq(item_id))
+                .select((id, date, time, store_id, name))
+                .first(conn)
+        }
+
+        fn select_all(conn: &DbConnection, query: ListQuery) -> Result<Listing<GroceryTrip>> {
+            use schema::grocery_trip::dsl::*;
+            use schema::store::{self, name};
+            let (total, q) = dated_page!(
+                conn,
+                query,
+                grocery_trip.inner_join(store::table),
+                (date, time, id)
+            );
+            let items = q.select((id, date, time, store_id, name)).load(conn)?;
+            Ok(Listing { total, items })
+        }
+
+        fn update(conn: &DbConnection, item_id: i32, item: NewGroceryTrip) -> Result<usize> {
+            update_row(conn, schema::grocery_trip::table, item_id, &item)
+        }
+
+        fn delete(conn: &DbConnection, item_id: i32) -> Result<usize> {
+            delete_row(conn, schema::grocery_trip::table, item_id)
+        }
+
+        fn dependents(conn: &DbConnection, item_id: i32) -> Result<Dependents> {
+            use schema::grocery_item::{self, trip_id};
+            let grocery_items = grocery_item::table
+                .filter(trip_id.eq(item_id))
+                .count()
+                .get_result(conn)?;
+            Ok(Dependents {
+                grocery_items,
+                ..Default::default()
+            })
+        }
+
+        /// Deletes the trip along with its items
+        fn delete_cascade(conn: &DbConnection, item_id: i32) -> Result<Dependents> {
+            use schema::grocery_item::{self, trip_id};
+            conn.transaction(|| {
+                let removed = Self::dependents(conn, item_id)?;
+                diesel::delete(grocery_item::table.filter(trip_id.eq(item_id))).execute(conn)?;
+                match Self::delete(conn, item_id)? {
+                    0 => Err(diesel::result::Error::NotFound),
+                    _ => Ok(removed),
+                }
+            })
+        }
 
-    pub fn delete(conn: &MysqlConnection, del_trip: GroceryTrip) -> Result<usize> {
-        use schema::grocery_trip::dsl::*;
-        diesel::delete(grocery_trip.filter(id.eq(del_trip.id))).execute(conn)
+        /// Moves the trip's items to another trip, then deletes it
+        fn delete_reassign(
+            conn: &DbConnection,
+            item_id: i32,
+            new_trip_id: i32,
+        ) -> Result<Dependents> {
+            use schema::grocery_item::{self, trip_id};
+            conn.transaction(|| {
+                let moved = Self::dependents(conn, item_id)?;
+                diesel::update(grocery_item::table.filter(trip_id.eq(item_id)))
+                    .set(trip_id.eq(new_trip_id))
+                    .execute(conn)?;
+                match Self::delete(conn, item_id)? {
+                    0 => Err(diesel::result::Error::NotFound),
+                    _ => Ok(moved),
+                }
+            })
+        }
     }
 }
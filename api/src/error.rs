@@ -0,0 +1,75 @@
+use diesel::result::{DatabaseErrorKind, Error as DieselError};
+use diet_database::api_error::{ApiError, ErrorCode};
+use diet_database::validate::FieldError;
+use rocket::http::Status;
+use rocket::request::Request;
+use rocket::response::{self, Responder, Response};
+use rocket_contrib::json::Json;
+
+/// An `ApiError` paired with the status it is sent with
+#[derive(Debug)]
+pub struct Error {
+    pub status: Status,
+    pub body: ApiError,
+}
+
+impl Error {
+    pub fn new(status: Status, code: ErrorCode, message: &str) -> Self {
+        Error {
+            status,
+            body: ApiError::new(code, message),
+        }
+    }
+
+    pub fn bad_request(message: &str) -> Self {
+        Error::new(Status::BadRequest, ErrorCode::BadRequest, message)
+    }
+
+    pub fn not_found(message: &str) -> Self {
+        Error::new(Status::NotFound, ErrorCode::NotFound, message)
+    }
+
+    /// 422 Unprocessable Entity, listing the rules the body broke
+    pub fn invalid(fields: Vec<FieldError>) -> Self {
+        Error {
+            status: Status::UnprocessableEntity,
+            body: ApiError::invalid(fields),
+        }
+    }
+}
+
+impl From<DieselError> for Error {
+    fn from(err: DieselError) -> Self {
+        match err {
+            DieselError::NotFound => Error::not_found("No such row"),
+            DieselError::DatabaseError(DatabaseErrorKind::UniqueViolation, _) => Error::new(
+                Status::Conflict,
+                ErrorCode::UniqueViolation,
+                "A row with that value already exists",
+            ),
+            DieselError::DatabaseError(DatabaseErrorKind::ForeignKeyViolation, _) => Error::new(
+                Status::Conflict,
+                ErrorCode::ForeignKeyViolation,
+                "The row is referenced by other rows, or refers to a row that does not exist",
+            ),
+            // The driver's message can quote SQL and constraint names, so it
+            // stays in the server's log
+            err => {
+                eprintln!("Database error: {}", err);
+                Error::new(
+                    Status::InternalServerError,
+                    ErrorCode::Internal,
+                    "Internal database error",
+                )
+            }
+        }
+    }
+}
+
+impl<'r> Responder<'r> for Error {
+    fn respond_to(self, req: &Request) -> response::Result<'r> {
+        Response::build_from(Json(self.body).respond_to(req)?)
+            .status(self.status)
+            .ok()
+    }
+}
@@ -1 +1,13 @@
+#[macro_use]
+extern crate diesel_migrations;
+#[macro_use]
+extern crate rocket;
+#[macro_use]
+extern crate rocket_contrib;
+
+pub mod analytics;
 pub mod db;
+pub mod error;
+pub mod migrations;
+pub mod pool;
+pub mod resource;
@@ -0,0 +1,30 @@
+use crate::db::DbConnection;
+use diesel_migrations::{MigrationConnection, RunMigrationsError};
+use diet_database::schema_status::SchemaStatus;
+
+include!(concat!(env!("OUT_DIR"), "/migrations.rs"));
+
+#[cfg(feature = "mysql")]
+embed_migrations!("../migrations");
+
+#[cfg(feature = "sqlite")]
+embed_migrations!("../migrations_sqlite");
+
+/// Applies every embedded migration the database has not seen yet
+pub fn run(conn: &DbConnection) -> Result<(), RunMigrationsError> {
+    embedded_migrations::run_with_output(conn, &mut std::io::stdout())
+}
+
+pub fn status(conn: &DbConnection) -> Result<SchemaStatus, RunMigrationsError> {
+    diesel_migrations::setup_database(conn)?;
+    let applied = conn.previously_run_migration_versions()?;
+    let pending = MIGRATIONS
+        .iter()
+        .filter(|(version, _)| !applied.contains(*version))
+        .map(|(_, name)| name.to_string())
+        .collect();
+    Ok(SchemaStatus {
+        version: conn.latest_run_migration_version()?,
+        pending,
+    })
+}
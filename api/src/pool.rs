@@ -0,0 +1,41 @@
+use rocket::request::{self, FromRequest, Request};
+use rocket::Outcome;
+use std::ops::Deref;
+
+use crate::db::DbConnection;
+
+/// Connection pool for the `diet` database declared in Rocket.toml
+#[database("diet")]
+pub struct DietPool(DbConnection);
+
+/// A pooled connection, ready to use.  Requests fail with 503 Service
+/// Unavailable when no connection can be made to the database.
+pub struct Db(DietPool);
+
+impl<'a, 'r> FromRequest<'a, 'r> for Db {
+    type Error = ();
+
+    fn from_request(request: &'a Request<'r>) -> request::Outcome<Self, ()> {
+        let pool = request.guard::<DietPool>()?;
+
+        // SQLite leaves foreign keys unchecked unless asked per connection
+        #[cfg(feature = "sqlite")]
+        {
+            use diesel::connection::SimpleConnection;
+            if pool.batch_execute("PRAGMA foreign_keys = ON").is_err() {
+                let status = rocket::http::Status::ServiceUnavailable;
+                return Outcome::Failure((status, ()));
+            }
+        }
+
+        Outcome::Success(Db(pool))
+    }
+}
+
+impl Deref for Db {
+    type Target = DbConnection;
+
+    fn deref(&self) -> &DbConnection {
+        &self.0
+    }
+}
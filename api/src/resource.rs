@@ -0,0 +1,440 @@
+use rocket::data::Data;
+use rocket::handler::{Handler, Outcome};
+use rocket::http::{ContentType, Method, Status};
+use rocket::request::{FormItems, FromForm, FromFormValue, Request};
+use rocket::response::content::Content;
+use rocket::response::status::Created;
+use rocket::{Rocket, Route};
+use rocket_contrib::json::Json;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::{Map, Value};
+use std::io::Read;
+use std::marker::PhantomData;
+
+use crate::db::{self, Resource, TableVisitor};
+use crate::error::Error;
+use crate::pool::Db;
+use diet_database::api_error::ErrorCode;
+use diet_database::dependents::Dependents;
+use diet_database::export::Format;
+use diet_database::import::ImportReport;
+use diet_database::listing::{ListQuery, Listing};
+use diet_database::units::UnitSystem;
+use diet_database::validate::Validate;
+use diet_database::{TableInfo, TableType, Tabular};
+
+/// Largest JSON body read when Rocket.toml sets no `limits.json`, the same
+/// default as `Json`
+const LIMIT: u64 = 1 << 20;
+
+/// Largest CSV upload read when Rocket.toml sets no `limits.csv`
+const CSV_LIMIT: u64 = 8 << 20;
+
+/// Query string accepted by every list route, e.g.
+/// `?from=2021-01-01&to=2021-01-31&order=asc&limit=20&offset=40`
+#[derive(FromForm)]
+pub struct ListParams {
+    from: Option<String>,
+    to: Option<String>,
+    order: Option<String>,
+    limit: Option<i64>,
+    offset: Option<i64>,
+}
+
+impl ListParams {
+    fn into_query(self) -> Result<ListQuery, Error> {
+        let date = |s: Option<String>| match s {
+            None => Ok(None),
+            Some(s) => s
+                .parse()
+                .map(Some)
+                .map_err(|_| Error::bad_request(&format!("{} is not a date", s))),
+        };
+        Ok(ListQuery {
+            from: date(self.from)?,
+            to: date(self.to)?,
+            order: match self.order {
+                None => Default::default(),
+                Some(s) => s.parse().map_err(|e: String| Error::bad_request(&e))?,
+            },
+            limit: self.limit,
+            offset: self.offset,
+        })
+    }
+}
+
+/// Mounts every route of a `Resource` under its table name, and its exports
+/// next to it
+pub trait MountResource {
+    fn mount_resource<R: Resource + 'static>(self) -> Self
+    where
+        Vec<R>: Tabular;
+
+    /// Mounts the `Resource` of every `TableType`, and `GET /tables`
+    fn mount_tables(self) -> Self;
+}
+
+impl MountResource for Rocket {
+    fn mount_resource<R: Resource + 'static>(self) -> Self
+    where
+        Vec<R>: Tabular,
+    {
+        self.mount(&R::TABLE.path(), routes::<R>())
+            .mount("/", export_routes::<R>())
+    }
+
+    fn mount_tables(self) -> Self {
+        let rocket = self.mount("/", routes![tables]);
+        TableType::ALL
+            .iter()
+            .fold(rocket, |rocket, &table| db::visit(table, Mount(rocket)))
+    }
+}
+
+/// Mounts the visited `Resource`
+struct Mount(Rocket);
+
+impl TableVisitor for Mount {
+    type Output = Rocket;
+
+    fn visit<R: Resource + 'static>(self) -> Rocket
+    where
+        Vec<R>: Tabular,
+    {
+        self.0.mount_resource::<R>()
+    }
+}
+
+/// The name, path and columns of every table
+#[get("/tables")]
+fn tables() -> Json<Vec<TableInfo>> {
+    Json(TableType::ALL.iter().map(|x| x.info()).collect())
+}
+
+/// The routes for `R`, relative to where they are mounted:
+///
+/// Rows are sent with their measurements as entered, unless converted with
+/// `?units=metric` or `?units=imperial`.
+///
+/// - `GET /` lists rows, see `ListParams`
+/// - `POST /` adds a row and responds with it
+/// - `GET /<id>` gets a row
+/// - `PUT /<id>` replaces a row
+/// - `PATCH /<id>` changes some of a row's fields, see `patch`
+/// - `DELETE /<id>?<cascade>&<reassign_to>` deletes a row, see `delete`
+/// - `GET /<id>/dependents` counts the rows referring to a row
+/// - `POST /import?<dry_run>` adds the rows of a CSV body, see `import`
+pub fn routes<R: Resource + 'static>() -> Vec<Route>
+where
+    Vec<R>: Tabular,
+{
+    let route = |method, path, action| handler_route::<R>(method, path, action);
+    vec![
+        route(Method::Get, "/", Action::List),
+        route(Method::Post, "/", Action::Add),
+        route(Method::Get, "/<id>", Action::Get),
+        route(Method::Put, "/<id>", Action::Update),
+        route(Method::Patch, "/<id>", Action::Patch),
+        route(Method::Delete, "/<id>", Action::Delete),
+        route(Method::Get, "/<id>/dependents", Action::Dependents),
+        route(Method::Post, "/import", Action::Import),
+    ]
+}
+
+/// `GET /<table>.<extension>` for every export `Format`, relative to the
+/// root.  They take the same query string as the list route, and convert
+/// measurements to `?units=` or else to metric, so that each column has a
+/// single unit.
+pub fn export_routes<R: Resource + 'static>() -> Vec<Route>
+where
+    Vec<R>: Tabular,
+{
+    Format::ALL
+        .iter()
+        .map(|&format| {
+            let path = format!("{}.{}", R::TABLE.path(), format.extension());
+            handler_route::<R>(Method::Get, &path, Action::Export(format))
+        })
+        .collect()
+}
+
+fn handler_route<R: Resource + 'static>(method: Method, path: &str, action: Action) -> Route
+where
+    Vec<R>: Tabular,
+{
+    let handler = ResourceHandler::<R> {
+        action,
+        resource: PhantomData,
+    };
+    Route::new(method, path, handler)
+}
+
+#[derive(Clone, Copy)]
+enum Action {
+    List,
+    Add,
+    Get,
+    Update,
+    Patch,
+    Delete,
+    Dependents,
+    Export(Format),
+    Import,
+}
+
+/// Serves one `Action` for the resource `R`.  Rocket's route attributes
+/// cannot be generic, so this stands in for them.
+pub struct ResourceHandler<R> {
+    action: Action,
+    resource: PhantomData<fn() -> R>,
+}
+
+impl<R> Clone for ResourceHandler<R> {
+    fn clone(&self) -> Self {
+        ResourceHandler {
+            action: self.action,
+            resource: PhantomData,
+        }
+    }
+}
+
+impl<R: Resource + 'static> Handler for ResourceHandler<R>
+where
+    Vec<R>: Tabular,
+{
+    fn handle<'r>(&self, req: &'r Request, data: Data) -> Outcome<'r> {
+        let conn = match req.guard::<Db>() {
+            rocket::Outcome::Success(conn) => conn,
+            rocket::Outcome::Failure((status, ())) => return Outcome::failure(status),
+            rocket::Outcome::Forward(()) => return Outcome::forward(data),
+        };
+        match self.action {
+            Action::List => Outcome::from(req, list::<R>(req, &conn)),
+            Action::Add => Outcome::from(req, add::<R>(req, &conn, data)),
+            Action::Get => Outcome::from(req, get::<R>(req, &conn)),
+            Action::Update => Outcome::from(req, update::<R>(req, &conn, data)),
+            Action::Patch => Outcome::from(req, patch::<R>(req, &conn, data)),
+            Action::Delete => Outcome::from(req, delete::<R>(req, &conn)),
+            Action::Dependents => Outcome::from(req, dependents::<R>(req, &conn)),
+            Action::Export(format) => Outcome::from(req, export::<R>(req, &conn, format)),
+            Action::Import => Outcome::from(req, import::<R>(req, &conn, data)),
+        }
+    }
+}
+
+fn list<R: Resource>(req: &Request, conn: &Db) -> Result<Json<Listing<R>>, Error> {
+    let query = list_query(req)?;
+    let units = units(req)?;
+    let mut listing = R::select_all(conn, query)?;
+    listing.items = listing
+        .items
+        .into_iter()
+        .map(|x| in_units(x, units))
+        .collect();
+    Ok(Json(listing))
+}
+
+fn export<R: Resource>(req: &Request, conn: &Db, format: Format) -> Result<Content<String>, Error>
+where
+    Vec<R>: Tabular,
+{
+    let query = list_query(req)?;
+    let units = units(req)?.unwrap_or_default();
+    let body = db::export::<R>(conn, query, units, format)?;
+    let content_type =
+        ContentType::parse_flexible(format.content_type()).unwrap_or(ContentType::Plain);
+    Ok(Content(content_type, body))
+}
+
+/// Responds with what became of each line, even when some could not be
+/// added.  `dry_run=true` only checks the file.
+fn import<R: Resource>(req: &Request, conn: &Db, data: Data) -> Result<Json<ImportReport>, Error> {
+    let dry_run = query_value(req, "dry_run")?.unwrap_or(false);
+    let text = read_body(req, data, "csv", CSV_LIMIT)?;
+    Ok(Json(db::import::<R>(conn, &text, dry_run)?))
+}
+
+fn add<R: Resource>(req: &Request, conn: &Db, data: Data) -> Result<Created<Json<R>>, Error> {
+    let item: R::New = body(req, data)?;
+    check::<R>(conn, &item)?;
+    let created = in_units(R::insert(conn, item)?, units(req)?);
+    Ok(Created(
+        format!("{}/{}", R::TABLE.path(), created.id()),
+        Some(Json(created)),
+    ))
+}
+
+fn get<R: Resource>(req: &Request, conn: &Db) -> Result<Json<R>, Error> {
+    let item = R::select(conn, id(req)?)?;
+    Ok(Json(in_units(item, units(req)?)))
+}
+
+fn update<R: Resource>(req: &Request, conn: &Db, data: Data) -> Result<Status, Error> {
+    let id = id(req)?;
+    let item: R::New = body(req, data)?;
+    check::<R>(conn, &item)?;
+    updated::<R>(conn, id, item)
+}
+
+/// The body is a JSON object with only the fields to change, e.g.
+/// `{"time": null}`.  The other fields keep their stored values, and the
+/// row as a whole is validated as for `PUT`.
+fn patch<R: Resource>(req: &Request, conn: &Db, data: Data) -> Result<Status, Error> {
+    let id = id(req)?;
+    let changes: Map<String, Value> = body(req, data)?;
+    let current = R::New::from(R::select(conn, id)?);
+    let item: R::New = merge(current, changes)?;
+    check::<R>(conn, &item)?;
+    updated::<R>(conn, id, item)
+}
+
+/// Checks `item` against its own rules, then against the rows it refers to
+fn check<R: Resource>(conn: &Db, item: &R::New) -> Result<(), Error> {
+    item.validate().map_err(Error::invalid)?;
+    let errors = R::check_references(conn, item)?;
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(Error::invalid(errors))
+    }
+}
+
+/// `item` with the fields in `changes` replaced
+fn merge<N: Serialize + DeserializeOwned>(
+    item: N,
+    changes: Map<String, Value>,
+) -> Result<N, Error> {
+    let invalid = |message: &str| {
+        Error::new(
+            Status::UnprocessableEntity,
+            ErrorCode::InvalidInput,
+            message,
+        )
+    };
+    let mut fields = match serde_json::to_value(item) {
+        Ok(Value::Object(fields)) => fields,
+        _ => return Err(invalid("The row cannot be changed field by field")),
+    };
+    for (key, value) in changes {
+        if !fields.contains_key(&key) {
+            return Err(invalid(&format!("Unknown field {}", key)));
+        }
+        fields.insert(key, value);
+    }
+    serde_json::from_value(Value::Object(fields)).map_err(|e| invalid(&e.to_string()))
+}
+
+/// Writes `item` over the row, or responds 404 if there is none.  MySQL
+/// counts the rows an update changed rather than matched, so when nothing
+/// changed the row is looked up to tell whether it exists.
+fn updated<R: Resource>(conn: &Db, id: i32, item: R::New) -> Result<Status, Error> {
+    if R::update(conn, id, item)? == 0 {
+        R::select(conn, id)?;
+    }
+    Ok(Status::Ok)
+}
+
+/// Without options this refuses to delete a row that is still referred to.
+/// `cascade=true` deletes the rows referring to it too, and
+/// `reassign_to=<id>` moves them to another row first.  Responds with the
+/// rows that were deleted or moved.
+fn delete<R: Resource>(req: &Request, conn: &Db) -> Result<Json<Dependents>, Error> {
+    let id = id(req)?;
+    let cascade = query_value(req, "cascade")?.unwrap_or(false);
+    let reassign_to = query_value(req, "reassign_to")?;
+    match (cascade, reassign_to) {
+        (true, Some(_)) => Err(Error::bad_request("Cannot both cascade and reassign")),
+        (_, Some(other)) if other == id => Err(Error::bad_request(
+            "Cannot reassign to the row being deleted",
+        )),
+        (true, None) => Ok(Json(R::delete_cascade(conn, id)?)),
+        (false, Some(other)) => Ok(Json(R::delete_reassign(conn, id, other)?)),
+        (false, None) => match R::delete(conn, id)? {
+            0 => Err(Error::not_found("No such row")),
+            _ => Ok(Json(Dependents::default())),
+        },
+    }
+}
+
+fn dependents<R: Resource>(req: &Request, conn: &Db) -> Result<Json<Dependents>, Error> {
+    let id = id(req)?;
+    R::select(conn, id)?;
+    Ok(Json(R::dependents(conn, id)?))
+}
+
+/// The `ListParams` in the query string
+fn list_query(req: &Request) -> Result<ListQuery, Error> {
+    let mut items = FormItems::from(req.uri().query().unwrap_or(""));
+    let params = ListParams::from_form(&mut items, false)
+        .map_err(|_| Error::bad_request("The query string could not be understood"))?;
+    params.into_query()
+}
+
+/// The `<id>` segment, which only fails to parse for a route that does not
+/// exist
+fn id(req: &Request) -> Result<i32, Error> {
+    match req.get_param(0) {
+        Some(Ok(id)) => Ok(id),
+        _ => Err(Error::not_found("No such route")),
+    }
+}
+
+/// The `units` values are converted to, or `None` to leave them as entered
+fn units(req: &Request) -> Result<Option<UnitSystem>, Error> {
+    match query_value::<String>(req, "units")? {
+        None => Ok(None),
+        Some(s) => s
+            .parse()
+            .map(Some)
+            .map_err(|e: String| Error::bad_request(&e)),
+    }
+}
+
+fn in_units<R: Resource>(item: R, units: Option<UnitSystem>) -> R {
+    match units {
+        Some(units) => item.in_units(units),
+        None => item,
+    }
+}
+
+fn query_value<'r, T: FromFormValue<'r>>(req: &'r Request, key: &str) -> Result<Option<T>, Error> {
+    req.get_query_value(key)
+        .transpose()
+        .map_err(|_| Error::bad_request(&format!("Invalid value for {}", key)))
+}
+
+/// Reads a JSON body with the same limit and statuses as `Json`
+fn body<T: DeserializeOwned>(req: &Request, data: Data) -> Result<T, Error> {
+    let body = read_body(req, data, "json", LIMIT)?;
+    serde_json::from_str(&body).map_err(|e| {
+        if e.is_data() {
+            Error::new(
+                Status::UnprocessableEntity,
+                ErrorCode::InvalidInput,
+                &e.to_string(),
+            )
+        } else {
+            Error::bad_request(&e.to_string())
+        }
+    })
+}
+
+/// Reads the body as text, up to the limit Rocket.toml sets for `kind`.
+/// Longer bodies are refused whole rather than cut off.
+fn read_body(req: &Request, data: Data, kind: &str, default_limit: u64) -> Result<String, Error> {
+    let limit = req.limits().get(kind).unwrap_or(default_limit);
+    let mut body = Vec::new();
+    data.open()
+        .take(limit + 1)
+        .read_to_end(&mut body)
+        .map_err(|_| Error::bad_request("The body could not be read"))?;
+    if body.len() as u64 > limit {
+        return Err(Error::new(
+            Status::PayloadTooLarge,
+            ErrorCode::PayloadTooLarge,
+            &format!("The body is over the limit of {} bytes", limit),
+        ));
+    }
+    String::from_utf8(body).map_err(|_| Error::bad_request("The body is not UTF-8 text"))
+}
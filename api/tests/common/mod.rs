@@ -0,0 +1,32 @@
+use rocket::config::{Config, Environment, Limits, Value};
+use rocket::local::Client;
+use std::collections::HashMap;
+
+use api::pool::DietPool;
+use api::resource::MountResource;
+
+/// A client for the api on a new SQLite database called `name`, with
+/// `limits` in place of Rocket's own.  The pool is returned too, so tests
+/// can look at the rows directly.
+pub fn client(name: &str, limits: Limits) -> (Client, DietPool) {
+    let path = std::env::temp_dir().join(format!("diet_test_{}.sqlite", name));
+    let _ = std::fs::remove_file(&path);
+
+    let mut database = HashMap::new();
+    database.insert("url", Value::from(path.to_string_lossy().to_string()));
+    let mut databases = HashMap::new();
+    databases.insert("diet", Value::from(database));
+    let config = Config::build(Environment::Development)
+        .extra("databases", databases)
+        .limits(limits)
+        .finalize()
+        .expect("the test config is valid");
+
+    let rocket = rocket::custom(config)
+        .attach(DietPool::fairing())
+        .mount_tables();
+    let conn = DietPool::get_one(&rocket).expect("the test database opens");
+    api::migrations::run(&conn).expect("the migrations run");
+    let client = Client::new(rocket).expect("the api starts");
+    (client, conn)
+}
@@ -0,0 +1,29 @@
+#![cfg(feature = "sqlite")]
+
+mod common;
+
+use rocket::config::Limits;
+use rocket::http::{ContentType, Status};
+
+use api::db::Resource;
+use diet_database::api_error::{ApiError, ErrorCode};
+use diet_database::food_log::FoodLog;
+use diet_database::listing::ListQuery;
+
+#[test]
+fn refuses_an_entry_for_a_food_that_does_not_exist() {
+    let (client, conn) = common::client("food_log_missing_food", Limits::new());
+    let mut response = client
+        .post("/food_log")
+        .header(ContentType::JSON)
+        .body(r#"{"date": "2021-01-31", "meal": "lunch", "servings": 1, "food_id": 999}"#)
+        .dispatch();
+    assert_eq!(response.status(), Status::UnprocessableEntity);
+    let error: ApiError = serde_json::from_str(&response.body_string().unwrap()).unwrap();
+    assert_eq!(error.code, ErrorCode::InvalidInput);
+    assert_eq!(error.fields.len(), 1);
+    assert_eq!(error.fields[0].field, "food_id");
+
+    let entries = FoodLog::select_all(&conn, ListQuery::default()).unwrap();
+    assert!(entries.items.is_empty());
+}
@@ -0,0 +1,48 @@
+#![cfg(feature = "sqlite")]
+
+mod common;
+
+use rocket::config::Limits;
+use rocket::http::{ContentType, Status};
+
+use api::db::Resource;
+use diet_database::api_error::{ApiError, ErrorCode};
+use diet_database::listing::ListQuery;
+use diet_database::weight::Weight;
+
+#[test]
+fn refuses_a_csv_over_the_limit() {
+    let (client, conn) = common::client("import_limit", Limits::new().limit("csv", 64));
+    let mut csv = String::from("date,value,unit\n");
+    for day in 1..=9 {
+        csv.push_str(&format!("2021-01-0{},72.5,kg\n", day));
+    }
+    assert!(csv.len() > 64);
+
+    let mut response = client
+        .post("/weight/import")
+        .header(ContentType::CSV)
+        .body(csv)
+        .dispatch();
+    assert_eq!(response.status(), Status::PayloadTooLarge);
+    let error: ApiError = serde_json::from_str(&response.body_string().unwrap()).unwrap();
+    assert_eq!(error.code, ErrorCode::PayloadTooLarge);
+
+    let weights = Weight::select_all(&conn, ListQuery::default()).unwrap();
+    assert!(weights.items.is_empty());
+}
+
+#[test]
+fn imports_a_csv_within_the_limit() {
+    let (client, conn) = common::client("import_within", Limits::new().limit("csv", 64));
+    let response = client
+        .post("/weight/import")
+        .header(ContentType::CSV)
+        .body("date,value,unit\n2021-01-01,72.5,kg\n")
+        .dispatch();
+    assert_eq!(response.status(), Status::Ok);
+
+    let weights = Weight::select_all(&conn, ListQuery::default()).unwrap();
+    assert_eq!(weights.items.len(), 1);
+    assert_eq!(weights.items[0].value, 72.5);
+}
@@ -0,0 +1,3 @@
+-- This file should undo anything in `up.sql`
+ALTER TABLE weight DROP COLUMN unit;
+ALTER TABLE metric DROP COLUMN length_unit;
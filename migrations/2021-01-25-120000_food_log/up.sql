@@ -0,0 +1,14 @@
+-- Nutrition is per serving, so an entry's totals scale with its servings
+CREATE TABLE food_log(
+    id INT AUTO_INCREMENT PRIMARY KEY,
+    date DATE NOT NULL,
+    time TIME,
+    meal VARCHAR(9) NOT NULL,
+    name VARCHAR(100) NOT NULL,
+    servings FLOAT NOT NULL,
+    calories FLOAT NOT NULL,
+    protein FLOAT,
+    carbs FLOAT,
+    fat FLOAT,
+    fiber FLOAT
+);
@@ -0,0 +1,3 @@
+ALTER TABLE grocery_item DROP FOREIGN KEY grocery_item_food, DROP COLUMN food_id;
+ALTER TABLE food_log DROP FOREIGN KEY food_log_food, DROP COLUMN food_id;
+DROP TABLE food;
@@ -0,0 +1,3 @@
+ALTER TABLE food_log DROP FOREIGN KEY food_log_recipe, DROP COLUMN recipe_id;
+DROP TABLE recipe_ingredient;
+DROP TABLE recipe;
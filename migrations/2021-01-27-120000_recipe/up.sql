@@ -0,0 +1,20 @@
+-- `servings` is how many servings a batch makes.  Nutrition is worked out
+-- from the ingredients' foods whenever a recipe is read.
+CREATE TABLE recipe(
+    id INT AUTO_INCREMENT PRIMARY KEY,
+    name VARCHAR(100) NOT NULL,
+    servings FLOAT NOT NULL
+);
+CREATE TABLE recipe_ingredient(
+    id INT AUTO_INCREMENT PRIMARY KEY,
+    recipe_id INT NOT NULL,
+    food_id INT NOT NULL,
+    amount FLOAT NOT NULL,
+    unit VARCHAR(100) NOT NULL,
+    FOREIGN KEY (recipe_id)
+        REFERENCES recipe(id),
+    FOREIGN KEY (food_id)
+        REFERENCES food(id)
+);
+ALTER TABLE food_log ADD COLUMN recipe_id INT,
+    ADD CONSTRAINT food_log_recipe FOREIGN KEY (recipe_id) REFERENCES recipe(id) ON DELETE SET NULL;
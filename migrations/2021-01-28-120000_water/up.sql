@@ -0,0 +1,10 @@
+-- `unit` is `ml` or `fl oz`, and `beverage` the lowercase name of what was
+-- drunk
+CREATE TABLE water(
+    id INT AUTO_INCREMENT PRIMARY KEY,
+    date DATE NOT NULL,
+    time TIME,
+    volume FLOAT NOT NULL,
+    unit VARCHAR(5) NOT NULL,
+    beverage VARCHAR(10) NOT NULL
+);
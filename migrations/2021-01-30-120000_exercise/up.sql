@@ -0,0 +1,13 @@
+-- `activity` and `intensity` are lowercase names, `duration` is in minutes
+-- and `distance_unit` is `km` or `mi`.  Calories burned are estimated when
+-- read, from the weight logged before the workout.
+CREATE TABLE exercise(
+    id INT AUTO_INCREMENT PRIMARY KEY,
+    date DATE NOT NULL,
+    time TIME,
+    activity VARCHAR(10) NOT NULL,
+    duration INT NOT NULL,
+    intensity VARCHAR(10) NOT NULL,
+    distance FLOAT,
+    distance_unit VARCHAR(5) NOT NULL
+);
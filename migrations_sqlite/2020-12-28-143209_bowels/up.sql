@@ -0,0 +1,6 @@
+CREATE TABLE bowel (
+    id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+    date DATE NOT NULL,
+    time TIME,
+    scale SMALLINT NOT NULL
+);
@@ -0,0 +1,4 @@
+CREATE TABLE store (
+    id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+    name VARCHAR(100) NOT NULL UNIQUE
+);
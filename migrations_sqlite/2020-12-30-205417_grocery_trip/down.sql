@@ -0,0 +1 @@
+DROP TABLE grocery_trip;
@@ -0,0 +1,9 @@
+CREATE TABLE grocery_trip (
+    id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+    date DATE NOT NULL,
+    time TIME,
+    store_id INTEGER NOT NULL,
+    CONSTRAINT store_fk
+    FOREIGN KEY (store_id)
+        REFERENCES store(id)
+);
@@ -0,0 +1,11 @@
+CREATE TABLE metric (
+    id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+    date DATE NOT NULL,
+    time TIME,
+    weight FLOAT,
+    body_fat FLOAT,
+    gut_circum FLOAT,
+    waist_circum FLOAT,
+    chest_circum FLOAT,
+    thigh_circum FLOAT
+);
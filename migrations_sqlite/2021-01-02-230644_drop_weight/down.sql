@@ -0,0 +1,2 @@
+ALTER TABLE metric
+ADD COLUMN weight FLOAT;
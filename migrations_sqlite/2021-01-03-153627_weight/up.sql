@@ -0,0 +1,6 @@
+CREATE TABLE weight (
+    id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+    date DATE NOT NULL,
+    time TIME,
+    value FLOAT NOT NULL
+);
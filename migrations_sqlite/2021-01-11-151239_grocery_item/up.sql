@@ -0,0 +1,9 @@
+CREATE TABLE grocery_item (
+    id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+    trip_id INTEGER NOT NULL,
+    name VARCHAR(100) NOT NULL,
+    amount FLOAT,
+    measure VARCHAR(100),
+    FOREIGN KEY (trip_id)
+        REFERENCES grocery_trip(id)
+);
@@ -0,0 +1,3 @@
+-- Entries made before units were recorded were in pounds and inches
+ALTER TABLE weight ADD COLUMN unit VARCHAR(2) NOT NULL DEFAULT 'lb';
+ALTER TABLE metric ADD COLUMN length_unit VARCHAR(2) NOT NULL DEFAULT 'in';
@@ -0,0 +1,34 @@
+-- SQLite cannot drop a column with a foreign key, so the tables are rebuilt
+CREATE TABLE grocery_item_old (
+    id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+    trip_id INTEGER NOT NULL,
+    name VARCHAR(100) NOT NULL,
+    amount FLOAT,
+    measure VARCHAR(100),
+    FOREIGN KEY (trip_id)
+        REFERENCES grocery_trip(id)
+);
+INSERT INTO grocery_item_old SELECT id, trip_id, name, amount, measure FROM grocery_item;
+DROP TABLE grocery_item;
+ALTER TABLE grocery_item_old RENAME TO grocery_item;
+
+CREATE TABLE food_log_old (
+    id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+    date DATE NOT NULL,
+    time TIME,
+    meal VARCHAR(9) NOT NULL,
+    name VARCHAR(100) NOT NULL,
+    servings FLOAT NOT NULL,
+    calories FLOAT NOT NULL,
+    protein FLOAT,
+    carbs FLOAT,
+    fat FLOAT,
+    fiber FLOAT
+);
+INSERT INTO food_log_old
+    SELECT id, date, time, meal, name, servings, calories, protein, carbs, fat, fiber
+    FROM food_log;
+DROP TABLE food_log;
+ALTER TABLE food_log_old RENAME TO food_log;
+
+DROP TABLE food;
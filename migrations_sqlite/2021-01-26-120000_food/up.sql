@@ -0,0 +1,18 @@
+-- Nutrition is per serving.  Rows referring to a food keep what was last
+-- copied from it if the food is deleted.
+CREATE TABLE food (
+    id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+    name VARCHAR(100) NOT NULL,
+    brand VARCHAR(100),
+    serving_size FLOAT NOT NULL,
+    serving_unit VARCHAR(100) NOT NULL,
+    calories FLOAT NOT NULL,
+    protein FLOAT,
+    carbs FLOAT,
+    fat FLOAT,
+    fiber FLOAT
+);
+ALTER TABLE food_log ADD COLUMN food_id INTEGER
+    REFERENCES food(id) ON DELETE SET NULL;
+ALTER TABLE grocery_item ADD COLUMN food_id INTEGER
+    REFERENCES food(id) ON DELETE SET NULL;
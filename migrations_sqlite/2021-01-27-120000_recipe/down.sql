@@ -0,0 +1,24 @@
+-- SQLite cannot drop a column with a foreign key, so the table is rebuilt
+CREATE TABLE food_log_old (
+    id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+    date DATE NOT NULL,
+    time TIME,
+    meal VARCHAR(9) NOT NULL,
+    name VARCHAR(100) NOT NULL,
+    servings FLOAT NOT NULL,
+    calories FLOAT NOT NULL,
+    protein FLOAT,
+    carbs FLOAT,
+    fat FLOAT,
+    fiber FLOAT,
+    food_id INTEGER
+        REFERENCES food(id) ON DELETE SET NULL
+);
+INSERT INTO food_log_old
+    SELECT id, date, time, meal, name, servings, calories, protein, carbs, fat, fiber, food_id
+    FROM food_log;
+DROP TABLE food_log;
+ALTER TABLE food_log_old RENAME TO food_log;
+
+DROP TABLE recipe_ingredient;
+DROP TABLE recipe;
@@ -0,0 +1,20 @@
+-- `servings` is how many servings a batch makes.  Nutrition is worked out
+-- from the ingredients' foods whenever a recipe is read.
+CREATE TABLE recipe (
+    id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+    name VARCHAR(100) NOT NULL,
+    servings FLOAT NOT NULL
+);
+CREATE TABLE recipe_ingredient (
+    id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+    recipe_id INTEGER NOT NULL,
+    food_id INTEGER NOT NULL,
+    amount FLOAT NOT NULL,
+    unit VARCHAR(100) NOT NULL,
+    FOREIGN KEY (recipe_id)
+        REFERENCES recipe(id),
+    FOREIGN KEY (food_id)
+        REFERENCES food(id)
+);
+ALTER TABLE food_log ADD COLUMN recipe_id INTEGER
+    REFERENCES recipe(id) ON DELETE SET NULL;
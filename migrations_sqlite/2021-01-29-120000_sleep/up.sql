@@ -0,0 +1,9 @@
+-- `date` is the day of `bed_time`.  A `wake_time` at or before `bed_time`
+-- is on the next day.
+CREATE TABLE sleep (
+    id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+    date DATE NOT NULL,
+    bed_time TIME NOT NULL,
+    wake_time TIME NOT NULL,
+    quality INTEGER NOT NULL
+);
@@ -0,0 +1,63 @@
+use serde::{Deserialize, Serialize};
+use std::fmt::{self, Display, Formatter};
+
+use crate::validate::FieldError;
+
+/// Body of every failed api response
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ApiError {
+    pub code: ErrorCode,
+    pub message: String,
+    /// The rules an `InvalidInput` body broke
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub fields: Vec<FieldError>,
+}
+
+impl ApiError {
+    pub fn new(code: ErrorCode, message: &str) -> Self {
+        ApiError {
+            code,
+            message: message.to_string(),
+            fields: Vec::new(),
+        }
+    }
+
+    pub fn invalid(fields: Vec<FieldError>) -> Self {
+        let broken = fields
+            .iter()
+            .map(|x| x.to_string())
+            .collect::<Vec<String>>()
+            .join(", ");
+        ApiError {
+            code: ErrorCode::InvalidInput,
+            message: format!("Invalid input: {}", broken),
+            fields,
+        }
+    }
+}
+
+impl Display for ApiError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+/// Machine readable reason a request failed
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorCode {
+    /// The request could not be understood, such as a bad query string
+    BadRequest,
+    /// The body was well formed but does not describe a valid row
+    InvalidInput,
+    NotFound,
+    /// A row with the same unique value already exists
+    UniqueViolation,
+    /// The row refers to, or is referred to by, rows that prevent the change
+    ForeignKeyViolation,
+    /// The body is larger than the api reads
+    PayloadTooLarge,
+    /// The database could not be reached
+    Unavailable,
+    Internal,
+}
@@ -1,17 +1,121 @@
 #[cfg(feature = "database")]
+use crate::db::sql_types::Byte;
+#[cfg(feature = "database")]
 use crate::schema::bowel;
 
 use chrono::naive::{NaiveDate, NaiveTime};
 use serde::{Deserialize, Serialize};
+use std::convert::TryFrom;
+use std::fmt;
+use std::str::FromStr;
 
+use crate::column::{Cell, Column, ValueType};
+use crate::import::{key_timestamp, Fields, Importable, Record};
+use crate::timestamp::{Timestamp, Timestamped};
+use crate::validate::{FieldError, Rules, Validate};
 use crate::Tabular;
 
-#[cfg_attr(feature = "database", derive(Insertable), table_name = "bowel")]
+/// A stool's type on the Bristol Stool Scale, stored and serialized as its
+/// number from 1 to 7
+#[cfg_attr(
+    feature = "database",
+    derive(AsExpression, FromSqlRow),
+    sql_type = "Byte"
+)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "i8", into = "i8")]
+pub enum Bristol {
+    SeparateLumps = 1,
+    Lumpy = 2,
+    Cracked = 3,
+    Smooth = 4,
+    SoftBlobs = 5,
+    Mushy = 6,
+    Liquid = 7,
+}
+
+impl Bristol {
+    pub const ALL: [Bristol; 7] = [
+        Bristol::SeparateLumps,
+        Bristol::Lumpy,
+        Bristol::Cracked,
+        Bristol::Smooth,
+        Bristol::SoftBlobs,
+        Bristol::Mushy,
+        Bristol::Liquid,
+    ];
+
+    pub fn number(self) -> i8 {
+        self as i8
+    }
+
+    pub fn description(self) -> &'static str {
+        match self {
+            Bristol::SeparateLumps => "Separate hard lumps",
+            Bristol::Lumpy => "Lumpy and sausage-like",
+            Bristol::Cracked => "Sausage shape with cracks",
+            Bristol::Smooth => "Smooth, soft sausage",
+            Bristol::SoftBlobs => "Soft blobs with clear-cut edges",
+            Bristol::Mushy => "Mushy with ragged edges",
+            Bristol::Liquid => "Liquid with no solid pieces",
+        }
+    }
+}
+
+impl TryFrom<i8> for Bristol {
+    type Error = String;
+
+    fn try_from(number: i8) -> Result<Self, Self::Error> {
+        Bristol::ALL
+            .iter()
+            .copied()
+            .find(|b| b.number() == number)
+            .ok_or_else(|| format!("{} is not a Bristol type, expected 1 to 7", number))
+    }
+}
+
+impl FromStr for Bristol {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let number = s
+            .parse::<i8>()
+            .map_err(|_| format!("{} is not a Bristol type, expected 1 to 7", s))?;
+        Bristol::try_from(number)
+    }
+}
+
+impl From<Bristol> for i8 {
+    fn from(bristol: Bristol) -> i8 {
+        bristol.number()
+    }
+}
+
+impl fmt::Display for Bristol {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}: {}", self.number(), self.description())
+    }
+}
+
+#[cfg_attr(
+    feature = "database",
+    derive(Insertable, AsChangeset),
+    table_name = "bowel",
+    changeset_options(treat_none_as_null = "true")
+)]
 #[derive(Clone, Copy, Debug, Serialize, Deserialize)]
 pub struct NewBowel {
     pub date: NaiveDate,
     pub time: Option<NaiveTime>,
-    pub scale: i8,
+    pub scale: Bristol,
+}
+
+impl Validate for NewBowel {
+    fn validate(&self) -> Result<(), Vec<FieldError>> {
+        let mut rules = Rules::new();
+        rules.not_future("date", self.date);
+        rules.finish()
+    }
 }
 
 #[cfg_attr(feature = "database", derive(Queryable))]
@@ -20,28 +124,140 @@ pub struct Bowel {
     pub id: i32,
     pub date: NaiveDate,
     pub time: Option<NaiveTime>,
-    pub scale: i8,
+    pub scale: Bristol,
+}
+
+impl Timestamped for NewBowel {
+    fn timestamp(&self) -> Timestamp {
+        Timestamp::new(self.date, self.time)
+    }
+}
+
+impl Timestamped for Bowel {
+    fn timestamp(&self) -> Timestamp {
+        Timestamp::new(self.date, self.time)
+    }
+}
+
+impl From<Bowel> for NewBowel {
+    fn from(bowel: Bowel) -> Self {
+        NewBowel {
+            date: bowel.date,
+            time: bowel.time,
+            scale: bowel.scale,
+        }
+    }
+}
+
+impl Importable for NewBowel {
+    fn from_record(record: &Record) -> Result<Self, Vec<FieldError>> {
+        let mut fields = Fields::new(record);
+        let date = fields.date("date");
+        let time = fields.time_option("time");
+        let scale = fields.required("scale");
+        fields.finish(|| {
+            Some(NewBowel {
+                date: date?,
+                time: time?,
+                scale: scale?,
+            })
+        })
+    }
+
+    fn duplicate_key(&self) -> String {
+        format!(
+            "{} {}",
+            key_timestamp(self.timestamp()),
+            self.scale.number()
+        )
+    }
 }
 
 impl Tabular for Vec<Bowel> {
-    fn headers(&self) -> Vec<String> {
-        let v = vec!["Date", "Time", "Scale"];
-        v.iter().map(|x| x.to_string()).collect()
+    fn columns(&self) -> Vec<Column> {
+        [
+            Timestamp::columns(),
+            vec![Column::new("scale", "Scale", ValueType::Int)],
+        ]
+        .concat()
     }
 
-    fn matrix(&self) -> Vec<Vec<String>> {
+    fn rows(&self) -> Vec<Vec<Cell>> {
         self.iter()
             .map(|bowel| {
-                let time = match bowel.time {
-                    None => String::new(),
-                    Some(t) => t.format("%-I:%M %p").to_string(),
-                };
-                vec![
-                    bowel.date.format("%b %d %Y").to_string(),
-                    time,
-                    bowel.scale.to_string(),
+                [
+                    bowel.timestamp().cells(),
+                    vec![Cell::int(
+                        bowel.scale.number().into(),
+                        bowel.scale.to_string(),
+                    )],
                 ]
+                .concat()
             })
-            .collect::<Vec<Vec<String>>>()
+            .collect()
+    }
+}
+
+/// The scale is stored as the type's number
+#[cfg(feature = "database")]
+mod sql {
+    use super::*;
+    use diesel::backend::Backend;
+    use diesel::deserialize::{self, FromSql};
+    use diesel::serialize::{self, Output, ToSql};
+    use std::io::Write;
+
+    impl<DB: Backend> ToSql<Byte, DB> for Bristol
+    where
+        i8: ToSql<Byte, DB>,
+    {
+        fn to_sql<W: Write>(&self, out: &mut Output<W, DB>) -> serialize::Result {
+            self.number().to_sql(out)
+        }
+    }
+
+    impl<DB: Backend> FromSql<Byte, DB> for Bristol
+    where
+        i8: FromSql<Byte, DB>,
+    {
+        fn from_sql(bytes: Option<&DB::RawValue>) -> deserialize::Result<Self> {
+            let number = i8::from_sql(bytes)?;
+            Ok(Bristol::try_from(number)?)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bristol_serializes_as_its_number() {
+        for &bristol in Bristol::ALL.iter() {
+            let json = serde_json::to_string(&bristol).unwrap();
+            assert_eq!(json, bristol.number().to_string());
+            let back: Bristol = serde_json::from_str(&json).unwrap();
+            assert_eq!(back, bristol);
+        }
+        assert_eq!(serde_json::to_string(&Bristol::Liquid).unwrap(), "7");
+    }
+
+    #[test]
+    fn bristol_rejects_numbers_outside_the_scale() {
+        assert!(serde_json::from_str::<Bristol>("0").is_err());
+        assert!(serde_json::from_str::<Bristol>("8").is_err());
+        assert!(Bristol::try_from(0).is_err());
+        assert!(Bristol::try_from(8).is_err());
+        assert_eq!(Bristol::try_from(1), Ok(Bristol::SeparateLumps));
+        assert_eq!("4".parse(), Ok(Bristol::Smooth));
+    }
+
+    #[test]
+    fn new_bowel_rejects_an_out_of_range_scale() {
+        let json = r#"{"date": "2021-01-31", "time": null, "scale": 8}"#;
+        assert!(serde_json::from_str::<NewBowel>(json).is_err());
+        let json = r#"{"date": "2021-01-31", "time": null, "scale": 3}"#;
+        let bowel: NewBowel = serde_json::from_str(json).unwrap();
+        assert_eq!(bowel.scale, Bristol::Cracked);
     }
 }
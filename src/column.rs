@@ -0,0 +1,132 @@
+use chrono::naive::{NaiveDate, NaiveTime};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+use crate::timestamp::{DATE_FORMAT, TIME_FORMAT};
+
+/// The kind of value a column holds
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ValueType {
+    Date,
+    Time,
+    Int,
+    Float,
+    Text,
+}
+
+impl ValueType {
+    /// Whether values sort and align as numbers
+    pub fn is_numeric(self) -> bool {
+        matches!(self, ValueType::Int | ValueType::Float)
+    }
+}
+
+/// Describes one column of a `Tabular`
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Column {
+    /// The field's name in the json body
+    pub key: String,
+    /// The header shown to readers
+    pub name: String,
+    pub value_type: ValueType,
+    /// Set when every value in the column is in the same unit
+    pub unit: Option<String>,
+}
+
+impl Column {
+    pub fn new(key: &str, name: &str, value_type: ValueType) -> Self {
+        Self {
+            key: key.to_string(),
+            name: name.to_string(),
+            value_type,
+            unit: None,
+        }
+    }
+
+    pub fn with_unit(mut self, unit: Option<&str>) -> Self {
+        self.unit = unit.map(|x| x.to_string());
+        self
+    }
+}
+
+/// A cell's value before it is formatted
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum Value {
+    Date(NaiveDate),
+    Time(NaiveTime),
+    Int(i32),
+    Float(f32),
+    Text(String),
+}
+
+/// Written the way it is serialized, e.g. `2021-01-31` or `72.5`
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Value::Date(d) => write!(f, "{}", d.format("%Y-%m-%d")),
+            Value::Time(t) => write!(f, "{}", t.format("%H:%M:%S")),
+            Value::Int(x) => write!(f, "{}", x),
+            Value::Float(x) => write!(f, "{}", x),
+            Value::Text(s) => write!(f, "{}", s),
+        }
+    }
+}
+
+/// One cell of a `Tabular`: what is shown, and the value it was made from.
+/// `value` is `None` for an empty cell.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Cell {
+    pub display: String,
+    pub value: Option<Value>,
+}
+
+impl Cell {
+    pub fn new(display: String, value: Option<Value>) -> Self {
+        Self { display, value }
+    }
+
+    pub fn empty() -> Self {
+        Self::new(String::new(), None)
+    }
+
+    pub fn date(date: NaiveDate) -> Self {
+        Self::new(
+            date.format(DATE_FORMAT).to_string(),
+            Some(Value::Date(date)),
+        )
+    }
+
+    pub fn time(time: Option<NaiveTime>) -> Self {
+        match time {
+            None => Self::empty(),
+            Some(t) => Self::new(t.format(TIME_FORMAT).to_string(), Some(Value::Time(t))),
+        }
+    }
+
+    pub fn int(value: i32, display: String) -> Self {
+        Self::new(display, Some(Value::Int(value)))
+    }
+
+    pub fn float(value: Option<f32>, display: impl Fn(f32) -> String) -> Self {
+        match value {
+            None => Self::empty(),
+            Some(x) => Self::new(display(x), Some(Value::Float(x))),
+        }
+    }
+
+    pub fn text(text: &str) -> Self {
+        Self::new(text.to_string(), Some(Value::Text(text.to_string())))
+    }
+}
+
+/// The value every item shares, or `None` if they differ or there are none
+pub fn common<T: PartialEq>(mut values: impl Iterator<Item = T>) -> Option<T> {
+    let first = values.next()?;
+    if values.all(|x| x == first) {
+        Some(first)
+    } else {
+        None
+    }
+}
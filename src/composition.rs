@@ -0,0 +1,222 @@
+use chrono::naive::{NaiveDate, NaiveTime};
+use serde::{Deserialize, Serialize};
+
+use crate::column::{Cell, Column, ValueType};
+use crate::metric::Metric;
+use crate::timestamp::{Timestamp, Timestamped};
+use crate::units::{with_suffix, WeightUnit};
+use crate::weight::Weight;
+use crate::Tabular;
+
+/// Weights logged more than this many days from a body fat reading are not
+/// paired with it
+pub const MAX_GAP_DAYS: i64 = 2;
+
+/// A body fat reading split into fat and lean mass, using the weight logged
+/// nearest to it
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct Composition {
+    pub metric_id: i32,
+    pub weight_id: i32,
+    pub date: NaiveDate,
+    pub time: Option<NaiveTime>,
+    pub weight: f32,
+    pub body_fat: f32,
+    pub fat_mass: f32,
+    pub lean_mass: f32,
+    /// Change since the previous reading, `None` for the first
+    pub fat_change: Option<f32>,
+    pub lean_change: Option<f32>,
+    pub unit: WeightUnit,
+}
+
+/// Pairs every metric that has a body fat reading with the nearest of
+/// `weights`, oldest first.  Readings without a weight close enough are
+/// left out.
+pub fn compose(metrics: &[Metric], weights: &[Weight], unit: WeightUnit) -> Vec<Composition> {
+    let mut readings = metrics
+        .iter()
+        .filter_map(|metric| {
+            let body_fat = metric.body_fat?;
+            let at = metric.timestamp().moment();
+            let gap = |x: &Weight| x.timestamp().moment() - at;
+            let weight = weights
+                .iter()
+                .min_by_key(|x| gap(x).num_minutes().abs())
+                .filter(|x| gap(x).num_hours().abs() <= MAX_GAP_DAYS * 24)?;
+            let value = weight.unit.convert(weight.value, unit);
+            let fat_mass = value * body_fat / 100.0;
+            Some(Composition {
+                metric_id: metric.id,
+                weight_id: weight.id,
+                date: metric.date,
+                time: metric.time,
+                weight: value,
+                body_fat,
+                fat_mass,
+                lean_mass: value - fat_mass,
+                fat_change: None,
+                lean_change: None,
+                unit,
+            })
+        })
+        .collect::<Vec<Composition>>();
+    readings.sort_by_key(|x| x.timestamp());
+
+    for i in 1..readings.len() {
+        let prev = readings[i - 1];
+        let reading = &mut readings[i];
+        reading.fat_change = Some(reading.fat_mass - prev.fat_mass);
+        reading.lean_change = Some(reading.lean_mass - prev.lean_mass);
+    }
+    readings
+}
+
+impl Timestamped for Composition {
+    fn timestamp(&self) -> Timestamp {
+        Timestamp::new(self.date, self.time)
+    }
+}
+
+impl Tabular for Vec<Composition> {
+    fn columns(&self) -> Vec<Column> {
+        let unit = self.first().map(|x| x.unit.suffix());
+        [
+            Timestamp::columns(),
+            vec![
+                Column::new("weight", "Weight", ValueType::Float).with_unit(unit),
+                Column::new("body_fat", "Body Fat %", ValueType::Float).with_unit(Some("%")),
+                Column::new("fat_mass", "Fat Mass", ValueType::Float).with_unit(unit),
+                Column::new("lean_mass", "Lean Mass", ValueType::Float).with_unit(unit),
+                Column::new("fat_change", "Fat Change", ValueType::Float).with_unit(unit),
+                Column::new("lean_change", "Lean Change", ValueType::Float).with_unit(unit),
+            ],
+        ]
+        .concat()
+    }
+
+    fn rows(&self) -> Vec<Vec<Cell>> {
+        self.iter()
+            .map(|x| {
+                let mass = |value: f32| Cell::float(Some(value), |v| with_suffix(v, x.unit));
+                let change =
+                    |value: Option<f32>| Cell::float(value, |v| format!("{:+.1} {}", v, x.unit));
+                [
+                    x.timestamp().cells(),
+                    vec![
+                        mass(x.weight),
+                        Cell::float(Some(x.body_fat), |v| v.to_string()),
+                        mass(x.fat_mass),
+                        mass(x.lean_mass),
+                        change(x.fat_change),
+                        change(x.lean_change),
+                    ],
+                ]
+                .concat()
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::units::LengthUnit;
+
+    fn at(s: &str) -> (NaiveDate, Option<NaiveTime>) {
+        let moment: chrono::NaiveDateTime = s.parse().unwrap();
+        (moment.date(), Some(moment.time()))
+    }
+
+    fn metric(id: i32, when: &str, body_fat: Option<f32>) -> Metric {
+        let (date, time) = at(when);
+        Metric {
+            id,
+            date,
+            time,
+            body_fat,
+            gut_circum: None,
+            waist_circum: None,
+            chest_circum: None,
+            thigh_circum: None,
+            length_unit: LengthUnit::Cm,
+        }
+    }
+
+    fn weight(id: i32, when: &str, value: f32, unit: WeightUnit) -> Weight {
+        let (date, time) = at(when);
+        Weight {
+            id,
+            date,
+            time,
+            value,
+            unit,
+        }
+    }
+
+    #[test]
+    fn pairs_the_nearest_weight() {
+        let metrics = [metric(1, "2021-02-10T08:00:00", Some(20.0))];
+        let weights = [
+            weight(1, "2021-02-08T09:00:00", 81.0, WeightUnit::Kg),
+            weight(2, "2021-02-10T20:00:00", 80.0, WeightUnit::Kg),
+            weight(3, "2021-02-11T07:00:00", 79.0, WeightUnit::Kg),
+        ];
+        let readings = compose(&metrics, &weights, WeightUnit::Kg);
+        assert_eq!(readings.len(), 1);
+        assert_eq!(readings[0].weight_id, 2);
+        assert_eq!(readings[0].fat_mass, 16.0);
+        assert_eq!(readings[0].lean_mass, 64.0);
+    }
+
+    #[test]
+    fn leaves_out_readings_without_a_weight_in_the_gap() {
+        let metrics = [
+            metric(1, "2021-02-01T08:00:00", Some(20.0)),
+            metric(2, "2021-02-10T08:00:00", Some(21.0)),
+            metric(3, "2021-02-12T08:00:00", None),
+        ];
+        let weights = [weight(1, "2021-02-12T07:00:00", 80.0, WeightUnit::Kg)];
+        let readings = compose(&metrics, &weights, WeightUnit::Kg);
+        assert_eq!(
+            readings.iter().map(|x| x.metric_id).collect::<Vec<_>>(),
+            vec![2]
+        );
+
+        // An hour past the gap
+        let weights = [weight(1, "2021-02-12T09:00:00", 80.0, WeightUnit::Kg)];
+        assert!(compose(&metrics, &weights, WeightUnit::Kg).is_empty());
+    }
+
+    #[test]
+    fn converts_pounds_to_kilograms() {
+        let metrics = [metric(1, "2021-02-10T08:00:00", Some(25.0))];
+        let weights = [weight(1, "2021-02-10T07:00:00", 200.0, WeightUnit::Lb)];
+        let reading = compose(&metrics, &weights, WeightUnit::Kg)[0];
+        assert_eq!(reading.unit, WeightUnit::Kg);
+        assert!((reading.weight - 90.718_47).abs() < 1e-3);
+        assert!((reading.fat_mass - 22.679_62).abs() < 1e-3);
+        assert!((reading.lean_mass - 68.038_85).abs() < 1e-3);
+    }
+
+    #[test]
+    fn changes_are_since_the_previous_reading() {
+        let metrics = [
+            metric(2, "2021-02-15T08:00:00", Some(19.0)),
+            metric(1, "2021-02-01T08:00:00", Some(20.0)),
+        ];
+        let weights = [
+            weight(1, "2021-02-01T07:00:00", 80.0, WeightUnit::Kg),
+            weight(2, "2021-02-15T07:00:00", 78.0, WeightUnit::Kg),
+        ];
+        let readings = compose(&metrics, &weights, WeightUnit::Kg);
+        assert_eq!(readings[0].metric_id, 1);
+        assert_eq!(readings[0].fat_change, None);
+        assert_eq!(readings[0].lean_change, None);
+
+        // 16 kg of fat and 64 kg lean, then 14.82 kg and 63.18 kg
+        assert_eq!(readings[1].metric_id, 2);
+        assert!((readings[1].fat_change.unwrap() + 1.18).abs() < 1e-3);
+        assert!((readings[1].lean_change.unwrap() + 0.82).abs() < 1e-3);
+    }
+}
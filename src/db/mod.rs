@@ -1 +1,3 @@
 pub mod schema;
+#[macro_use]
+pub mod sql_types;
@@ -1,9 +1,58 @@
 table! {
+    use diesel::sql_types::*;
+    use crate::db::sql_types::Byte;
+
     bowel (id) {
         id -> Integer,
         date -> Date,
         time -> Nullable<Time>,
-        scale -> Tinyint,
+        scale -> Byte,
+    }
+}
+
+table! {
+    exercise (id) {
+        id -> Integer,
+        date -> Date,
+        time -> Nullable<Time>,
+        activity -> Varchar,
+        duration -> Integer,
+        intensity -> Varchar,
+        distance -> Nullable<Float>,
+        distance_unit -> Varchar,
+    }
+}
+
+table! {
+    food (id) {
+        id -> Integer,
+        name -> Varchar,
+        brand -> Nullable<Varchar>,
+        serving_size -> Float,
+        serving_unit -> Varchar,
+        calories -> Float,
+        protein -> Nullable<Float>,
+        carbs -> Nullable<Float>,
+        fat -> Nullable<Float>,
+        fiber -> Nullable<Float>,
+    }
+}
+
+table! {
+    food_log (id) {
+        id -> Integer,
+        date -> Date,
+        time -> Nullable<Time>,
+        meal -> Varchar,
+        name -> Varchar,
+        servings -> Float,
+        calories -> Float,
+        protein -> Nullable<Float>,
+        carbs -> Nullable<Float>,
+        fat -> Nullable<Float>,
+        fiber -> Nullable<Float>,
+        food_id -> Nullable<Integer>,
+        recipe_id -> Nullable<Integer>,
     }
 }
 
@@ -14,6 +63,7 @@ table! {
         name -> Varchar,
         amount -> Nullable<Float>,
         measure -> Nullable<Varchar>,
+        food_id -> Nullable<Integer>,
     }
 }
 
@@ -36,6 +86,35 @@ table! {
         waist_circum -> Nullable<Float>,
         chest_circum -> Nullable<Float>,
         thigh_circum -> Nullable<Float>,
+        length_unit -> Varchar,
+    }
+}
+
+table! {
+    recipe (id) {
+        id -> Integer,
+        name -> Varchar,
+        servings -> Float,
+    }
+}
+
+table! {
+    recipe_ingredient (id) {
+        id -> Integer,
+        recipe_id -> Integer,
+        food_id -> Integer,
+        amount -> Float,
+        unit -> Varchar,
+    }
+}
+
+table! {
+    sleep (id) {
+        id -> Integer,
+        date -> Date,
+        bed_time -> Time,
+        wake_time -> Time,
+        quality -> Integer,
     }
 }
 
@@ -46,23 +125,47 @@ table! {
     }
 }
 
+table! {
+    water (id) {
+        id -> Integer,
+        date -> Date,
+        time -> Nullable<Time>,
+        volume -> Float,
+        unit -> Varchar,
+        beverage -> Varchar,
+    }
+}
+
 table! {
     weight (id) {
         id -> Integer,
         date -> Date,
         time -> Nullable<Time>,
         value -> Float,
+        unit -> Varchar,
     }
 }
 
+joinable!(food_log -> food (food_id));
+joinable!(food_log -> recipe (recipe_id));
+joinable!(grocery_item -> food (food_id));
 joinable!(grocery_item -> grocery_trip (trip_id));
 joinable!(grocery_trip -> store (store_id));
+joinable!(recipe_ingredient -> food (food_id));
+joinable!(recipe_ingredient -> recipe (recipe_id));
 
 allow_tables_to_appear_in_same_query!(
     bowel,
+    exercise,
+    food,
+    food_log,
     grocery_item,
     grocery_trip,
     metric,
+    recipe,
+    recipe_ingredient,
+    sleep,
     store,
+    water,
     weight,
 );
@@ -0,0 +1,87 @@
+//! SQL types that behave the same on every supported backend.
+
+use diesel::deserialize::{self, FromSql};
+use diesel::serialize::{self, Output, ToSql};
+use std::io::Write;
+
+/// Stores a type as `Text`, written with the given method and read back with
+/// `FromStr`.  The method defaults to `name`.
+macro_rules! text_sql {
+    ($type:ty) => {
+        text_sql!($type, name);
+    };
+    ($type:ty, $text:ident) => {
+        impl<DB> diesel::serialize::ToSql<diesel::sql_types::Text, DB> for $type
+        where
+            DB: diesel::backend::Backend,
+            str: diesel::serialize::ToSql<diesel::sql_types::Text, DB>,
+        {
+            fn to_sql<W: std::io::Write>(
+                &self,
+                out: &mut diesel::serialize::Output<W, DB>,
+            ) -> diesel::serialize::Result {
+                diesel::serialize::ToSql::<diesel::sql_types::Text, DB>::to_sql(self.$text(), out)
+            }
+        }
+
+        impl<DB> diesel::deserialize::FromSql<diesel::sql_types::Text, DB> for $type
+        where
+            DB: diesel::backend::Backend,
+            String: diesel::deserialize::FromSql<diesel::sql_types::Text, DB>,
+        {
+            fn from_sql(bytes: Option<&DB::RawValue>) -> diesel::deserialize::Result<Self> {
+                let text: String =
+                    diesel::deserialize::FromSql::<diesel::sql_types::Text, DB>::from_sql(bytes)?;
+                Ok(text.parse()?)
+            }
+        }
+    };
+}
+
+/// A single signed byte.  MySQL stores it as `TINYINT`, which diesel only
+/// supports on MySQL, so SQLite falls back to `SMALLINT`.
+#[derive(Debug, Clone, Copy, Default, QueryId, SqlType)]
+#[mysql_type = "Tiny"]
+#[sqlite_type = "SmallInt"]
+pub struct Byte;
+
+#[cfg(feature = "mysql")]
+mod mysql {
+    use super::*;
+    use diesel::mysql::Mysql;
+    use diesel::sql_types::TinyInt;
+
+    impl ToSql<Byte, Mysql> for i8 {
+        fn to_sql<W: Write>(&self, out: &mut Output<W, Mysql>) -> serialize::Result {
+            ToSql::<TinyInt, Mysql>::to_sql(self, out)
+        }
+    }
+
+    impl FromSql<Byte, Mysql> for i8 {
+        fn from_sql(bytes: Option<&[u8]>) -> deserialize::Result<Self> {
+            FromSql::<TinyInt, Mysql>::from_sql(bytes)
+        }
+    }
+}
+
+#[cfg(feature = "sqlite")]
+mod sqlite {
+    use super::*;
+    use diesel::backend::Backend;
+    use diesel::sql_types::SmallInt;
+    use diesel::sqlite::Sqlite;
+    use std::convert::TryFrom;
+
+    impl ToSql<Byte, Sqlite> for i8 {
+        fn to_sql<W: Write>(&self, out: &mut Output<W, Sqlite>) -> serialize::Result {
+            ToSql::<SmallInt, Sqlite>::to_sql(&i16::from(*self), out)
+        }
+    }
+
+    impl FromSql<Byte, Sqlite> for i8 {
+        fn from_sql(value: Option<&<Sqlite as Backend>::RawValue>) -> deserialize::Result<Self> {
+            let wide: i16 = FromSql::<SmallInt, Sqlite>::from_sql(value)?;
+            Ok(i8::try_from(wide)?)
+        }
+    }
+}
@@ -0,0 +1,40 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Rows that depend on another row, and so are deleted or moved along with
+/// it
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize)]
+pub struct Dependents {
+    pub grocery_trips: i64,
+    pub grocery_items: i64,
+    #[serde(default)]
+    pub recipe_ingredients: i64,
+}
+
+impl Dependents {
+    pub fn is_empty(&self) -> bool {
+        self.grocery_trips == 0 && self.grocery_items == 0 && self.recipe_ingredients == 0
+    }
+}
+
+/// The kinds of rows there are any of, e.g. `2 grocery trip(s) and 5
+/// grocery item(s)`
+impl fmt::Display for Dependents {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let counts = [
+            (self.grocery_trips, "grocery trip(s)"),
+            (self.grocery_items, "grocery item(s)"),
+            (self.recipe_ingredients, "recipe ingredient(s)"),
+        ];
+        let parts: Vec<String> = counts
+            .iter()
+            .filter(|(count, _)| *count > 0)
+            .map(|(count, rows)| format!("{} {}", count, rows))
+            .collect();
+        match parts.split_last() {
+            None => write!(f, "nothing"),
+            Some((last, [])) => write!(f, "{}", last),
+            Some((last, rest)) => write!(f, "{} and {}", rest.join(", "), last),
+        }
+    }
+}
@@ -0,0 +1,563 @@
+#[cfg(feature = "database")]
+use crate::schema::exercise;
+#[cfg(feature = "database")]
+use diesel::sql_types::Text;
+
+use chrono::naive::{NaiveDate, NaiveTime};
+use convert_case::{Case, Casing};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+
+use crate::column::{common, Cell, Column, Value, ValueType};
+use crate::food_log::DailyTotal;
+use crate::import::{key_float, key_timestamp, Fields, Importable, Record};
+use crate::nutrition::kcal;
+use crate::timestamp::{Timestamp, Timestamped};
+use crate::units::{with_suffix, DistanceUnit, UnitSystem, WeightUnit};
+use crate::validate::{FieldError, Rules, Validate};
+use crate::weight::Weight;
+use crate::Tabular;
+
+/// The kind of workout, stored as its lowercase name
+#[cfg_attr(
+    feature = "database",
+    derive(AsExpression, FromSqlRow),
+    sql_type = "Text"
+)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Activity {
+    Walking,
+    Running,
+    Cycling,
+    Swimming,
+    Hiking,
+    Rowing,
+    Strength,
+    Yoga,
+    Other,
+}
+
+impl Activity {
+    pub const ALL: [Activity; 9] = [
+        Activity::Walking,
+        Activity::Running,
+        Activity::Cycling,
+        Activity::Swimming,
+        Activity::Hiking,
+        Activity::Rowing,
+        Activity::Strength,
+        Activity::Yoga,
+        Activity::Other,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Activity::Walking => "walking",
+            Activity::Running => "running",
+            Activity::Cycling => "cycling",
+            Activity::Swimming => "swimming",
+            Activity::Hiking => "hiking",
+            Activity::Rowing => "rowing",
+            Activity::Strength => "strength",
+            Activity::Yoga => "yoga",
+            Activity::Other => "other",
+        }
+    }
+
+    /// Metabolic equivalent of the activity at `intensity`: the energy it
+    /// takes relative to sitting still, roughly as the Compendium of
+    /// Physical Activities has it
+    pub fn met(self, intensity: Intensity) -> f32 {
+        let (light, moderate, vigorous) = match self {
+            Activity::Walking => (2.8, 3.5, 5.0),
+            Activity::Running => (6.0, 8.3, 11.0),
+            Activity::Cycling => (4.0, 6.8, 10.0),
+            Activity::Swimming => (5.8, 8.3, 9.8),
+            Activity::Hiking => (5.3, 6.0, 7.8),
+            Activity::Rowing => (4.8, 7.0, 8.5),
+            Activity::Strength => (3.5, 5.0, 6.0),
+            Activity::Yoga => (2.5, 3.0, 4.0),
+            Activity::Other => (3.0, 4.5, 6.0),
+        };
+        match intensity {
+            Intensity::Light => light,
+            Intensity::Moderate => moderate,
+            Intensity::Vigorous => vigorous,
+        }
+    }
+}
+
+impl FromStr for Activity {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.to_lowercase();
+        Activity::ALL
+            .iter()
+            .copied()
+            .find(|x| x.name() == s)
+            .ok_or_else(|| format!("unknown activity {}", s))
+    }
+}
+
+impl fmt::Display for Activity {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name().to_case(Case::Title))
+    }
+}
+
+/// How hard a workout was, stored as its lowercase name
+#[cfg_attr(
+    feature = "database",
+    derive(AsExpression, FromSqlRow),
+    sql_type = "Text"
+)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Intensity {
+    Light,
+    Moderate,
+    Vigorous,
+}
+
+impl Intensity {
+    pub const ALL: [Intensity; 3] = [Intensity::Light, Intensity::Moderate, Intensity::Vigorous];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Intensity::Light => "light",
+            Intensity::Moderate => "moderate",
+            Intensity::Vigorous => "vigorous",
+        }
+    }
+}
+
+impl FromStr for Intensity {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.to_lowercase();
+        Intensity::ALL
+            .iter()
+            .copied()
+            .find(|x| x.name() == s)
+            .ok_or_else(|| format!("unknown intensity {}", s))
+    }
+}
+
+impl fmt::Display for Intensity {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name().to_case(Case::Title))
+    }
+}
+
+#[cfg_attr(
+    feature = "database",
+    derive(Insertable, AsChangeset),
+    table_name = "exercise",
+    changeset_options(treat_none_as_null = "true")
+)]
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct NewExercise {
+    pub date: NaiveDate,
+    pub time: Option<NaiveTime>,
+    pub activity: Activity,
+    /// In minutes
+    pub duration: i32,
+    pub intensity: Intensity,
+    pub distance: Option<f32>,
+    pub distance_unit: DistanceUnit,
+}
+
+impl Validate for NewExercise {
+    fn validate(&self) -> Result<(), Vec<FieldError>> {
+        let mut rules = Rules::new();
+        rules.not_future("date", self.date);
+        rules.range("duration", self.duration, 1, 24 * 60);
+        if let Some(distance) = self.distance {
+            rules.positive("distance", distance);
+        }
+        rules.finish()
+    }
+}
+
+/// A workout with the calories it burned, estimated from the weight logged
+/// before it
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct Exercise {
+    pub id: i32,
+    pub date: NaiveDate,
+    pub time: Option<NaiveTime>,
+    pub activity: Activity,
+    pub duration: i32,
+    pub intensity: Intensity,
+    pub distance: Option<f32>,
+    pub distance_unit: DistanceUnit,
+    /// `None` when no weight has been logged
+    pub calories: Option<f32>,
+}
+
+impl Exercise {
+    /// `weights` can be in any order
+    pub fn new(id: i32, exercise: NewExercise, weights: &[Weight]) -> Self {
+        let calories = body_weight(weights, exercise.timestamp()).map(|kg| {
+            let hours = exercise.duration as f32 / 60.0;
+            exercise.activity.met(exercise.intensity) * kg * hours
+        });
+        Exercise {
+            id,
+            date: exercise.date,
+            time: exercise.time,
+            activity: exercise.activity,
+            duration: exercise.duration,
+            intensity: exercise.intensity,
+            distance: exercise.distance,
+            distance_unit: exercise.distance_unit,
+            calories,
+        }
+    }
+
+    /// The same workout with the distance converted to `units`
+    pub fn in_units(self, units: UnitSystem) -> Self {
+        let unit = units.distance();
+        Exercise {
+            distance: self.distance.map(|x| self.distance_unit.convert(x, unit)),
+            distance_unit: unit,
+            ..self
+        }
+    }
+}
+
+/// The latest of `weights` logged at or before `at`, in kilograms.  Before
+/// the first weight, that one is used.
+fn body_weight(weights: &[Weight], at: Timestamp) -> Option<f32> {
+    let before = weights
+        .iter()
+        .filter(|x| x.timestamp() <= at)
+        .max_by_key(|x| x.timestamp());
+    before
+        .or_else(|| weights.iter().min_by_key(|x| x.timestamp()))
+        .map(|x| x.unit.convert(x.value, WeightUnit::Kg))
+}
+
+impl Timestamped for NewExercise {
+    fn timestamp(&self) -> Timestamp {
+        Timestamp::new(self.date, self.time)
+    }
+}
+
+impl Timestamped for Exercise {
+    fn timestamp(&self) -> Timestamp {
+        Timestamp::new(self.date, self.time)
+    }
+}
+
+impl From<Exercise> for NewExercise {
+    fn from(exercise: Exercise) -> Self {
+        NewExercise {
+            date: exercise.date,
+            time: exercise.time,
+            activity: exercise.activity,
+            duration: exercise.duration,
+            intensity: exercise.intensity,
+            distance: exercise.distance,
+            distance_unit: exercise.distance_unit,
+        }
+    }
+}
+
+impl Importable for NewExercise {
+    fn from_record(record: &Record) -> Result<Self, Vec<FieldError>> {
+        let mut fields = Fields::new(record);
+        let date = fields.date("date");
+        let time = fields.time_option("time");
+        let activity = fields.required("activity");
+        let duration = fields.required("duration");
+        let intensity = fields.optional("intensity");
+        let distance = fields.optional("distance");
+        // The unit only matters when there is a distance to go with it
+        let distance_unit = if record.get("distance").is_some() {
+            fields.unit("distance_unit", &["distance"])
+        } else {
+            Some(DistanceUnit::Km)
+        };
+        fields.finish(|| {
+            Some(NewExercise {
+                date: date?,
+                time: time?,
+                activity: activity?,
+                duration: duration?,
+                intensity: intensity?.unwrap_or(Intensity::Moderate),
+                distance: distance?,
+                distance_unit: distance_unit?,
+            })
+        })
+    }
+
+    /// Distances are compared in kilometers
+    fn duplicate_key(&self) -> String {
+        let km = self
+            .distance
+            .map(|x| self.distance_unit.convert(x, DistanceUnit::Km));
+        format!(
+            "{} {} {} {}",
+            key_timestamp(self.timestamp()),
+            self.activity.name(),
+            self.duration,
+            key_float(km)
+        )
+    }
+}
+
+impl Tabular for Vec<Exercise> {
+    fn columns(&self) -> Vec<Column> {
+        let unit = common(self.iter().map(|x| x.distance_unit));
+        [
+            Timestamp::columns(),
+            vec![
+                Column::new("activity", "Activity", ValueType::Text),
+                Column::new("duration", "Duration", ValueType::Int).with_unit(Some("min")),
+                Column::new("intensity", "Intensity", ValueType::Text),
+                Column::new("distance", "Distance", ValueType::Float)
+                    .with_unit(unit.map(|x| x.suffix())),
+                Column::new("calories", "Calories", ValueType::Float).with_unit(Some("kcal")),
+            ],
+        ]
+        .concat()
+    }
+
+    fn rows(&self) -> Vec<Vec<Cell>> {
+        self.iter()
+            .map(|item| {
+                [
+                    item.timestamp().cells(),
+                    vec![
+                        Cell::new(
+                            item.activity.to_string(),
+                            Some(Value::Text(item.activity.name().to_string())),
+                        ),
+                        Cell::int(item.duration, format!("{} min", item.duration)),
+                        Cell::new(
+                            item.intensity.to_string(),
+                            Some(Value::Text(item.intensity.name().to_string())),
+                        ),
+                        Cell::float(item.distance, |x| with_suffix(x, item.distance_unit)),
+                        Cell::float(item.calories, kcal),
+                    ],
+                ]
+                .concat()
+            })
+            .collect()
+    }
+}
+
+/// Calories burned on one day next to those eaten
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct DailyBurn {
+    pub date: NaiveDate,
+    pub workouts: i32,
+    /// In minutes
+    pub duration: i32,
+    pub burned: f32,
+    pub eaten: f32,
+    /// Eaten less burned
+    pub net: f32,
+    /// Workouts left out of `burned` because no weight had been logged
+    pub uncounted: i32,
+}
+
+/// The totals of each day with a workout or food logged, oldest first
+pub fn daily_totals(exercises: &[Exercise], intake: &[DailyTotal]) -> Vec<DailyBurn> {
+    let empty = |date| DailyBurn {
+        date,
+        workouts: 0,
+        duration: 0,
+        burned: 0.0,
+        eaten: 0.0,
+        net: 0.0,
+        uncounted: 0,
+    };
+    let mut days: BTreeMap<NaiveDate, DailyBurn> = BTreeMap::new();
+    for exercise in exercises {
+        let day = days
+            .entry(exercise.date)
+            .or_insert_with(|| empty(exercise.date));
+        day.workouts += 1;
+        day.duration += exercise.duration;
+        match exercise.calories {
+            Some(calories) => day.burned += calories,
+            None => day.uncounted += 1,
+        }
+    }
+    for total in intake {
+        let day = days.entry(total.date).or_insert_with(|| empty(total.date));
+        day.eaten += total.nutrition.calories;
+    }
+    days.values()
+        .map(|day| DailyBurn {
+            net: day.eaten - day.burned,
+            ..*day
+        })
+        .collect()
+}
+
+impl Tabular for Vec<DailyBurn> {
+    fn columns(&self) -> Vec<Column> {
+        let calories = |key, name| Column::new(key, name, ValueType::Float).with_unit(Some("kcal"));
+        vec![
+            Column::new("date", "Date", ValueType::Date),
+            Column::new("workouts", "Workouts", ValueType::Int),
+            Column::new("duration", "Duration", ValueType::Int).with_unit(Some("min")),
+            calories("burned", "Burned"),
+            calories("eaten", "Eaten"),
+            calories("net", "Net"),
+            Column::new("uncounted", "Uncounted Workouts", ValueType::Int),
+        ]
+    }
+
+    fn rows(&self) -> Vec<Vec<Cell>> {
+        self.iter()
+            .map(|day| {
+                vec![
+                    Cell::date(day.date),
+                    Cell::int(day.workouts, day.workouts.to_string()),
+                    Cell::int(day.duration, format!("{} min", day.duration)),
+                    Cell::float(Some(day.burned), kcal),
+                    Cell::float(Some(day.eaten), kcal),
+                    Cell::float(Some(day.net), kcal),
+                    Cell::int(day.uncounted, day.uncounted.to_string()),
+                ]
+            })
+            .collect()
+    }
+}
+
+/// Activities and intensities are stored as their lowercase name
+#[cfg(feature = "database")]
+mod sql {
+    use super::*;
+
+    text_sql!(Activity);
+    text_sql!(Intensity);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::nutrition::Nutrition;
+
+    fn date(s: &str) -> NaiveDate {
+        s.parse().unwrap()
+    }
+
+    fn at(day: &str, time: &str) -> Timestamp {
+        Timestamp::new(date(day), Some(time.parse().unwrap()))
+    }
+
+    fn weight(id: i32, day: &str, time: &str, value: f32, unit: WeightUnit) -> Weight {
+        Weight {
+            id,
+            date: date(day),
+            time: Some(time.parse().unwrap()),
+            value,
+            unit,
+        }
+    }
+
+    fn workout(id: i32, day: &str, duration: i32, weights: &[Weight]) -> Exercise {
+        let new = NewExercise {
+            date: date(day),
+            time: None,
+            activity: Activity::Running,
+            duration,
+            intensity: Intensity::Moderate,
+            distance: None,
+            distance_unit: DistanceUnit::Km,
+        };
+        Exercise::new(id, new, weights)
+    }
+
+    #[test]
+    fn uses_the_first_weight_for_earlier_workouts() {
+        let weights = [
+            weight(2, "2021-02-10", "07:00:00", 78.0, WeightUnit::Kg),
+            weight(1, "2021-02-01", "07:00:00", 80.0, WeightUnit::Kg),
+        ];
+        assert_eq!(
+            body_weight(&weights, at("2021-01-20", "18:00:00")),
+            Some(80.0)
+        );
+        assert_eq!(body_weight(&[], at("2021-01-20", "18:00:00")), None);
+    }
+
+    #[test]
+    fn uses_the_latest_weight_before_a_workout() {
+        let weights = [
+            weight(1, "2021-02-01", "07:00:00", 80.0, WeightUnit::Kg),
+            weight(3, "2021-02-20", "07:00:00", 76.0, WeightUnit::Kg),
+            weight(2, "2021-02-10", "07:00:00", 78.0, WeightUnit::Kg),
+        ];
+        assert_eq!(
+            body_weight(&weights, at("2021-02-15", "18:00:00")),
+            Some(78.0)
+        );
+        assert_eq!(
+            body_weight(&weights, at("2021-02-10", "07:00:00")),
+            Some(78.0)
+        );
+    }
+
+    #[test]
+    fn converts_the_weight_to_kilograms() {
+        let weights = [weight(1, "2021-02-01", "07:00:00", 176.0, WeightUnit::Lb)];
+        let kg = body_weight(&weights, at("2021-02-02", "18:00:00")).unwrap();
+        assert!((kg - 79.832_26).abs() < 1e-3);
+
+        let run = workout(1, "2021-02-02", 30, &weights);
+        assert!((run.calories.unwrap() - 8.3 * kg / 2.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn totals_days_with_only_food_logged() {
+        let weights = [weight(1, "2021-02-01", "07:00:00", 80.0, WeightUnit::Kg)];
+        let exercises = [
+            workout(1, "2021-02-02", 60, &weights),
+            workout(2, "2021-02-02", 30, &weights),
+            workout(3, "2021-02-04", 30, &[]),
+        ];
+        let eaten = |day, calories| DailyTotal {
+            date: date(day),
+            entries: 1,
+            nutrition: Nutrition {
+                calories,
+                ..Nutrition::default()
+            },
+        };
+        let intake = [eaten("2021-02-03", 2000.0), eaten("2021-02-02", 2500.0)];
+
+        let days = daily_totals(&exercises, &intake);
+        let dates = days.iter().map(|x| x.date).collect::<Vec<_>>();
+        assert_eq!(
+            dates,
+            vec![date("2021-02-02"), date("2021-02-03"), date("2021-02-04")]
+        );
+
+        let burned = 8.3 * 80.0 * 1.5;
+        assert_eq!(days[0].workouts, 2);
+        assert_eq!(days[0].duration, 90);
+        assert!((days[0].burned - burned).abs() < 1e-3);
+        assert!((days[0].net - (2500.0 - burned)).abs() < 1e-3);
+
+        assert_eq!(days[1].workouts, 0);
+        assert_eq!(days[1].burned, 0.0);
+        assert_eq!(days[1].eaten, 2000.0);
+        assert_eq!(days[1].net, 2000.0);
+
+        assert_eq!(days[2].workouts, 1);
+        assert_eq!(days[2].uncounted, 1);
+        assert_eq!(days[2].eaten, 0.0);
+    }
+}
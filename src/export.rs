@@ -0,0 +1,240 @@
+use serde::ser::{Serialize, SerializeMap, Serializer};
+use std::fmt;
+use std::str::FromStr;
+
+use crate::column::{Cell, Column};
+use crate::Tabular;
+
+/// A file format any `Tabular` can be written in
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Format {
+    /// Raw values under a header of column keys, for spreadsheets
+    Csv,
+    /// The table as shown in the web app
+    Markdown,
+    /// One json object of raw values per row, for notebooks
+    JsonLines,
+}
+
+impl Format {
+    pub const ALL: [Format; 3] = [Format::Csv, Format::Markdown, Format::JsonLines];
+
+    pub fn extension(self) -> &'static str {
+        match self {
+            Format::Csv => "csv",
+            Format::Markdown => "md",
+            Format::JsonLines => "jsonl",
+        }
+    }
+
+    pub fn content_type(self) -> &'static str {
+        match self {
+            Format::Csv => "text/csv; charset=utf-8",
+            Format::Markdown => "text/markdown; charset=utf-8",
+            Format::JsonLines => "application/x-ndjson",
+        }
+    }
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "csv" => Ok(Format::Csv),
+            "md" | "markdown" => Ok(Format::Markdown),
+            "jsonl" | "jsonlines" => Ok(Format::JsonLines),
+            _ => Err(format!("unknown export format {}", s)),
+        }
+    }
+}
+
+impl fmt::Display for Format {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.extension())
+    }
+}
+
+/// Writes every row of `table` in `format`
+pub fn export<T: Tabular>(table: &T, format: Format) -> String {
+    let columns = table.columns();
+    let rows = table.rows();
+    match format {
+        Format::Csv => csv(&columns, &rows),
+        Format::Markdown => markdown(&columns, &rows),
+        Format::JsonLines => json_lines(&columns, &rows),
+    }
+}
+
+/// The header names a column by its key, followed by its unit if it has
+/// one, e.g. `value (kg)`
+fn csv(columns: &[Column], rows: &[Vec<Cell>]) -> String {
+    let header = columns.iter().map(|column| match &column.unit {
+        None => column.key.clone(),
+        Some(unit) => format!("{} ({})", column.key, unit),
+    });
+    let mut out = csv_line(header);
+    for row in rows {
+        let values = row.iter().map(|cell| match &cell.value {
+            None => String::new(),
+            Some(value) => value.to_string(),
+        });
+        out.push_str(&csv_line(values));
+    }
+    out
+}
+
+fn csv_line(fields: impl Iterator<Item = String>) -> String {
+    let fields = fields
+        .map(|field| {
+            if field.contains(&[',', '"', '\n', '\r'][..]) {
+                format!("\"{}\"", field.replace('"', "\"\""))
+            } else {
+                field
+            }
+        })
+        .collect::<Vec<String>>();
+    format!("{}\r\n", fields.join(","))
+}
+
+fn markdown(columns: &[Column], rows: &[Vec<Cell>]) -> String {
+    let header = columns.iter().map(|column| column.name.clone());
+    let rule = columns.iter().map(|column| {
+        if column.value_type.is_numeric() {
+            "---:".to_string()
+        } else {
+            "---".to_string()
+        }
+    });
+    let mut out = markdown_line(header);
+    out.push_str(&markdown_line(rule));
+    for row in rows {
+        out.push_str(&markdown_line(row.iter().map(|cell| cell.display.clone())));
+    }
+    out
+}
+
+fn markdown_line(cells: impl Iterator<Item = String>) -> String {
+    let cells = cells
+        .map(|cell| cell.replace('|', "\\|").replace('\n', " "))
+        .collect::<Vec<String>>();
+    format!("| {} |\n", cells.join(" | "))
+}
+
+fn json_lines(columns: &[Column], rows: &[Vec<Cell>]) -> String {
+    rows.iter()
+        .map(|cells| {
+            let record = Record { columns, cells };
+            let line = serde_json::to_string(&record).expect("a row always serializes");
+            format!("{}\n", line)
+        })
+        .collect()
+}
+
+/// A row as a json object keyed by column
+struct Record<'a> {
+    columns: &'a [Column],
+    cells: &'a [Cell],
+}
+
+impl Serialize for Record<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(Some(self.columns.len()))?;
+        for (column, cell) in self.columns.iter().zip(self.cells) {
+            map.serialize_entry(&column.key, &cell.value)?;
+        }
+        map.end()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::column::ValueType;
+
+    struct Note {
+        text: &'static str,
+        amount: Option<f32>,
+    }
+
+    impl Tabular for Vec<Note> {
+        fn columns(&self) -> Vec<Column> {
+            vec![
+                Column::new("text", "Text", ValueType::Text),
+                Column::new("amount", "Amount", ValueType::Float).with_unit(Some("g")),
+            ]
+        }
+
+        fn rows(&self) -> Vec<Vec<Cell>> {
+            self.iter()
+                .map(|x| {
+                    vec![
+                        Cell::text(x.text),
+                        Cell::float(x.amount, |v| format!("{} g", v)),
+                    ]
+                })
+                .collect()
+        }
+    }
+
+    fn notes() -> Vec<Note> {
+        vec![
+            Note {
+                text: "plain",
+                amount: Some(2.5),
+            },
+            Note {
+                text: "eggs, \"large\"\nfree range",
+                amount: None,
+            },
+            Note {
+                text: "salt | pepper",
+                amount: Some(1.0),
+            },
+        ]
+    }
+
+    #[test]
+    fn csv_quotes_fields_with_commas_quotes_and_newlines() {
+        let text = export(&notes(), Format::Csv);
+        assert_eq!(
+            text,
+            "text,amount (g)\r\n\
+             plain,2.5\r\n\
+             \"eggs, \"\"large\"\"\nfree range\",\r\n\
+             salt | pepper,1\r\n"
+        );
+
+        let mut reader = csv::Reader::from_reader(text.as_bytes());
+        let second = reader.records().nth(1).unwrap().unwrap();
+        assert_eq!(&second[0], "eggs, \"large\"\nfree range");
+        assert_eq!(&second[1], "");
+    }
+
+    #[test]
+    fn markdown_escapes_pipes_in_cells() {
+        let text = export(&notes(), Format::Markdown);
+        let lines = text.lines().collect::<Vec<&str>>();
+        assert_eq!(lines[0], "| Text | Amount |");
+        assert_eq!(lines[1], "| --- | ---: |");
+        assert_eq!(lines[2], "| plain | 2.5 g |");
+        assert_eq!(lines[3], "| eggs, \"large\" free range |  |");
+        assert_eq!(lines[4], "| salt \\| pepper | 1 g |");
+        assert_eq!(lines.len(), 5);
+    }
+
+    #[test]
+    fn json_lines_writes_one_object_per_row() {
+        let text = export(&notes(), Format::JsonLines);
+        let lines = text.lines().collect::<Vec<&str>>();
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[0], r#"{"text":"plain","amount":2.5}"#);
+        assert_eq!(
+            lines[1],
+            r#"{"text":"eggs, \"large\"\nfree range","amount":null}"#
+        );
+        let last: serde_json::Value = serde_json::from_str(lines[2]).unwrap();
+        assert_eq!(last["text"], "salt | pepper");
+        assert_eq!(last["amount"], 1.0);
+    }
+}
@@ -0,0 +1,201 @@
+#[cfg(feature = "database")]
+use crate::schema::food;
+
+use serde::{Deserialize, Serialize};
+
+use crate::column::{Cell, Column, ValueType};
+use crate::import::{Fields, Importable, Record};
+use crate::nutrition::{self, grams, kcal, Nutrition};
+use crate::units::Quantity;
+use crate::validate::{FieldError, Rules, Validate};
+use crate::Tabular;
+
+/// A food in the catalog.  Calories and macros are per serving.
+#[cfg_attr(
+    feature = "database",
+    derive(Insertable, AsChangeset),
+    table_name = "food",
+    changeset_options(treat_none_as_null = "true")
+)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct NewFood {
+    pub name: String,
+    pub brand: Option<String>,
+    pub serving_size: f32,
+    /// e.g. `g`, `ml` or `slice`
+    pub serving_unit: String,
+    pub calories: f32,
+    pub protein: Option<f32>,
+    pub carbs: Option<f32>,
+    pub fat: Option<f32>,
+    pub fiber: Option<f32>,
+}
+
+impl Validate for NewFood {
+    fn validate(&self) -> Result<(), Vec<FieldError>> {
+        let mut rules = Rules::new();
+        rules.text("name", &self.name);
+        if let Some(brand) = &self.brand {
+            rules.text("brand", brand);
+        }
+        rules.positive("serving_size", self.serving_size);
+        rules.text("serving_unit", &self.serving_unit);
+        nutrition::validate(
+            &mut rules,
+            self.calories,
+            [
+                ("protein", self.protein),
+                ("carbs", self.carbs),
+                ("fat", self.fat),
+                ("fiber", self.fiber),
+            ],
+        );
+        rules.finish()
+    }
+}
+
+#[cfg_attr(feature = "database", derive(Queryable))]
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Food {
+    pub id: i32,
+    pub name: String,
+    pub brand: Option<String>,
+    pub serving_size: f32,
+    pub serving_unit: String,
+    pub calories: f32,
+    pub protein: Option<f32>,
+    pub carbs: Option<f32>,
+    pub fat: Option<f32>,
+    pub fiber: Option<f32>,
+}
+
+impl Food {
+    /// What one serving holds
+    pub fn per_serving(&self) -> Nutrition {
+        Nutrition::new(
+            self.calories,
+            self.protein,
+            self.carbs,
+            self.fat,
+            self.fiber,
+        )
+    }
+
+    /// The name followed by the brand, to tell foods apart when picking one
+    pub fn label(&self) -> String {
+        match &self.brand {
+            None => self.name.clone(),
+            Some(brand) => format!("{} ({})", self.name, brand),
+        }
+    }
+
+    /// e.g. `40 g`
+    pub fn serving(&self) -> String {
+        format!("{} {}", self.serving_size, self.serving_unit)
+    }
+
+    /// How many servings `amount` of `unit` is.  Besides the serving unit
+    /// itself this takes `serving`, or any weight or volume when servings
+    /// are measured the same way.  `None` when the two cannot be compared,
+    /// e.g. grams of a food measured in slices.
+    pub fn servings_in(&self, amount: f32, unit: &str) -> Option<f32> {
+        let unit = unit.trim();
+        if unit.eq_ignore_ascii_case(self.serving_unit.trim()) {
+            return Some(amount / self.serving_size);
+        }
+        if unit.eq_ignore_ascii_case("serving") || unit.eq_ignore_ascii_case("servings") {
+            return Some(amount);
+        }
+        let serving = Quantity::new(self.serving_size, &self.serving_unit)?;
+        Quantity::new(amount, unit)?.ratio(serving)
+    }
+}
+
+impl From<Food> for NewFood {
+    fn from(food: Food) -> Self {
+        NewFood {
+            name: food.name,
+            brand: food.brand,
+            serving_size: food.serving_size,
+            serving_unit: food.serving_unit,
+            calories: food.calories,
+            protein: food.protein,
+            carbs: food.carbs,
+            fat: food.fat,
+            fiber: food.fiber,
+        }
+    }
+}
+
+impl Importable for NewFood {
+    fn from_record(record: &Record) -> Result<Self, Vec<FieldError>> {
+        let mut fields = Fields::new(record);
+        let name = fields.required("name");
+        let brand = fields.optional("brand");
+        let serving_size = fields.required("serving_size");
+        let serving_unit = fields.required("serving_unit");
+        let calories = fields.required("calories");
+        let protein = fields.optional("protein");
+        let carbs = fields.optional("carbs");
+        let fat = fields.optional("fat");
+        let fiber = fields.optional("fiber");
+        fields.finish(|| {
+            Some(NewFood {
+                name: name?,
+                brand: brand?,
+                serving_size: serving_size?,
+                serving_unit: serving_unit?,
+                calories: calories?,
+                protein: protein?,
+                carbs: carbs?,
+                fat: fat?,
+                fiber: fiber?,
+            })
+        })
+    }
+
+    /// The same name from the same brand is the same food
+    fn duplicate_key(&self) -> String {
+        let brand = self.brand.as_deref().unwrap_or_default();
+        format!(
+            "{} {}",
+            self.name.trim().to_lowercase(),
+            brand.trim().to_lowercase()
+        )
+    }
+}
+
+impl Tabular for Vec<Food> {
+    fn columns(&self) -> Vec<Column> {
+        let grams = |key, name| Column::new(key, name, ValueType::Float).with_unit(Some("g"));
+        vec![
+            Column::new("name", "Name", ValueType::Text),
+            Column::new("brand", "Brand", ValueType::Text),
+            Column::new("serving_size", "Serving", ValueType::Float),
+            Column::new("serving_unit", "Serving Unit", ValueType::Text),
+            Column::new("calories", "Calories", ValueType::Float).with_unit(Some("kcal")),
+            grams("protein", "Protein"),
+            grams("carbs", "Carbs"),
+            grams("fat", "Fat"),
+            grams("fiber", "Fiber"),
+        ]
+    }
+
+    fn rows(&self) -> Vec<Vec<Cell>> {
+        self.iter()
+            .map(|food| {
+                vec![
+                    Cell::text(&food.name),
+                    food.brand.as_deref().map_or_else(Cell::empty, Cell::text),
+                    Cell::float(Some(food.serving_size), |_| food.serving()),
+                    Cell::text(&food.serving_unit),
+                    Cell::float(Some(food.calories), kcal),
+                    Cell::float(food.protein, grams),
+                    Cell::float(food.carbs, grams),
+                    Cell::float(food.fat, grams),
+                    Cell::float(food.fiber, grams),
+                ]
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,474 @@
+#[cfg(feature = "database")]
+use crate::schema::food_log;
+#[cfg(feature = "database")]
+use diesel::sql_types::Text;
+
+use chrono::naive::{NaiveDate, NaiveTime};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+
+use crate::column::{Cell, Column, Value, ValueType};
+use crate::food::Food;
+use crate::import::{key_float, key_timestamp, Fields, Importable, Record};
+use crate::nutrition::{self, grams, kcal, Nutrition};
+use crate::recipe::Recipe;
+use crate::timestamp::{Timestamp, Timestamped};
+use crate::validate::{FieldError, Rules, Validate};
+use crate::Tabular;
+
+/// Which meal an entry was part of, stored as its lowercase name
+#[cfg_attr(
+    feature = "database",
+    derive(AsExpression, FromSqlRow),
+    sql_type = "Text"
+)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Meal {
+    Breakfast,
+    Lunch,
+    Dinner,
+    Snack,
+}
+
+impl Meal {
+    pub const ALL: [Meal; 4] = [Meal::Breakfast, Meal::Lunch, Meal::Dinner, Meal::Snack];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Meal::Breakfast => "breakfast",
+            Meal::Lunch => "lunch",
+            Meal::Dinner => "dinner",
+            Meal::Snack => "snack",
+        }
+    }
+}
+
+impl FromStr for Meal {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.to_lowercase();
+        Meal::ALL
+            .iter()
+            .copied()
+            .find(|x| x.name() == s)
+            .ok_or_else(|| format!("unknown meal {}", s))
+    }
+}
+
+impl fmt::Display for Meal {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Meal::Breakfast => write!(f, "Breakfast"),
+            Meal::Lunch => write!(f, "Lunch"),
+            Meal::Dinner => write!(f, "Dinner"),
+            Meal::Snack => write!(f, "Snack"),
+        }
+    }
+}
+
+/// Something eaten.  Calories and macros are per serving.  An entry for a
+/// food in the catalog or a recipe takes its name and nutrition from it, so
+/// they can be left out.
+#[cfg_attr(
+    feature = "database",
+    derive(Insertable, AsChangeset),
+    table_name = "food_log",
+    changeset_options(treat_none_as_null = "true")
+)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct NewFoodLog {
+    pub date: NaiveDate,
+    pub time: Option<NaiveTime>,
+    pub meal: Meal,
+    #[serde(default)]
+    pub name: String,
+    pub servings: f32,
+    #[serde(default)]
+    pub calories: f32,
+    pub protein: Option<f32>,
+    pub carbs: Option<f32>,
+    pub fat: Option<f32>,
+    pub fiber: Option<f32>,
+    pub food_id: Option<i32>,
+    pub recipe_id: Option<i32>,
+}
+
+impl NewFoodLog {
+    /// The entry with the name and nutrition of `food`
+    pub fn with_food(self, food: &Food) -> Self {
+        NewFoodLog {
+            name: food.name.clone(),
+            calories: food.calories,
+            protein: food.protein,
+            carbs: food.carbs,
+            fat: food.fat,
+            fiber: food.fiber,
+            ..self
+        }
+    }
+
+    /// The entry with the name and nutrition of a serving of `recipe`
+    pub fn with_recipe(self, recipe: &Recipe) -> Self {
+        let serving = recipe.per_serving;
+        NewFoodLog {
+            name: recipe.name.clone(),
+            calories: serving.calories,
+            protein: Some(serving.protein),
+            carbs: Some(serving.carbs),
+            fat: Some(serving.fat),
+            fiber: Some(serving.fiber),
+            ..self
+        }
+    }
+}
+
+impl Validate for NewFoodLog {
+    fn validate(&self) -> Result<(), Vec<FieldError>> {
+        let mut rules = Rules::new();
+        rules.not_future("date", self.date);
+        match (self.food_id, self.recipe_id) {
+            (None, None) => rules.text("name", &self.name),
+            (Some(_), Some(_)) => rules.check(false, "recipe_id", "cannot be given with a food"),
+            _ => {}
+        }
+        rules.positive("servings", self.servings);
+        nutrition::validate(
+            &mut rules,
+            self.calories,
+            [
+                ("protein", self.protein),
+                ("carbs", self.carbs),
+                ("fat", self.fat),
+                ("fiber", self.fiber),
+            ],
+        );
+        rules.finish()
+    }
+}
+
+#[cfg_attr(feature = "database", derive(Queryable))]
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct FoodLog {
+    pub id: i32,
+    pub date: NaiveDate,
+    pub time: Option<NaiveTime>,
+    pub meal: Meal,
+    pub name: String,
+    pub servings: f32,
+    pub calories: f32,
+    pub protein: Option<f32>,
+    pub carbs: Option<f32>,
+    pub fat: Option<f32>,
+    pub fiber: Option<f32>,
+    pub food_id: Option<i32>,
+    pub recipe_id: Option<i32>,
+}
+
+impl FoodLog {
+    /// The entry with the name and nutrition of `food`, as it is now
+    pub fn with_food(self, food: &Food) -> Self {
+        FoodLog {
+            name: food.name.clone(),
+            calories: food.calories,
+            protein: food.protein,
+            carbs: food.carbs,
+            fat: food.fat,
+            fiber: food.fiber,
+            ..self
+        }
+    }
+
+    /// The entry with the name and nutrition of a serving of `recipe`, as
+    /// the catalog has its ingredients now
+    pub fn with_recipe(self, recipe: &Recipe) -> Self {
+        let serving = recipe.per_serving;
+        FoodLog {
+            name: recipe.name.clone(),
+            calories: serving.calories,
+            protein: Some(serving.protein),
+            carbs: Some(serving.carbs),
+            fat: Some(serving.fat),
+            fiber: Some(serving.fiber),
+            ..self
+        }
+    }
+
+    /// What one serving holds
+    pub fn per_serving(&self) -> Nutrition {
+        Nutrition::new(
+            self.calories,
+            self.protein,
+            self.carbs,
+            self.fat,
+            self.fiber,
+        )
+    }
+
+    /// What was eaten in all
+    pub fn nutrition(&self) -> Nutrition {
+        self.per_serving().times(self.servings)
+    }
+}
+
+impl Timestamped for NewFoodLog {
+    fn timestamp(&self) -> Timestamp {
+        Timestamp::new(self.date, self.time)
+    }
+}
+
+impl Timestamped for FoodLog {
+    fn timestamp(&self) -> Timestamp {
+        Timestamp::new(self.date, self.time)
+    }
+}
+
+impl From<FoodLog> for NewFoodLog {
+    fn from(entry: FoodLog) -> Self {
+        NewFoodLog {
+            date: entry.date,
+            time: entry.time,
+            meal: entry.meal,
+            name: entry.name,
+            servings: entry.servings,
+            calories: entry.calories,
+            protein: entry.protein,
+            carbs: entry.carbs,
+            fat: entry.fat,
+            fiber: entry.fiber,
+            food_id: entry.food_id,
+            recipe_id: entry.recipe_id,
+        }
+    }
+}
+
+impl Importable for NewFoodLog {
+    fn from_record(record: &Record) -> Result<Self, Vec<FieldError>> {
+        let mut fields = Fields::new(record);
+        let date = fields.date("date");
+        let time = fields.time_option("time");
+        let meal = fields.required("meal");
+        let food_id = fields.optional("food_id");
+        let recipe_id = fields.optional("recipe_id");
+        // Entries for a catalog food or recipe get its name and calories when
+        // inserted
+        let name;
+        let calories;
+        if let (Some(Some(_)), _) | (_, Some(Some(_))) = (food_id, recipe_id) {
+            name = fields.optional("name").map(Option::unwrap_or_default);
+            calories = fields.optional("calories").map(Option::unwrap_or_default);
+        } else {
+            name = fields.required("name");
+            calories = fields.required("calories");
+        }
+        let servings = fields.optional("servings");
+        let protein = fields.optional("protein");
+        let carbs = fields.optional("carbs");
+        let fat = fields.optional("fat");
+        let fiber = fields.optional("fiber");
+        fields.finish(|| {
+            Some(NewFoodLog {
+                date: date?,
+                time: time?,
+                meal: meal?,
+                name: name?,
+                servings: servings?.unwrap_or(1.0),
+                calories: calories?,
+                protein: protein?,
+                carbs: carbs?,
+                fat: fat?,
+                fiber: fiber?,
+                food_id: food_id?,
+                recipe_id: recipe_id?,
+            })
+        })
+    }
+
+    fn duplicate_key(&self) -> String {
+        format!(
+            "{} {} {} {}",
+            key_timestamp(self.timestamp()),
+            self.meal.name(),
+            self.name.trim().to_lowercase(),
+            key_float(Some(self.servings))
+        )
+    }
+}
+
+impl Tabular for Vec<FoodLog> {
+    fn columns(&self) -> Vec<Column> {
+        let grams = |key, name| Column::new(key, name, ValueType::Float).with_unit(Some("g"));
+        [
+            Timestamp::columns(),
+            vec![
+                Column::new("meal", "Meal", ValueType::Text),
+                Column::new("name", "Food", ValueType::Text),
+                Column::new("servings", "Servings", ValueType::Float),
+                Column::new("calories", "Calories / Serving", ValueType::Float)
+                    .with_unit(Some("kcal")),
+                grams("protein", "Protein / Serving"),
+                grams("carbs", "Carbs / Serving"),
+                grams("fat", "Fat / Serving"),
+                grams("fiber", "Fiber / Serving"),
+                Column::new("total_calories", "Total Calories", ValueType::Float)
+                    .with_unit(Some("kcal")),
+            ],
+        ]
+        .concat()
+    }
+
+    fn rows(&self) -> Vec<Vec<Cell>> {
+        self.iter()
+            .map(|entry| {
+                [
+                    entry.timestamp().cells(),
+                    vec![
+                        Cell::new(
+                            entry.meal.to_string(),
+                            Some(Value::Text(entry.meal.name().to_string())),
+                        ),
+                        Cell::text(&entry.name),
+                        Cell::float(Some(entry.servings), |x| x.to_string()),
+                        Cell::float(Some(entry.calories), kcal),
+                        Cell::float(entry.protein, grams),
+                        Cell::float(entry.carbs, grams),
+                        Cell::float(entry.fat, grams),
+                        Cell::float(entry.fiber, grams),
+                        Cell::float(Some(entry.nutrition().calories), kcal),
+                    ],
+                ]
+                .concat()
+            })
+            .collect()
+    }
+}
+
+/// Everything eaten on one day
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct DailyTotal {
+    pub date: NaiveDate,
+    pub entries: i32,
+    #[serde(flatten)]
+    pub nutrition: Nutrition,
+}
+
+/// The totals of each day `entries` were logged on, oldest first
+pub fn daily_totals(entries: &[FoodLog]) -> Vec<DailyTotal> {
+    let mut days: BTreeMap<NaiveDate, DailyTotal> = BTreeMap::new();
+    for entry in entries {
+        let day = days.entry(entry.date).or_insert(DailyTotal {
+            date: entry.date,
+            entries: 0,
+            nutrition: Nutrition::default(),
+        });
+        day.entries += 1;
+        day.nutrition = day.nutrition + entry.nutrition();
+    }
+    days.values().copied().collect()
+}
+
+impl Tabular for Vec<DailyTotal> {
+    fn columns(&self) -> Vec<Column> {
+        let grams = |key, name| Column::new(key, name, ValueType::Float).with_unit(Some("g"));
+        vec![
+            Column::new("date", "Date", ValueType::Date),
+            Column::new("entries", "Entries", ValueType::Int),
+            Column::new("calories", "Calories", ValueType::Float).with_unit(Some("kcal")),
+            grams("protein", "Protein"),
+            grams("carbs", "Carbs"),
+            grams("fat", "Fat"),
+            grams("fiber", "Fiber"),
+        ]
+    }
+
+    fn rows(&self) -> Vec<Vec<Cell>> {
+        self.iter()
+            .map(|day| {
+                let total = day.nutrition;
+                vec![
+                    Cell::date(day.date),
+                    Cell::int(day.entries, day.entries.to_string()),
+                    Cell::float(Some(total.calories), kcal),
+                    Cell::float(Some(total.protein), grams),
+                    Cell::float(Some(total.carbs), grams),
+                    Cell::float(Some(total.fat), grams),
+                    Cell::float(Some(total.fiber), grams),
+                ]
+            })
+            .collect()
+    }
+}
+
+/// Meals are stored as their lowercase name
+#[cfg(feature = "database")]
+mod sql {
+    use super::*;
+
+    text_sql!(Meal);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(id: i32, date: &str, servings: f32, calories: f32, protein: Option<f32>) -> FoodLog {
+        FoodLog {
+            id,
+            date: date.parse().unwrap(),
+            time: None,
+            meal: Meal::Lunch,
+            name: format!("Food {}", id),
+            servings,
+            calories,
+            protein,
+            carbs: None,
+            fat: None,
+            fiber: None,
+            food_id: None,
+            recipe_id: None,
+        }
+    }
+
+    fn close(a: f32, b: f32) -> bool {
+        (a - b).abs() < 1e-3
+    }
+
+    #[test]
+    fn daily_totals_groups_by_day_oldest_first() {
+        let entries = vec![
+            entry(1, "2021-03-02", 1.0, 300.0, Some(10.0)),
+            entry(2, "2021-03-01", 1.0, 200.0, None),
+            entry(3, "2021-03-02", 1.0, 450.0, Some(25.0)),
+        ];
+        let days = daily_totals(&entries);
+        assert_eq!(days.len(), 2);
+        assert_eq!(days[0].date, "2021-03-01".parse().unwrap());
+        assert_eq!(days[0].entries, 1);
+        assert!(close(days[0].nutrition.calories, 200.0));
+        assert!(close(days[0].nutrition.protein, 0.0));
+        assert_eq!(days[1].date, "2021-03-02".parse().unwrap());
+        assert_eq!(days[1].entries, 2);
+        assert!(close(days[1].nutrition.calories, 750.0));
+        assert!(close(days[1].nutrition.protein, 35.0));
+    }
+
+    #[test]
+    fn daily_totals_scales_each_entry_by_its_servings() {
+        let entries = vec![
+            entry(1, "2021-03-01", 2.5, 100.0, Some(4.0)),
+            entry(2, "2021-03-01", 0.5, 300.0, Some(20.0)),
+        ];
+        let days = daily_totals(&entries);
+        assert_eq!(days.len(), 1);
+        assert!(close(days[0].nutrition.calories, 400.0));
+        assert!(close(days[0].nutrition.protein, 20.0));
+    }
+
+    #[test]
+    fn daily_totals_of_nothing_is_empty() {
+        assert!(daily_totals(&[]).is_empty());
+    }
+}
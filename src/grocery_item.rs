@@ -1,55 +1,124 @@
 #[cfg(feature = "database")]
 use crate::schema::grocery_item;
 
-use chrono::naive::{NaiveDate, NaiveTime};
 use serde::{Deserialize, Serialize};
 
+use crate::column::{common, Cell, Column, ValueType};
+use crate::import::{key_float, Fields, Importable, Record};
+use crate::validate::{FieldError, Rules, Validate};
 use crate::Tabular;
 
-#[cfg_attr(feature = "database", derive(Insertable), table_name = "grocery_item")]
+#[cfg_attr(
+    feature = "database",
+    derive(Insertable, AsChangeset),
+    table_name = "grocery_item",
+    changeset_options(treat_none_as_null = "true")
+)]
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct NewGroceryItem {
     pub trip_id: i32,
     pub name: String,
     pub amount: Option<f32>,
     pub measure: Option<String>,
+    pub food_id: Option<i32>,
 }
 
-#[cfg(feature = "database")]
-use diesel::types::*;
+impl Validate for NewGroceryItem {
+    fn validate(&self) -> Result<(), Vec<FieldError>> {
+        let mut rules = Rules::new();
+        rules.text("name", &self.name);
+        if let Some(amount) = self.amount {
+            rules.positive("amount", amount);
+        }
+        if let Some(measure) = &self.measure {
+            rules.text("measure", measure);
+        }
+        rules.finish()
+    }
+}
 
 #[cfg_attr(feature = "database", derive(Queryable))]
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct GroceryItem {
     pub id: i32,
+    pub trip_id: i32,
     pub trip_desc: String,
     pub name: String,
     pub amount: Option<f32>,
     pub measure: Option<String>,
+    pub food_id: Option<i32>,
+}
+
+impl From<GroceryItem> for NewGroceryItem {
+    fn from(item: GroceryItem) -> Self {
+        NewGroceryItem {
+            trip_id: item.trip_id,
+            name: item.name,
+            amount: item.amount,
+            measure: item.measure,
+            food_id: item.food_id,
+        }
+    }
+}
+
+impl Importable for NewGroceryItem {
+    fn from_record(record: &Record) -> Result<Self, Vec<FieldError>> {
+        let mut fields = Fields::new(record);
+        let trip_id = fields.required("trip_id");
+        let name = fields.required("name");
+        let amount = fields.optional("amount");
+        let measure = fields.optional("measure");
+        let food_id = fields.optional("food_id");
+        fields.finish(|| {
+            Some(NewGroceryItem {
+                trip_id: trip_id?,
+                name: name?,
+                amount: amount?,
+                measure: measure?,
+                food_id: food_id?,
+            })
+        })
+    }
+
+    fn duplicate_key(&self) -> String {
+        format!(
+            "{} {} {} {}",
+            self.trip_id,
+            self.name.trim().to_lowercase(),
+            key_float(self.amount),
+            self.measure.as_deref().unwrap_or_default(),
+        )
+    }
 }
 
 impl Tabular for Vec<GroceryItem> {
-    fn headers(&self) -> Vec<String> {
-        let v = vec!["Trip", "Name", "Amount"];
-        v.iter().map(|x| x.to_string()).collect()
+    /// Includes the ids and measure `NewGroceryItem::from_record` reads, so an
+    /// export can be imported again
+    fn columns(&self) -> Vec<Column> {
+        let measure = common(self.iter().map(|x| x.measure.as_deref()));
+        vec![
+            Column::new("trip_id", "Trip Id", ValueType::Int),
+            Column::new("trip_desc", "Trip", ValueType::Text),
+            Column::new("name", "Name", ValueType::Text),
+            Column::new("amount", "Amount", ValueType::Float).with_unit(measure.flatten()),
+            Column::new("measure", "Measure", ValueType::Text),
+            Column::new("food_id", "Food Id", ValueType::Int),
+        ]
     }
 
-    fn matrix(&self) -> Vec<Vec<String>> {
+    fn rows(&self) -> Vec<Vec<Cell>> {
         self.iter()
             .map(|item| {
-                let amount = match &item.amount {
-                    None => String::new(),
-                    Some(val) => match &item.measure {
-                        None => format!("{}", val),
-                        Some(msr) => format!("{} {}", val, msr),
-                    }
-                };
                 vec![
-                    item.trip_desc.clone(),
-                    item.name.clone(),
-                    amount,
+                    Cell::int(item.trip_id, item.trip_id.to_string()),
+                    Cell::text(&item.trip_desc),
+                    Cell::text(&item.name),
+                    Cell::float(item.amount, |val| val.to_string()),
+                    item.measure.as_deref().map_or_else(Cell::empty, Cell::text),
+                    item.food_id
+                        .map_or_else(Cell::empty, |id| Cell::int(id, id.to_string())),
                 ]
             })
-            .collect::<Vec<Vec<String>>>()
+            .collect()
     }
 }
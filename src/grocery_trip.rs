@@ -4,9 +4,18 @@ use crate::schema::grocery_trip;
 use chrono::naive::{NaiveDate, NaiveTime};
 use serde::{Deserialize, Serialize};
 
+use crate::column::{Cell, Column, ValueType};
+use crate::import::{key_timestamp, Fields, Importable, Record};
+use crate::timestamp::{Timestamp, Timestamped};
+use crate::validate::{FieldError, Rules, Validate};
 use crate::Tabular;
 
-#[cfg_attr(feature = "database", derive(Insertable), table_name = "grocery_trip")]
+#[cfg_attr(
+    feature = "database",
+    derive(Insertable, AsChangeset),
+    table_name = "grocery_trip",
+    changeset_options(treat_none_as_null = "true")
+)]
 #[derive(Clone, Copy, Debug, Serialize, Deserialize)]
 pub struct NewGroceryTrip {
     pub date: NaiveDate,
@@ -14,34 +23,78 @@ pub struct NewGroceryTrip {
     pub store_id: i32,
 }
 
+impl Validate for NewGroceryTrip {
+    fn validate(&self) -> Result<(), Vec<FieldError>> {
+        let mut rules = Rules::new();
+        rules.not_future("date", self.date);
+        rules.finish()
+    }
+}
+
 #[cfg_attr(feature = "database", derive(Queryable))]
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct GroceryTrip {
     pub id: i32,
     pub date: NaiveDate,
     pub time: Option<NaiveTime>,
+    pub store_id: i32,
     pub store_name: String,
 }
 
+impl Timestamped for NewGroceryTrip {
+    fn timestamp(&self) -> Timestamp {
+        Timestamp::new(self.date, self.time)
+    }
+}
+
+impl Timestamped for GroceryTrip {
+    fn timestamp(&self) -> Timestamp {
+        Timestamp::new(self.date, self.time)
+    }
+}
+
+impl From<GroceryTrip> for NewGroceryTrip {
+    fn from(trip: GroceryTrip) -> Self {
+        NewGroceryTrip {
+            date: trip.date,
+            time: trip.time,
+            store_id: trip.store_id,
+        }
+    }
+}
+
+impl Importable for NewGroceryTrip {
+    fn from_record(record: &Record) -> Result<Self, Vec<FieldError>> {
+        let mut fields = Fields::new(record);
+        let date = fields.date("date");
+        let time = fields.time_option("time");
+        let store_id = fields.required("store_id");
+        fields.finish(|| {
+            Some(NewGroceryTrip {
+                date: date?,
+                time: time?,
+                store_id: store_id?,
+            })
+        })
+    }
+
+    fn duplicate_key(&self) -> String {
+        format!("{} {}", key_timestamp(self.timestamp()), self.store_id)
+    }
+}
+
 impl Tabular for Vec<GroceryTrip> {
-    fn headers(&self) -> Vec<String> {
-        let v = vec!["Date", "Time", "Scale"];
-        v.iter().map(|x| x.to_string()).collect()
+    fn columns(&self) -> Vec<Column> {
+        [
+            Timestamp::columns(),
+            vec![Column::new("store_name", "Store", ValueType::Text)],
+        ]
+        .concat()
     }
 
-    fn matrix(&self) -> Vec<Vec<String>> {
+    fn rows(&self) -> Vec<Vec<Cell>> {
         self.iter()
-            .map(|trip| {
-                let time = match trip.time {
-                    None => String::new(),
-                    Some(t) => t.format("%-I:%M %p").to_string(),
-                };
-                vec![
-                    trip.date.format("%b %d %Y").to_string(),
-                    time,
-                    trip.store_name.to_string(),
-                ]
-            })
-            .collect::<Vec<Vec<String>>>()
+            .map(|trip| [trip.timestamp().cells(), vec![Cell::text(&trip.store_name)]].concat())
+            .collect()
     }
 }
@@ -0,0 +1,379 @@
+use chrono::naive::{NaiveDate, NaiveTime};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fmt::Display;
+use std::str::FromStr;
+
+use crate::timestamp::Timestamp;
+use crate::validate::{FieldError, Validate};
+
+/// Date formats tried in order, e.g. `2021-01-31`, `1/31/21` or `Jan 31 2021`.
+/// `%Y` reads any number of digits, so two digit years are tried first.
+const DATE_FORMATS: [&str; 8] = [
+    "%Y-%m-%d",
+    "%m/%d/%y",
+    "%m/%d/%Y",
+    "%Y/%m/%d",
+    "%d.%m.%Y",
+    "%b %d %Y",
+    "%b %d, %Y",
+    "%B %d, %Y",
+];
+
+/// Time formats tried in order, e.g. `13:05`, `13:05:00` or `1:05 PM`
+const TIME_FORMATS: [&str; 5] = [
+    "%H:%M",
+    "%H:%M:%S",
+    "%H:%M:%S%.f",
+    "%I:%M %p",
+    "%I:%M:%S %p",
+];
+
+/// A `New*` row that can be read from a line of CSV
+pub trait Importable: Validate + Sized {
+    fn from_record(record: &Record) -> Result<Self, Vec<FieldError>>;
+
+    /// Rows with the same key are the same entry, so only the first is kept
+    fn duplicate_key(&self) -> String;
+}
+
+/// One line of CSV, keyed by its header.  Headers are matched without
+/// regard to case, and may be followed by a unit as in exports, e.g.
+/// `value (kg)`.
+#[derive(Debug)]
+pub struct Record {
+    fields: HashMap<String, String>,
+    units: HashMap<String, String>,
+}
+
+impl Record {
+    fn new(headers: &[(String, Option<String>)], values: &csv::StringRecord) -> Self {
+        let mut fields = HashMap::new();
+        let mut units = HashMap::new();
+        for ((key, unit), value) in headers.iter().zip(values.iter()) {
+            fields.insert(key.clone(), value.to_string());
+            if let Some(unit) = unit {
+                units.insert(key.clone(), unit.clone());
+            }
+        }
+        Record { fields, units }
+    }
+
+    /// The value under `key`, if it is present and not blank
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.fields
+            .get(key)
+            .map(|x| x.trim())
+            .filter(|x| !x.is_empty())
+    }
+
+    /// The unit given in the header of `key`
+    pub fn unit(&self, key: &str) -> Option<&str> {
+        self.units.get(key).map(|x| x.as_str())
+    }
+}
+
+/// Splits a header into its key and unit, e.g. `Body Fat (%)` into
+/// `body_fat` and `%`
+fn header(s: &str) -> (String, Option<String>) {
+    let (name, unit) = match (s.find('('), s.ends_with(')')) {
+        (Some(i), true) => (&s[..i], Some(s[i + 1..s.len() - 1].trim().to_string())),
+        _ => (s, None),
+    };
+    let key = name.trim().to_lowercase().replace(' ', "_");
+    (key, unit)
+}
+
+/// Reads the fields of a `Record`, collecting every field that cannot be
+/// read rather than stopping at the first
+pub struct Fields<'a> {
+    record: &'a Record,
+    errors: Vec<FieldError>,
+}
+
+impl<'a> Fields<'a> {
+    pub fn new(record: &'a Record) -> Self {
+        Fields {
+            record,
+            errors: Vec::new(),
+        }
+    }
+
+    fn error(&mut self, field: &str, message: &str) {
+        self.errors.push(field_error(field, message));
+    }
+
+    /// A value that must be present
+    pub fn required<T: FromStr>(&mut self, key: &str) -> Option<T>
+    where
+        T::Err: Display,
+    {
+        match self.optional(key) {
+            Some(None) => {
+                self.error(key, "is required");
+                None
+            }
+            value => value.flatten(),
+        }
+    }
+
+    /// A value that may be left blank
+    pub fn optional<T: FromStr>(&mut self, key: &str) -> Option<Option<T>>
+    where
+        T::Err: Display,
+    {
+        self.parse_with(key, |s| s.parse().map_err(|e: T::Err| e.to_string()))
+    }
+
+    pub fn date(&mut self, key: &str) -> Option<NaiveDate> {
+        let date = self.parse_with(key, |s| {
+            DATE_FORMATS
+                .iter()
+                .find_map(|f| NaiveDate::parse_from_str(s, f).ok())
+                .ok_or_else(|| "not a known date format".to_string())
+        });
+        match date {
+            Some(None) => {
+                self.error(key, "is required");
+                None
+            }
+            date => date.flatten(),
+        }
+    }
+
+    pub fn time(&mut self, key: &str) -> Option<NaiveTime> {
+        match self.time_option(key) {
+            Some(None) => {
+                self.error(key, "is required");
+                None
+            }
+            time => time.flatten(),
+        }
+    }
+
+    pub fn time_option(&mut self, key: &str) -> Option<Option<NaiveTime>> {
+        self.parse_with(key, |s| {
+            TIME_FORMATS
+                .iter()
+                .find_map(|f| NaiveTime::parse_from_str(s, f).ok())
+                .ok_or_else(|| "not a known time format".to_string())
+        })
+    }
+
+    /// The unit in the column `key`, or else in the header of the first of
+    /// `value_keys` that has one
+    pub fn unit<U: FromStr>(&mut self, key: &str, value_keys: &[&str]) -> Option<U> {
+        let record = self.record;
+        let unit = record
+            .get(key)
+            .or_else(|| value_keys.iter().find_map(|x| record.unit(x)));
+        match unit.map(|x| x.parse()) {
+            Some(Ok(unit)) => Some(unit),
+            Some(Err(_)) => {
+                self.error(key, "is not a known unit");
+                None
+            }
+            None => {
+                self.error(key, "is required");
+                None
+            }
+        }
+    }
+
+    /// `None` if the value could not be parsed, otherwise `Some` of the
+    /// value if it was present
+    fn parse_with<T>(
+        &mut self,
+        key: &str,
+        parse: impl Fn(&str) -> Result<T, String>,
+    ) -> Option<Option<T>> {
+        match self.record.get(key) {
+            None => Some(None),
+            Some(s) => match parse(s) {
+                Ok(value) => Some(Some(value)),
+                Err(e) => {
+                    self.error(key, &format!("cannot read {:?}: {}", s, e));
+                    None
+                }
+            },
+        }
+    }
+
+    /// The row built by `build`, which only sees fields that were all read
+    pub fn finish<T>(self, build: impl FnOnce() -> Option<T>) -> Result<T, Vec<FieldError>> {
+        if !self.errors.is_empty() {
+            return Err(self.errors);
+        }
+        build().ok_or_else(Vec::new)
+    }
+}
+
+/// The fields that stopped a line from being imported
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RowError {
+    pub line: usize,
+    pub errors: Vec<FieldError>,
+}
+
+/// What happened to each line of an import, by line number
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct ImportReport {
+    /// Nothing was written, `added` lists the lines that would have been
+    pub dry_run: bool,
+    pub added: Vec<usize>,
+    /// Lines matching an existing row or an earlier line
+    pub duplicates: Vec<usize>,
+    pub errors: Vec<RowError>,
+}
+
+impl ImportReport {
+    pub fn error(&mut self, line: usize, field: &str, message: &str) {
+        self.errors.push(RowError {
+            line,
+            errors: vec![field_error(field, message)],
+        });
+    }
+}
+
+/// A CSV file read and checked, but not yet written
+pub struct Import<N> {
+    /// The rows to add, with their line numbers
+    pub rows: Vec<(usize, N)>,
+    pub report: ImportReport,
+}
+
+/// Reads every line of `text`, setting aside lines that cannot be read or
+/// are invalid, and lines that duplicate one of `existing` or each other
+pub fn prepare<N: Importable>(text: &str, existing: impl IntoIterator<Item = N>) -> Import<N> {
+    let mut seen = existing
+        .into_iter()
+        .map(|x| x.duplicate_key())
+        .collect::<HashSet<String>>();
+    let mut import = Import {
+        rows: Vec::new(),
+        report: ImportReport::default(),
+    };
+
+    let mut reader = csv::ReaderBuilder::new()
+        .trim(csv::Trim::All)
+        .from_reader(text.as_bytes());
+    let headers = match reader.headers() {
+        Ok(headers) => headers.iter().map(header).collect::<Vec<_>>(),
+        Err(e) => {
+            import.report.error(1, "header", &e.to_string());
+            return import;
+        }
+    };
+
+    for (i, result) in reader.records().enumerate() {
+        let position = match &result {
+            Ok(values) => values.position(),
+            Err(e) => e.position(),
+        };
+        // Quoted fields can span lines, so count from the header only as a
+        // last resort
+        let line = position.map_or(i + 2, |x| x.line() as usize);
+        let item = result
+            .map_err(|e| vec![field_error("line", &e.to_string())])
+            .and_then(|values| N::from_record(&Record::new(&headers, &values)))
+            .and_then(|item| item.validate().map(|()| item));
+        match item {
+            Err(errors) => import.report.errors.push(RowError { line, errors }),
+            Ok(item) => {
+                if seen.insert(item.duplicate_key()) {
+                    import.rows.push((line, item));
+                } else {
+                    import.report.duplicates.push(line);
+                }
+            }
+        }
+    }
+    import
+}
+
+fn field_error(field: &str, message: &str) -> FieldError {
+    FieldError {
+        field: field.to_string(),
+        message: message.to_string(),
+    }
+}
+
+/// A float written for a `duplicate_key`, rounded so that converting units
+/// back and forth does not make a row look new
+pub fn key_float(value: Option<f32>) -> String {
+    value.map(|x| format!("{:.1}", x)).unwrap_or_default()
+}
+
+/// A timestamp written for a `duplicate_key`, to the minute
+pub fn key_timestamp(timestamp: Timestamp) -> String {
+    let time = timestamp.time.map(|t| t.format("%H:%M").to_string());
+    format!("{} {}", timestamp.date, time.unwrap_or_default())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::units::WeightUnit;
+    use crate::weight::NewWeight;
+
+    fn fields(import: &Import<NewWeight>) -> Vec<(usize, Vec<&str>)> {
+        import
+            .report
+            .errors
+            .iter()
+            .map(|x| (x.line, x.errors.iter().map(|e| e.field.as_str()).collect()))
+            .collect()
+    }
+
+    #[test]
+    fn reads_a_valid_row() {
+        let import = prepare::<NewWeight>("date,time,value (lb)\n1/31/21,7:30 AM,180.5\n", vec![]);
+        assert!(import.report.errors.is_empty());
+        assert_eq!(import.rows.len(), 1);
+        let (line, weight) = &import.rows[0];
+        assert_eq!(*line, 2);
+        assert_eq!(weight.date, NaiveDate::from_ymd_opt(2021, 1, 31).unwrap());
+        assert_eq!(weight.time, NaiveTime::from_hms_opt(7, 30, 0));
+        assert_eq!(weight.value, 180.5);
+        assert_eq!(weight.unit, WeightUnit::Lb);
+    }
+
+    #[test]
+    fn rejects_a_missing_field() {
+        let import = prepare::<NewWeight>("date,value,unit\n2021-01-31,,kg\n", vec![]);
+        assert!(import.rows.is_empty());
+        assert_eq!(fields(&import), vec![(2, vec!["value"])]);
+    }
+
+    #[test]
+    fn rejects_a_bad_unit() {
+        let import = prepare::<NewWeight>("date,value,unit\n2021-01-31,80,stone\n", vec![]);
+        assert!(import.rows.is_empty());
+        assert_eq!(fields(&import), vec![(2, vec!["unit"])]);
+    }
+
+    #[test]
+    fn skips_a_line_repeated_in_the_file() {
+        let text = "date,value,unit\n2021-01-31,80,kg\n2021-02-01,79.5,kg\n2021-01-31,80,kg\n";
+        let import = prepare::<NewWeight>(text, vec![]);
+        assert_eq!(
+            import.rows.iter().map(|x| x.0).collect::<Vec<_>>(),
+            vec![2, 3]
+        );
+        assert_eq!(import.report.duplicates, vec![4]);
+    }
+
+    #[test]
+    fn skips_a_line_matching_an_existing_row() {
+        let existing = NewWeight {
+            date: NaiveDate::from_ymd_opt(2021, 1, 31).unwrap(),
+            time: None,
+            value: 80.0,
+            unit: WeightUnit::Kg,
+        };
+        let text = "date,value (lb)\n2021-01-31,176.37\n2021-02-01,176\n";
+        let import = prepare::<NewWeight>(text, vec![existing]);
+        assert_eq!(import.rows.iter().map(|x| x.0).collect::<Vec<_>>(), vec![3]);
+        assert_eq!(import.report.duplicates, vec![2]);
+    }
+}
@@ -3,36 +3,184 @@
 extern crate diesel;
 
 use convert_case::{Case, Casing};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+use crate::column::{Cell, Column};
 
 #[cfg(feature = "database")]
+#[macro_use]
 pub mod db;
 
 #[cfg(feature = "database")]
 pub use db::schema;
 
+pub mod api_error;
 pub mod bowel;
+pub mod column;
+pub mod composition;
+pub mod dependents;
+pub mod exercise;
+pub mod export;
+pub mod food;
+pub mod food_log;
 pub mod grocery_trip;
 pub mod grocery_item;
+pub mod import;
+pub mod listing;
 pub mod metric;
+pub mod nutrition;
+pub mod recipe;
+pub mod recipe_ingredient;
+pub mod schema_status;
+pub mod sleep;
+pub mod timestamp;
+pub mod trend;
+pub mod units;
+pub mod validate;
+pub mod water;
 pub mod weight;
 pub mod store;
 
+/// Rows that can be shown as a table.  Each row has one `Cell` per `Column`.
 pub trait Tabular: std::default::Default {
-    fn headers(&self) -> Vec<String>;
-    fn matrix(&self) -> Vec<Vec<String>>;
+    fn columns(&self) -> Vec<Column>;
+    fn rows(&self) -> Vec<Vec<Cell>>;
+
+    fn headers(&self) -> Vec<String> {
+        self.columns().into_iter().map(|x| x.name).collect()
+    }
+
+    fn matrix(&self) -> Vec<Vec<String>> {
+        self.rows()
+            .into_iter()
+            .map(|row| row.into_iter().map(|x| x.display).collect())
+            .collect()
+    }
 }
 
-#[derive(Debug, Clone, Copy)]
+/// Every table that can be tracked.  The api, cli and web app build their
+/// lists of tables from `TableType::ALL`, and the api finds each table's row
+/// type with `api::db::visit`.  The database schema still lists them itself,
+/// since diesel generates it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum TableType {
     Bowel,
+    Exercise,
+    Food,
+    FoodLog,
+    GroceryItem,
     GroceryTrip,
     Metric,
+    Recipe,
+    RecipeIngredient,
+    Sleep,
+    Water,
     Weight,
     Store,
 }
 
 impl TableType {
+    /// In the order they are offered to users
+    pub const ALL: [TableType; 13] = [
+        TableType::FoodLog,
+        TableType::Food,
+        TableType::Recipe,
+        TableType::RecipeIngredient,
+        TableType::Water,
+        TableType::Sleep,
+        TableType::Exercise,
+        TableType::Bowel,
+        TableType::Store,
+        TableType::GroceryTrip,
+        TableType::GroceryItem,
+        TableType::Metric,
+        TableType::Weight,
+    ];
+
     pub fn snake_case(&self) -> String {
         format!("{:?}", self).to_case(Case::Snake)
     }
+
+    pub fn display_name(&self) -> &'static str {
+        use TableType::*;
+        match self {
+            Bowel => "Bowel Movements",
+            Food => "Foods",
+            FoodLog => "Food Log",
+            GroceryItem => "Grocery Items",
+            GroceryTrip => "Grocery Trips",
+            Metric => "Body Metrics",
+            Recipe => "Recipes",
+            RecipeIngredient => "Recipe Ingredients",
+            Exercise => "Exercise",
+            Sleep => "Sleep",
+            Water => "Water",
+            Weight => "Weight",
+            Store => "Grocery Stores",
+        }
+    }
+
+    /// Where the table is served, relative to the api's root
+    pub fn path(&self) -> String {
+        format!("/{}", self.snake_case())
+    }
+
+    /// The table's columns, without units since those depend on the rows
+    pub fn columns(&self) -> Vec<Column> {
+        use TableType::*;
+        match self {
+            Bowel => Vec::<bowel::Bowel>::new().columns(),
+            Food => Vec::<food::Food>::new().columns(),
+            FoodLog => Vec::<food_log::FoodLog>::new().columns(),
+            GroceryItem => Vec::<grocery_item::GroceryItem>::new().columns(),
+            GroceryTrip => Vec::<grocery_trip::GroceryTrip>::new().columns(),
+            Metric => Vec::<metric::Metric>::new().columns(),
+            Recipe => Vec::<recipe::Recipe>::new().columns(),
+            RecipeIngredient => Vec::<recipe_ingredient::RecipeIngredient>::new().columns(),
+            Exercise => Vec::<exercise::Exercise>::new().columns(),
+            Sleep => Vec::<sleep::Sleep>::new().columns(),
+            Water => Vec::<water::Water>::new().columns(),
+            Weight => Vec::<weight::Weight>::new().columns(),
+            Store => Vec::<store::Store>::new().columns(),
+        }
+    }
+
+    pub fn info(&self) -> TableInfo {
+        TableInfo {
+            table: *self,
+            display_name: self.display_name().to_string(),
+            path: self.path(),
+            columns: self.columns(),
+        }
+    }
+}
+
+impl FromStr for TableType {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        TableType::ALL
+            .iter()
+            .find(|x| x.snake_case() == s.to_lowercase())
+            .copied()
+            .ok_or_else(|| format!("unknown table {}", s))
+    }
+}
+
+impl fmt::Display for TableType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.snake_case())
+    }
+}
+
+/// Describes a table to clients
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TableInfo {
+    pub table: TableType,
+    pub display_name: String,
+    pub path: String,
+    pub columns: Vec<Column>,
 }
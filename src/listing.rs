@@ -0,0 +1,63 @@
+use chrono::naive::NaiveDate;
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+/// Filtering, ordering and paging for the rows of a table.  Rows are
+/// ordered by date and time, or by name for tables without a date.
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize)]
+pub struct ListQuery {
+    pub from: Option<NaiveDate>,
+    pub to: Option<NaiveDate>,
+    pub order: Order,
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+}
+
+impl ListQuery {
+    /// The limit and offset to apply, if paging was asked for at all.
+    /// Both MySQL and SQLite need a limit before an offset, so an offset
+    /// alone is paired with an unbounded limit.
+    pub fn page(&self) -> Option<(i64, i64)> {
+        match (self.limit, self.offset) {
+            (None, None) => None,
+            (limit, offset) => Some((limit.unwrap_or(i64::MAX), offset.unwrap_or(0))),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Order {
+    Asc,
+    /// Newest entries first
+    #[default]
+    Desc,
+}
+
+impl FromStr for Order {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "asc" => Ok(Order::Asc),
+            "desc" => Ok(Order::Desc),
+            _ => Err(format!("unknown order {}", s)),
+        }
+    }
+}
+
+/// One page of rows, along with how many rows matched before paging
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Listing<T> {
+    pub total: i64,
+    pub items: Vec<T>,
+}
+
+impl<T> Default for Listing<T> {
+    fn default() -> Self {
+        Listing {
+            total: 0,
+            items: Vec::new(),
+        }
+    }
+}
@@ -4,9 +4,19 @@ use crate::schema::metric;
 use chrono::naive::{NaiveDate, NaiveTime};
 use serde::{Deserialize, Serialize};
 
+use crate::column::{common, Cell, Column, ValueType};
+use crate::import::{key_float, key_timestamp, Fields, Importable, Record};
+use crate::timestamp::{Timestamp, Timestamped};
+use crate::units::{with_suffix, LengthUnit, UnitSystem};
+use crate::validate::{FieldError, Rules, Validate};
 use crate::Tabular;
 
-#[cfg_attr(feature = "database", derive(Insertable), table_name = "metric")]
+#[cfg_attr(
+    feature = "database",
+    derive(Insertable, AsChangeset),
+    table_name = "metric",
+    changeset_options(treat_none_as_null = "true")
+)]
 #[derive(Clone, Copy, Debug, Serialize, Deserialize)]
 pub struct NewMetric {
     pub date: NaiveDate,
@@ -16,6 +26,29 @@ pub struct NewMetric {
     pub waist_circum: Option<f32>,
     pub chest_circum: Option<f32>,
     pub thigh_circum: Option<f32>,
+    pub length_unit: LengthUnit,
+}
+
+impl Validate for NewMetric {
+    fn validate(&self) -> Result<(), Vec<FieldError>> {
+        let mut rules = Rules::new();
+        rules.not_future("date", self.date);
+        if let Some(body_fat) = self.body_fat {
+            rules.range("body_fat", body_fat, 0.0, 100.0);
+        }
+        let circums = vec![
+            ("gut_circum", self.gut_circum),
+            ("waist_circum", self.waist_circum),
+            ("chest_circum", self.chest_circum),
+            ("thigh_circum", self.thigh_circum),
+        ];
+        for (field, value) in circums {
+            if let Some(value) = value {
+                rules.positive(field, value);
+            }
+        }
+        rules.finish()
+    }
 }
 
 #[cfg_attr(feature = "database", derive(Queryable))]
@@ -29,48 +62,132 @@ pub struct Metric {
     pub waist_circum: Option<f32>,
     pub chest_circum: Option<f32>,
     pub thigh_circum: Option<f32>,
+    pub length_unit: LengthUnit,
+}
+
+impl Metric {
+    /// The same measurements with the circumferences converted to `units`
+    pub fn in_units(self, units: UnitSystem) -> Self {
+        let unit = units.length();
+        let convert = |x: Option<f32>| x.map(|x| self.length_unit.convert(x, unit));
+        Metric {
+            gut_circum: convert(self.gut_circum),
+            waist_circum: convert(self.waist_circum),
+            chest_circum: convert(self.chest_circum),
+            thigh_circum: convert(self.thigh_circum),
+            length_unit: unit,
+            ..self
+        }
+    }
+}
+
+impl Timestamped for NewMetric {
+    fn timestamp(&self) -> Timestamp {
+        Timestamp::new(self.date, self.time)
+    }
+}
+
+impl Timestamped for Metric {
+    fn timestamp(&self) -> Timestamp {
+        Timestamp::new(self.date, self.time)
+    }
+}
+
+impl From<Metric> for NewMetric {
+    fn from(metric: Metric) -> Self {
+        NewMetric {
+            date: metric.date,
+            time: metric.time,
+            body_fat: metric.body_fat,
+            gut_circum: metric.gut_circum,
+            waist_circum: metric.waist_circum,
+            chest_circum: metric.chest_circum,
+            thigh_circum: metric.thigh_circum,
+            length_unit: metric.length_unit,
+        }
+    }
+}
+
+const CIRCUMS: [&str; 4] = ["gut_circum", "waist_circum", "chest_circum", "thigh_circum"];
+
+impl Importable for NewMetric {
+    fn from_record(record: &Record) -> Result<Self, Vec<FieldError>> {
+        let mut fields = Fields::new(record);
+        let date = fields.date("date");
+        let time = fields.time_option("time");
+        let body_fat = fields.optional("body_fat");
+        let gut_circum = fields.optional("gut_circum");
+        let waist_circum = fields.optional("waist_circum");
+        let chest_circum = fields.optional("chest_circum");
+        let thigh_circum = fields.optional("thigh_circum");
+        // The unit only matters when there is a circumference to go with it
+        let length_unit = if CIRCUMS.iter().any(|x| record.get(x).is_some()) {
+            fields.unit("length_unit", &CIRCUMS)
+        } else {
+            Some(LengthUnit::Cm)
+        };
+        fields.finish(|| {
+            Some(NewMetric {
+                date: date?,
+                time: time?,
+                body_fat: body_fat?,
+                gut_circum: gut_circum?,
+                waist_circum: waist_circum?,
+                chest_circum: chest_circum?,
+                thigh_circum: thigh_circum?,
+                length_unit: length_unit?,
+            })
+        })
+    }
+
+    /// Circumferences are compared in centimeters
+    fn duplicate_key(&self) -> String {
+        let cm = |x: Option<f32>| key_float(x.map(|x| self.length_unit.convert(x, LengthUnit::Cm)));
+        format!(
+            "{} {} {} {} {} {}",
+            key_timestamp(self.timestamp()),
+            key_float(self.body_fat),
+            cm(self.gut_circum),
+            cm(self.waist_circum),
+            cm(self.chest_circum),
+            cm(self.thigh_circum),
+        )
+    }
 }
 
 impl Tabular for Vec<Metric> {
-    fn headers(&self) -> Vec<String> {
-        let v = vec![
-            "Date",
-            "Time",
-            "Body Fat %",
-            "Gut",
-            "Waist",
-            "Chest",
-            "Thigh",
-        ];
-        v.iter().map(|x| x.to_string()).collect()
+    fn columns(&self) -> Vec<Column> {
+        let unit = common(self.iter().map(|x| x.length_unit)).map(|x| x.suffix());
+        [
+            Timestamp::columns(),
+            vec![
+                Column::new("body_fat", "Body Fat %", ValueType::Float).with_unit(Some("%")),
+                Column::new("gut_circum", "Gut", ValueType::Float).with_unit(unit),
+                Column::new("waist_circum", "Waist", ValueType::Float).with_unit(unit),
+                Column::new("chest_circum", "Chest", ValueType::Float).with_unit(unit),
+                Column::new("thigh_circum", "Thigh", ValueType::Float).with_unit(unit),
+            ],
+        ]
+        .concat()
     }
 
-    fn matrix(&self) -> Vec<Vec<String>> {
+    fn rows(&self) -> Vec<Vec<Cell>> {
         self.iter()
             .map(|metric| {
-                let time = match metric.time {
-                    None => String::new(),
-                    Some(t) => t.format("%-I:%M %p").to_string(),
-                };
-                vec![
-                    metric.date.format("%b %d %Y").to_string(),
-                    time,
-                    metric.body_fat.map(|x| x.to_string()).unwrap_or_default(),
-                    metric.gut_circum.map(|x| x.to_string()).unwrap_or_default(),
-                    metric
-                        .waist_circum
-                        .map(|x| x.to_string())
-                        .unwrap_or_default(),
-                    metric
-                        .chest_circum
-                        .map(|x| x.to_string())
-                        .unwrap_or_default(),
-                    metric
-                        .thigh_circum
-                        .map(|x| x.to_string())
-                        .unwrap_or_default(),
+                let circum =
+                    |x: Option<f32>| Cell::float(x, |x| with_suffix(x, metric.length_unit));
+                [
+                    metric.timestamp().cells(),
+                    vec![
+                        Cell::float(metric.body_fat, |x| x.to_string()),
+                        circum(metric.gut_circum),
+                        circum(metric.waist_circum),
+                        circum(metric.chest_circum),
+                        circum(metric.thigh_circum),
+                    ],
                 ]
+                .concat()
             })
-            .collect::<Vec<Vec<String>>>()
+            .collect()
     }
 }
@@ -0,0 +1,85 @@
+use serde::{Deserialize, Serialize};
+use std::iter::Sum;
+use std::ops::Add;
+
+use crate::validate::Rules;
+
+/// Energy in kilocalories, and the rest in grams.  Amounts left out when
+/// they were logged count as 0.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Nutrition {
+    pub calories: f32,
+    pub protein: f32,
+    pub carbs: f32,
+    pub fat: f32,
+    pub fiber: f32,
+}
+
+impl Nutrition {
+    pub fn new(
+        calories: f32,
+        protein: Option<f32>,
+        carbs: Option<f32>,
+        fat: Option<f32>,
+        fiber: Option<f32>,
+    ) -> Self {
+        Nutrition {
+            calories,
+            protein: protein.unwrap_or_default(),
+            carbs: carbs.unwrap_or_default(),
+            fat: fat.unwrap_or_default(),
+            fiber: fiber.unwrap_or_default(),
+        }
+    }
+
+    /// The nutrition of `servings` of this
+    pub fn times(self, servings: f32) -> Self {
+        Nutrition {
+            calories: self.calories * servings,
+            protein: self.protein * servings,
+            carbs: self.carbs * servings,
+            fat: self.fat * servings,
+            fiber: self.fiber * servings,
+        }
+    }
+}
+
+impl Add for Nutrition {
+    type Output = Nutrition;
+
+    fn add(self, other: Nutrition) -> Nutrition {
+        Nutrition {
+            calories: self.calories + other.calories,
+            protein: self.protein + other.protein,
+            carbs: self.carbs + other.carbs,
+            fat: self.fat + other.fat,
+            fiber: self.fiber + other.fiber,
+        }
+    }
+}
+
+impl Sum for Nutrition {
+    fn sum<I: Iterator<Item = Nutrition>>(iter: I) -> Self {
+        iter.fold(Nutrition::default(), Add::add)
+    }
+}
+
+/// Checks the calories and any macros that were given
+pub fn validate(rules: &mut Rules, calories: f32, macros: [(&str, Option<f32>); 4]) {
+    rules.not_negative("calories", calories);
+    for (field, value) in macros.iter() {
+        if let Some(value) = value {
+            rules.not_negative(field, *value);
+        }
+    }
+}
+
+/// Grams, rounded for display
+pub fn grams(value: f32) -> String {
+    format!("{:.1} g", value)
+}
+
+/// Kilocalories, rounded for display
+pub fn kcal(value: f32) -> String {
+    format!("{:.0} kcal", value)
+}
@@ -0,0 +1,205 @@
+#[cfg(feature = "database")]
+use crate::schema::recipe;
+
+use serde::{Deserialize, Serialize};
+
+use crate::column::{Cell, Column, ValueType};
+use crate::import::{Fields, Importable, Record};
+use crate::nutrition::{grams, kcal, Nutrition};
+use crate::recipe_ingredient::RecipeIngredient;
+use crate::validate::{FieldError, Rules, Validate};
+use crate::Tabular;
+
+/// A dish cooked in batches.  Its ingredients are in `recipe_ingredient`.
+#[cfg_attr(
+    feature = "database",
+    derive(Insertable, AsChangeset),
+    table_name = "recipe"
+)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct NewRecipe {
+    pub name: String,
+    /// How many servings a batch makes
+    pub servings: f32,
+}
+
+impl Validate for NewRecipe {
+    fn validate(&self) -> Result<(), Vec<FieldError>> {
+        let mut rules = Rules::new();
+        rules.text("name", &self.name);
+        rules.positive("servings", self.servings);
+        rules.finish()
+    }
+}
+
+/// A recipe with the nutrition of its ingredients, as the catalog has them
+/// now
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Recipe {
+    pub id: i32,
+    pub name: String,
+    pub servings: f32,
+    pub per_serving: Nutrition,
+    /// Ingredients left out of `per_serving` because their amount could not
+    /// be compared with their food's serving
+    pub uncounted: i32,
+}
+
+impl Recipe {
+    pub fn new(id: i32, recipe: NewRecipe, ingredients: &[RecipeIngredient]) -> Self {
+        let batch: Nutrition = ingredients.iter().filter_map(|x| x.nutrition).sum();
+        let uncounted = ingredients.iter().filter(|x| x.nutrition.is_none()).count();
+        Recipe {
+            id,
+            name: recipe.name,
+            servings: recipe.servings,
+            per_serving: batch.times(1.0 / recipe.servings),
+            uncounted: uncounted as i32,
+        }
+    }
+}
+
+impl From<Recipe> for NewRecipe {
+    fn from(recipe: Recipe) -> Self {
+        NewRecipe {
+            name: recipe.name,
+            servings: recipe.servings,
+        }
+    }
+}
+
+impl Importable for NewRecipe {
+    fn from_record(record: &Record) -> Result<Self, Vec<FieldError>> {
+        let mut fields = Fields::new(record);
+        let name = fields.required("name");
+        let servings = fields.required("servings");
+        fields.finish(|| {
+            Some(NewRecipe {
+                name: name?,
+                servings: servings?,
+            })
+        })
+    }
+
+    fn duplicate_key(&self) -> String {
+        self.name.trim().to_lowercase()
+    }
+}
+
+impl Tabular for Vec<Recipe> {
+    fn columns(&self) -> Vec<Column> {
+        let grams = |key, name| Column::new(key, name, ValueType::Float).with_unit(Some("g"));
+        vec![
+            Column::new("name", "Name", ValueType::Text),
+            Column::new("servings", "Servings", ValueType::Float),
+            Column::new("calories", "Calories / Serving", ValueType::Float).with_unit(Some("kcal")),
+            grams("protein", "Protein / Serving"),
+            grams("carbs", "Carbs / Serving"),
+            grams("fat", "Fat / Serving"),
+            grams("fiber", "Fiber / Serving"),
+            Column::new("uncounted", "Uncounted Ingredients", ValueType::Int),
+        ]
+    }
+
+    fn rows(&self) -> Vec<Vec<Cell>> {
+        self.iter()
+            .map(|recipe| {
+                let serving = recipe.per_serving;
+                vec![
+                    Cell::text(&recipe.name),
+                    Cell::float(Some(recipe.servings), |x| x.to_string()),
+                    Cell::float(Some(serving.calories), kcal),
+                    Cell::float(Some(serving.protein), grams),
+                    Cell::float(Some(serving.carbs), grams),
+                    Cell::float(Some(serving.fat), grams),
+                    Cell::float(Some(serving.fiber), grams),
+                    Cell::int(recipe.uncounted, recipe.uncounted.to_string()),
+                ]
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ingredient(id: i32, nutrition: Option<Nutrition>) -> RecipeIngredient {
+        RecipeIngredient {
+            id,
+            recipe_id: 1,
+            recipe_name: "Chili".to_string(),
+            food_id: id,
+            food_name: format!("Food {}", id),
+            amount: 1.0,
+            unit: "cup".to_string(),
+            nutrition,
+        }
+    }
+
+    fn close(a: f32, b: f32) -> bool {
+        (a - b).abs() < 1e-3
+    }
+
+    #[test]
+    fn new_divides_the_batch_by_its_servings() {
+        let recipe = NewRecipe {
+            name: "Chili".to_string(),
+            servings: 4.0,
+        };
+        let ingredients = vec![
+            ingredient(
+                1,
+                Some(Nutrition::new(
+                    400.0,
+                    Some(20.0),
+                    Some(60.0),
+                    Some(8.0),
+                    Some(12.0),
+                )),
+            ),
+            ingredient(
+                2,
+                Some(Nutrition::new(200.0, Some(4.0), None, Some(16.0), None)),
+            ),
+            ingredient(3, None),
+        ];
+        let recipe = Recipe::new(7, recipe, &ingredients);
+        let serving = recipe.per_serving;
+        assert_eq!(recipe.id, 7);
+        assert!(close(recipe.servings, 4.0));
+        assert!(close(serving.calories, 150.0));
+        assert!(close(serving.protein, 6.0));
+        assert!(close(serving.carbs, 15.0));
+        assert!(close(serving.fat, 6.0));
+        assert!(close(serving.fiber, 3.0));
+        assert_eq!(recipe.uncounted, 1);
+    }
+
+    #[test]
+    fn new_with_a_fractional_serving_count() {
+        let recipe = NewRecipe {
+            name: "Dressing".to_string(),
+            servings: 0.5,
+        };
+        let ingredients = vec![ingredient(
+            1,
+            Some(Nutrition::new(120.0, None, None, Some(14.0), None)),
+        )];
+        let recipe = Recipe::new(1, recipe, &ingredients);
+        assert!(close(recipe.per_serving.calories, 240.0));
+        assert!(close(recipe.per_serving.fat, 28.0));
+        assert_eq!(recipe.uncounted, 0);
+    }
+
+    #[test]
+    fn new_without_ingredients_is_empty() {
+        let recipe = NewRecipe {
+            name: "Water".to_string(),
+            servings: 2.0,
+        };
+        let recipe = Recipe::new(1, recipe, &[]);
+        assert_eq!(recipe.per_serving, Nutrition::default());
+        assert_eq!(recipe.uncounted, 0);
+    }
+}
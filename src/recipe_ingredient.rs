@@ -0,0 +1,132 @@
+#[cfg(feature = "database")]
+use crate::schema::recipe_ingredient;
+
+use serde::{Deserialize, Serialize};
+
+use crate::column::{Cell, Column, ValueType};
+use crate::food::Food;
+use crate::import::{key_float, Fields, Importable, Record};
+use crate::nutrition::{kcal, Nutrition};
+use crate::validate::{FieldError, Rules, Validate};
+use crate::Tabular;
+
+/// An amount of a catalog food that goes into a batch of a recipe, e.g.
+/// `2 tbsp` of olive oil
+#[cfg_attr(
+    feature = "database",
+    derive(Insertable, AsChangeset),
+    table_name = "recipe_ingredient"
+)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct NewRecipeIngredient {
+    pub recipe_id: i32,
+    pub food_id: i32,
+    pub amount: f32,
+    pub unit: String,
+}
+
+impl Validate for NewRecipeIngredient {
+    fn validate(&self) -> Result<(), Vec<FieldError>> {
+        let mut rules = Rules::new();
+        rules.positive("amount", self.amount);
+        rules.text("unit", &self.unit);
+        rules.finish()
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RecipeIngredient {
+    pub id: i32,
+    pub recipe_id: i32,
+    pub recipe_name: String,
+    pub food_id: i32,
+    pub food_name: String,
+    pub amount: f32,
+    pub unit: String,
+    /// What the amount holds, or `None` when the unit cannot be compared
+    /// with the food's serving
+    pub nutrition: Option<Nutrition>,
+}
+
+impl RecipeIngredient {
+    pub fn new(id: i32, ingredient: NewRecipeIngredient, recipe_name: String, food: &Food) -> Self {
+        let nutrition = food
+            .servings_in(ingredient.amount, &ingredient.unit)
+            .map(|servings| food.per_serving().times(servings));
+        RecipeIngredient {
+            id,
+            recipe_id: ingredient.recipe_id,
+            recipe_name,
+            food_id: ingredient.food_id,
+            food_name: food.label(),
+            amount: ingredient.amount,
+            unit: ingredient.unit,
+            nutrition,
+        }
+    }
+}
+
+impl From<RecipeIngredient> for NewRecipeIngredient {
+    fn from(ingredient: RecipeIngredient) -> Self {
+        NewRecipeIngredient {
+            recipe_id: ingredient.recipe_id,
+            food_id: ingredient.food_id,
+            amount: ingredient.amount,
+            unit: ingredient.unit,
+        }
+    }
+}
+
+impl Importable for NewRecipeIngredient {
+    fn from_record(record: &Record) -> Result<Self, Vec<FieldError>> {
+        let mut fields = Fields::new(record);
+        let recipe_id = fields.required("recipe_id");
+        let food_id = fields.required("food_id");
+        let amount = fields.required("amount");
+        let unit = fields.required("unit");
+        fields.finish(|| {
+            Some(NewRecipeIngredient {
+                recipe_id: recipe_id?,
+                food_id: food_id?,
+                amount: amount?,
+                unit: unit?,
+            })
+        })
+    }
+
+    fn duplicate_key(&self) -> String {
+        format!(
+            "{} {} {} {}",
+            self.recipe_id,
+            self.food_id,
+            key_float(Some(self.amount)),
+            self.unit.trim().to_lowercase()
+        )
+    }
+}
+
+impl Tabular for Vec<RecipeIngredient> {
+    fn columns(&self) -> Vec<Column> {
+        vec![
+            Column::new("recipe_name", "Recipe", ValueType::Text),
+            Column::new("food_name", "Food", ValueType::Text),
+            Column::new("amount", "Amount", ValueType::Float),
+            Column::new("unit", "Unit", ValueType::Text),
+            Column::new("calories", "Calories", ValueType::Float).with_unit(Some("kcal")),
+        ]
+    }
+
+    fn rows(&self) -> Vec<Vec<Cell>> {
+        self.iter()
+            .map(|ingredient| {
+                vec![
+                    Cell::text(&ingredient.recipe_name),
+                    Cell::text(&ingredient.food_name),
+                    Cell::float(Some(ingredient.amount), |x| x.to_string()),
+                    Cell::text(&ingredient.unit),
+                    Cell::float(ingredient.nutrition.map(|x| x.calories), kcal),
+                ]
+            })
+            .collect()
+    }
+}
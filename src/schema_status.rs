@@ -0,0 +1,10 @@
+use serde::{Deserialize, Serialize};
+
+/// Which migrations the database has had applied
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct SchemaStatus {
+    /// Version of the latest applied migration, if any
+    pub version: Option<String>,
+    /// Names of embedded migrations that have not been applied, oldest first
+    pub pending: Vec<String>,
+}
@@ -0,0 +1,273 @@
+#[cfg(feature = "database")]
+use crate::schema::sleep;
+
+use chrono::naive::{NaiveDate, NaiveDateTime, NaiveTime};
+use chrono::{Datelike, Duration};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use crate::column::{Cell, Column, ValueType};
+use crate::import::{key_timestamp, Fields, Importable, Record};
+use crate::timestamp::{Timestamp, Timestamped};
+use crate::validate::{FieldError, Rules, Validate};
+use crate::Tabular;
+
+/// A night's sleep.  `date` is the day of `bed_time`, and a `wake_time` at
+/// or before it is on the next day.
+#[cfg_attr(
+    feature = "database",
+    derive(Insertable, AsChangeset),
+    table_name = "sleep"
+)]
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct NewSleep {
+    pub date: NaiveDate,
+    pub bed_time: NaiveTime,
+    pub wake_time: NaiveTime,
+    /// From 1, poor, to 5, great
+    pub quality: i32,
+}
+
+impl Validate for NewSleep {
+    fn validate(&self) -> Result<(), Vec<FieldError>> {
+        let mut rules = Rules::new();
+        rules.not_future("date", self.date);
+        rules.check(
+            self.wake_time != self.bed_time,
+            "wake_time",
+            "cannot be the same as bed time",
+        );
+        rules.range("quality", self.quality, 1, 5);
+        rules.finish()
+    }
+}
+
+#[cfg_attr(feature = "database", derive(Queryable))]
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct Sleep {
+    pub id: i32,
+    pub date: NaiveDate,
+    pub bed_time: NaiveTime,
+    pub wake_time: NaiveTime,
+    pub quality: i32,
+}
+
+impl Sleep {
+    pub fn bed(&self) -> NaiveDateTime {
+        self.date.and_time(self.bed_time)
+    }
+
+    /// When the night ended, which may be the day after `date`
+    pub fn wake(&self) -> NaiveDateTime {
+        self.bed() + self.duration()
+    }
+
+    pub fn duration(&self) -> Duration {
+        duration(self.bed_time, self.wake_time)
+    }
+}
+
+/// The time from `bed` until the next `wake`
+fn duration(bed: NaiveTime, wake: NaiveTime) -> Duration {
+    let time = wake - bed;
+    if time > Duration::zero() {
+        time
+    } else {
+        time + Duration::days(1)
+    }
+}
+
+/// e.g. `7h 05m`
+pub fn hours_minutes(minutes: i64) -> String {
+    format!("{}h {:02}m", minutes / 60, minutes % 60)
+}
+
+impl Timestamped for NewSleep {
+    fn timestamp(&self) -> Timestamp {
+        Timestamp::new(self.date, Some(self.bed_time))
+    }
+}
+
+impl Timestamped for Sleep {
+    fn timestamp(&self) -> Timestamp {
+        Timestamp::new(self.date, Some(self.bed_time))
+    }
+}
+
+impl From<Sleep> for NewSleep {
+    fn from(sleep: Sleep) -> Self {
+        NewSleep {
+            date: sleep.date,
+            bed_time: sleep.bed_time,
+            wake_time: sleep.wake_time,
+            quality: sleep.quality,
+        }
+    }
+}
+
+impl Importable for NewSleep {
+    fn from_record(record: &Record) -> Result<Self, Vec<FieldError>> {
+        let mut fields = Fields::new(record);
+        let date = fields.date("date");
+        let bed_time = fields.time("bed_time");
+        let wake_time = fields.time("wake_time");
+        let quality = fields.required("quality");
+        fields.finish(|| {
+            Some(NewSleep {
+                date: date?,
+                bed_time: bed_time?,
+                wake_time: wake_time?,
+                quality: quality?,
+            })
+        })
+    }
+
+    /// Only one night starts at a given time
+    fn duplicate_key(&self) -> String {
+        key_timestamp(self.timestamp())
+    }
+}
+
+impl Tabular for Vec<Sleep> {
+    fn columns(&self) -> Vec<Column> {
+        vec![
+            Column::new("date", "Date", ValueType::Date),
+            Column::new("bed_time", "Bed Time", ValueType::Time),
+            Column::new("wake_time", "Wake Time", ValueType::Time),
+            Column::new("duration", "Duration", ValueType::Int).with_unit(Some("min")),
+            Column::new("quality", "Quality", ValueType::Int),
+        ]
+    }
+
+    fn rows(&self) -> Vec<Vec<Cell>> {
+        self.iter()
+            .map(|night| {
+                let minutes = night.duration().num_minutes();
+                vec![
+                    Cell::date(night.date),
+                    Cell::time(Some(night.bed_time)),
+                    Cell::time(Some(night.wake_time)),
+                    Cell::int(minutes as i32, hours_minutes(minutes)),
+                    Cell::int(night.quality, format!("{} / 5", night.quality)),
+                ]
+            })
+            .collect()
+    }
+}
+
+/// The nights of one week, which starts on Monday
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct WeeklySleep {
+    pub week: NaiveDate,
+    pub nights: i32,
+    /// In minutes
+    pub average_duration: f32,
+    pub average_quality: f32,
+}
+
+/// The averages of each week `nights` were logged in, oldest first.  Nights
+/// count toward the week they started in.
+pub fn weekly_averages(nights: &[Sleep]) -> Vec<WeeklySleep> {
+    let mut weeks: BTreeMap<NaiveDate, Vec<&Sleep>> = BTreeMap::new();
+    for night in nights {
+        let monday =
+            night.date - Duration::days(night.date.weekday().num_days_from_monday() as i64);
+        weeks.entry(monday).or_default().push(night);
+    }
+    weeks
+        .into_iter()
+        .map(|(week, nights)| {
+            let count = nights.len() as f32;
+            let minutes: i64 = nights.iter().map(|x| x.duration().num_minutes()).sum();
+            let quality: i32 = nights.iter().map(|x| x.quality).sum();
+            WeeklySleep {
+                week,
+                nights: nights.len() as i32,
+                average_duration: minutes as f32 / count,
+                average_quality: quality as f32 / count,
+            }
+        })
+        .collect()
+}
+
+impl Tabular for Vec<WeeklySleep> {
+    fn columns(&self) -> Vec<Column> {
+        vec![
+            Column::new("week", "Week Of", ValueType::Date),
+            Column::new("nights", "Nights", ValueType::Int),
+            Column::new("average_duration", "Average Duration", ValueType::Float)
+                .with_unit(Some("min")),
+            Column::new("average_quality", "Average Quality", ValueType::Float),
+        ]
+    }
+
+    fn rows(&self) -> Vec<Vec<Cell>> {
+        self.iter()
+            .map(|week| {
+                vec![
+                    Cell::date(week.week),
+                    Cell::int(week.nights, week.nights.to_string()),
+                    Cell::float(Some(week.average_duration), |x| {
+                        hours_minutes(x.round() as i64)
+                    }),
+                    Cell::float(Some(week.average_quality), |x| format!("{:.1} / 5", x)),
+                ]
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn night(id: i32, date: &str, bed: &str, wake: &str, quality: i32) -> Sleep {
+        Sleep {
+            id,
+            date: date.parse().unwrap(),
+            bed_time: bed.parse().unwrap(),
+            wake_time: wake.parse().unwrap(),
+            quality,
+        }
+    }
+
+    #[test]
+    fn a_night_ends_the_next_day() {
+        let sleep = night(1, "2021-01-31", "23:15:00", "07:00:00", 4);
+        assert_eq!(sleep.duration(), Duration::minutes(7 * 60 + 45));
+        assert_eq!(sleep.wake(), "2021-02-01T07:00:00".parse().unwrap());
+        assert_eq!(hours_minutes(sleep.duration().num_minutes()), "7h 45m");
+    }
+
+    #[test]
+    fn a_nap_ends_the_same_day() {
+        let nap = night(1, "2021-01-31", "13:30:00", "14:05:00", 3);
+        assert_eq!(nap.duration(), Duration::minutes(35));
+        assert_eq!(nap.wake(), "2021-01-31T14:05:00".parse().unwrap());
+    }
+
+    #[test]
+    fn nights_count_toward_the_week_they_start_in() {
+        let nights = [
+            // Sunday night into Monday is still the week before
+            night(1, "2021-02-07", "23:00:00", "07:00:00", 2),
+            night(2, "2021-02-01", "22:00:00", "06:00:00", 4),
+            night(3, "2021-02-08", "23:00:00", "06:00:00", 5),
+            night(4, "2021-02-03", "00:30:00", "06:30:00", 3),
+        ];
+        let weeks = weekly_averages(&nights);
+        assert_eq!(weeks.len(), 2);
+
+        let first = weeks[0];
+        assert_eq!(first.week, "2021-02-01".parse().unwrap());
+        assert_eq!(first.nights, 3);
+        assert_eq!(first.average_duration, (8.0 + 8.0 + 6.0) * 60.0 / 3.0);
+        assert_eq!(first.average_quality, 3.0);
+
+        let second = weeks[1];
+        assert_eq!(second.week, "2021-02-08".parse().unwrap());
+        assert_eq!(second.nights, 1);
+        assert_eq!(second.average_duration, 7.0 * 60.0);
+        assert_eq!(second.average_quality, 5.0);
+    }
+}
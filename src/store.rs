@@ -3,14 +3,30 @@ use crate::schema::store;
 
 use serde::{Deserialize, Serialize};
 
+use crate::column::{Cell, Column, ValueType};
+use crate::import::{Fields, Importable, Record};
+use crate::validate::{FieldError, Rules, Validate};
 use crate::Tabular;
 
-#[cfg_attr(feature = "database", derive(Insertable), table_name = "store")]
+#[cfg_attr(
+    feature = "database",
+    derive(Insertable, AsChangeset),
+    table_name = "store",
+    changeset_options(treat_none_as_null = "true")
+)]
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct NewStore {
     pub name: String,
 }
 
+impl Validate for NewStore {
+    fn validate(&self) -> Result<(), Vec<FieldError>> {
+        let mut rules = Rules::new();
+        rules.text("name", &self.name);
+        rules.finish()
+    }
+}
+
 #[cfg_attr(feature = "database", derive(Queryable))]
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Store {
@@ -18,14 +34,32 @@ pub struct Store {
     pub name: String,
 }
 
+impl From<Store> for NewStore {
+    fn from(store: Store) -> Self {
+        NewStore { name: store.name }
+    }
+}
+
+impl Importable for NewStore {
+    fn from_record(record: &Record) -> Result<Self, Vec<FieldError>> {
+        let mut fields = Fields::new(record);
+        let name = fields.required("name");
+        fields.finish(|| Some(NewStore { name: name? }))
+    }
+
+    fn duplicate_key(&self) -> String {
+        self.name.trim().to_lowercase()
+    }
+}
+
 impl Tabular for Vec<Store> {
-    fn headers(&self) -> Vec<String> {
-        vec!["Name".to_string()]
+    fn columns(&self) -> Vec<Column> {
+        vec![Column::new("name", "Name", ValueType::Text)]
     }
 
-    fn matrix(&self) -> Vec<Vec<String>> {
+    fn rows(&self) -> Vec<Vec<Cell>> {
         self.iter()
-            .map(|store| vec![store.name.clone()])
-            .collect::<Vec<Vec<String>>>()
+            .map(|store| vec![Cell::text(&store.name)])
+            .collect()
     }
 }
@@ -0,0 +1,164 @@
+use chrono::naive::{NaiveDate, NaiveDateTime, NaiveTime};
+use chrono::{DateTime, Local, TimeZone};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+use crate::column::{Cell, Column, ValueType};
+
+/// How dates are shown, e.g. `Jan 31 2021`
+pub const DATE_FORMAT: &str = "%b %d %Y";
+
+/// How times are shown, e.g. `1:05 PM`
+pub const TIME_FORMAT: &str = "%-I:%M %p";
+
+/// When an entry was logged: a date, and the time of day if one was given.
+/// Both are the wall clock of whoever logged it, which is how they are
+/// stored.
+///
+/// Timestamps order by date and then time, with entries that have no time
+/// first on their day, the same way the database orders rows.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct Timestamp {
+    pub date: NaiveDate,
+    pub time: Option<NaiveTime>,
+}
+
+/// A row logged at a point in time
+pub trait Timestamped {
+    fn timestamp(&self) -> Timestamp;
+}
+
+impl Timestamp {
+    pub fn new(date: NaiveDate, time: Option<NaiveTime>) -> Self {
+        Timestamp { date, time }
+    }
+
+    /// The current date and time on this machine's clock
+    pub fn now() -> Self {
+        Self::from_datetime(&Local::now())
+    }
+
+    /// The date and time on the clock of `at`'s timezone
+    pub fn from_datetime<Tz: TimeZone>(at: &DateTime<Tz>) -> Self {
+        let local = at.naive_local();
+        Timestamp::new(local.date(), Some(local.time()))
+    }
+
+    /// The instant this was logged, read on the clock of `tz`.  `None` when
+    /// that time was skipped by a daylight saving change.
+    pub fn to_datetime<Tz: TimeZone>(self, tz: &Tz) -> Option<DateTime<Tz>> {
+        tz.from_local_datetime(&self.moment()).earliest()
+    }
+
+    /// The date and time, taking entries without a time to be from midday
+    pub fn moment(self) -> NaiveDateTime {
+        let noon = NaiveTime::from_hms_opt(12, 0, 0).expect("midday is a valid time");
+        self.date.and_time(self.time.unwrap_or(noon))
+    }
+
+    pub fn same_day(self, other: Timestamp) -> bool {
+        self.date == other.date
+    }
+
+    pub fn date_display(self) -> String {
+        self.date.format(DATE_FORMAT).to_string()
+    }
+
+    /// Empty for entries without a time
+    pub fn time_display(self) -> String {
+        self.time
+            .map(|t| t.format(TIME_FORMAT).to_string())
+            .unwrap_or_default()
+    }
+
+    /// The date and time columns every dated table starts with
+    pub fn columns() -> Vec<Column> {
+        vec![
+            Column::new("date", "Date", ValueType::Date),
+            Column::new("time", "Time", ValueType::Time),
+        ]
+    }
+
+    /// The cells for `Timestamp::columns`
+    pub fn cells(self) -> Vec<Cell> {
+        vec![Cell::date(self.date), Cell::time(self.time)]
+    }
+}
+
+impl From<NaiveDateTime> for Timestamp {
+    fn from(at: NaiveDateTime) -> Self {
+        Timestamp::new(at.date(), Some(at.time()))
+    }
+}
+
+/// e.g. `Jan 31 2021 1:05 PM`, or `Jan 31 2021` without a time
+impl fmt::Display for Timestamp {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.time {
+            None => write!(f, "{}", self.date_display()),
+            Some(_) => write!(f, "{} {}", self.date_display(), self.time_display()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::FixedOffset;
+
+    fn date(s: &str) -> NaiveDate {
+        s.parse().unwrap()
+    }
+
+    fn time(s: &str) -> Option<NaiveTime> {
+        Some(s.parse().unwrap())
+    }
+
+    #[test]
+    fn entries_without_a_time_come_first_on_their_day() {
+        let untimed = Timestamp::new(date("2021-01-31"), None);
+        let early = Timestamp::new(date("2021-01-31"), time("00:00:00"));
+        let late = Timestamp::new(date("2021-01-31"), time("23:59:00"));
+        let day_before = Timestamp::new(date("2021-01-30"), time("23:59:00"));
+        let mut stamps = vec![late, untimed, early, day_before];
+        stamps.sort();
+        assert_eq!(stamps, vec![day_before, untimed, early, late]);
+    }
+
+    #[test]
+    fn same_day_ignores_the_time() {
+        let untimed = Timestamp::new(date("2021-01-31"), None);
+        let timed = Timestamp::new(date("2021-01-31"), time("08:15:00"));
+        let next = Timestamp::new(date("2021-02-01"), time("08:15:00"));
+        assert!(untimed.same_day(timed));
+        assert!(timed.same_day(untimed));
+        assert!(!timed.same_day(next));
+    }
+
+    #[test]
+    fn to_datetime_reads_the_wall_clock_in_the_zone() {
+        let stamp = Timestamp::new(date("2021-01-31"), time("23:30:00"));
+        let east = FixedOffset::east_opt(5 * 3600).unwrap();
+        let west = FixedOffset::west_opt(8 * 3600).unwrap();
+
+        let in_east = stamp.to_datetime(&east).unwrap();
+        assert_eq!(in_east.naive_local(), stamp.moment());
+        assert_eq!(
+            in_east.naive_utc(),
+            "2021-01-31T18:30:00".parse::<NaiveDateTime>().unwrap()
+        );
+        // The same wall clock in the west is the next day in UTC
+        let in_west = stamp.to_datetime(&west).unwrap();
+        assert_eq!(
+            in_west.naive_utc(),
+            "2021-02-01T07:30:00".parse::<NaiveDateTime>().unwrap()
+        );
+        assert_eq!(Timestamp::from_datetime(&in_west), stamp);
+
+        let noon = Timestamp::new(date("2021-01-31"), None).to_datetime(&east);
+        assert_eq!(
+            noon.unwrap().naive_utc(),
+            "2021-01-31T07:00:00".parse::<NaiveDateTime>().unwrap()
+        );
+    }
+}
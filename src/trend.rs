@@ -0,0 +1,238 @@
+use chrono::naive::NaiveDate;
+use chrono::Duration;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use crate::units::WeightUnit;
+use crate::weight::Weight;
+
+/// How much of each day's change the smoothed trend takes on, as in The
+/// Hacker's Diet
+const SMOOTHING: f32 = 0.1;
+
+/// Days of recent weights the regression is fitted to
+const REGRESSION_DAYS: i64 = 30;
+
+/// Projections further out than this are no better than none
+const MAX_PROJECTION_DAYS: f32 = 10.0 * 365.0;
+
+/// A day's weight along with the trends up to that day
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct TrendPoint {
+    pub date: NaiveDate,
+    /// The mean of the day's weights
+    pub value: f32,
+    /// The mean of the days logged in the 7 days up to this one
+    pub average_7: f32,
+    pub average_30: f32,
+    /// Exponentially smoothed weight, which moves a tenth of the way to each
+    /// day's value
+    pub smoothed: f32,
+    /// Change in the smoothed weight over the last week, per week.  `None`
+    /// until there is a week of history.
+    pub weekly_rate: Option<f32>,
+}
+
+/// When the regression line reaches a target weight
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct Projection {
+    pub target: f32,
+    /// `None` when the weight is not heading toward the target
+    pub date: Option<NaiveDate>,
+}
+
+/// Trends over a series of weights, all in `unit`
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Trend {
+    pub unit: WeightUnit,
+    pub points: Vec<TrendPoint>,
+    /// Slope of the regression over the last 30 days, per week
+    pub rate_per_week: Option<f32>,
+    pub projection: Option<Projection>,
+}
+
+/// The trend of `weights` in `unit`, projected to `target` if given
+pub fn trend(weights: &[Weight], unit: WeightUnit, target: Option<f32>) -> Trend {
+    let days = daily(weights, unit);
+    let mut points: Vec<TrendPoint> = Vec::with_capacity(days.len());
+    for (i, &(date, value)) in days.iter().enumerate() {
+        let smoothed = match points.last() {
+            None => value,
+            Some(prev) => {
+                // Gaps count as days of no change in the underlying weight
+                let gap = (date - prev.date).num_days() as i32;
+                let keep = (1.0 - SMOOTHING).powi(gap);
+                prev.smoothed + (1.0 - keep) * (value - prev.smoothed)
+            }
+        };
+        let weekly_rate = points
+            .iter()
+            .rev()
+            .find(|x| date - x.date >= Duration::days(7))
+            .map(|x| (smoothed - x.smoothed) * 7.0 / (date - x.date).num_days() as f32);
+        points.push(TrendPoint {
+            date,
+            value,
+            average_7: average(&days[..=i], date, 7),
+            average_30: average(&days[..=i], date, 30),
+            smoothed,
+            weekly_rate,
+        });
+    }
+
+    let fit = days
+        .last()
+        .and_then(|&(last, _)| regression(&days, last - Duration::days(REGRESSION_DAYS - 1)));
+    let projection = match (target, fit, days.last()) {
+        (Some(target), Some(fit), Some(&(last, _))) => Some(Projection {
+            target,
+            date: fit.reaches(target, last),
+        }),
+        _ => None,
+    };
+    Trend {
+        unit,
+        points,
+        rate_per_week: fit.map(|x| x.slope * 7.0),
+        projection,
+    }
+}
+
+/// The mean weight of each day, in date order
+fn daily(weights: &[Weight], unit: WeightUnit) -> Vec<(NaiveDate, f32)> {
+    let mut days: BTreeMap<NaiveDate, (f32, u32)> = BTreeMap::new();
+    for weight in weights {
+        let day = days.entry(weight.date).or_insert((0.0, 0));
+        day.0 += weight.unit.convert(weight.value, unit);
+        day.1 += 1;
+    }
+    days.into_iter()
+        .map(|(date, (sum, count))| (date, sum / count as f32))
+        .collect()
+}
+
+/// The mean of the `days` that fall in the `window` days ending on `date`
+fn average(days: &[(NaiveDate, f32)], date: NaiveDate, window: i64) -> f32 {
+    let start = date - Duration::days(window - 1);
+    let values = days
+        .iter()
+        .filter(|(d, _)| *d >= start && *d <= date)
+        .map(|(_, x)| *x)
+        .collect::<Vec<f32>>();
+    values.iter().sum::<f32>() / values.len() as f32
+}
+
+/// A least squares line through weight by day
+#[derive(Clone, Copy, Debug)]
+struct Fit {
+    origin: NaiveDate,
+    intercept: f32,
+    /// Change per day
+    slope: f32,
+}
+
+impl Fit {
+    fn at(self, date: NaiveDate) -> f32 {
+        self.intercept + self.slope * (date - self.origin).num_days() as f32
+    }
+
+    /// The first day from `from` on that the line is at or past `target`
+    fn reaches(self, target: f32, from: NaiveDate) -> Option<NaiveDate> {
+        let remaining = target - self.at(from);
+        if remaining == 0.0 {
+            return Some(from);
+        }
+        if self.slope == 0.0 || remaining.signum() != self.slope.signum() {
+            return None;
+        }
+        let days = (remaining / self.slope).ceil();
+        if days > MAX_PROJECTION_DAYS {
+            return None;
+        }
+        from.checked_add_signed(Duration::days(days as i64))
+    }
+}
+
+/// Fits the days from `start` on, if there are at least two of them
+fn regression(days: &[(NaiveDate, f32)], start: NaiveDate) -> Option<Fit> {
+    let recent = days
+        .iter()
+        .filter(|(d, _)| *d >= start)
+        .map(|(d, x)| ((*d - start).num_days() as f32, *x))
+        .collect::<Vec<(f32, f32)>>();
+    if recent.len() < 2 {
+        return None;
+    }
+    let n = recent.len() as f32;
+    let mean_x = recent.iter().map(|(x, _)| x).sum::<f32>() / n;
+    let mean_y = recent.iter().map(|(_, y)| y).sum::<f32>() / n;
+    let covariance: f32 = recent
+        .iter()
+        .map(|(x, y)| (x - mean_x) * (y - mean_y))
+        .sum();
+    let variance: f32 = recent.iter().map(|(x, _)| (x - mean_x).powi(2)).sum();
+    let slope = covariance / variance;
+    Some(Fit {
+        origin: start,
+        intercept: mean_y - slope * mean_x,
+        slope,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn day(n: i64) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2021, 1, 1).unwrap() + Duration::days(n)
+    }
+
+    fn weight(n: i64, value: f32) -> Weight {
+        Weight {
+            id: n as i32,
+            date: day(n),
+            time: None,
+            value,
+            unit: WeightUnit::Kg,
+        }
+    }
+
+    #[test]
+    fn fits_a_line_exactly() {
+        let days = (0..10)
+            .map(|n| (day(n), 90.0 - 0.5 * n as f32))
+            .collect::<Vec<_>>();
+        let fit = regression(&days, day(0)).unwrap();
+        assert_eq!(fit.origin, day(0));
+        assert_eq!(fit.slope, -0.5);
+        assert_eq!(fit.intercept, 90.0);
+
+        let weights = (0..10)
+            .map(|n| weight(n, 90.0 - 0.5 * n as f32))
+            .collect::<Vec<_>>();
+        let trend = trend(&weights, WeightUnit::Kg, Some(85.0));
+        assert_eq!(trend.rate_per_week, Some(-3.5));
+        assert_eq!(trend.projection.unwrap().date, Some(day(10)));
+    }
+
+    #[test]
+    fn has_nothing_without_weights() {
+        let trend = trend(&[], WeightUnit::Kg, Some(80.0));
+        assert!(trend.points.is_empty());
+        assert!(trend.rate_per_week.is_none());
+        assert!(trend.projection.is_none());
+    }
+
+    #[test]
+    fn has_no_rate_from_one_weight() {
+        let trend = trend(&[weight(0, 80.0)], WeightUnit::Kg, Some(75.0));
+        assert_eq!(trend.points.len(), 1);
+        let point = trend.points[0];
+        assert_eq!(point.value, 80.0);
+        assert_eq!(point.average_7, 80.0);
+        assert_eq!(point.smoothed, 80.0);
+        assert!(point.weekly_rate.is_none());
+        assert!(trend.rate_per_week.is_none());
+        assert!(trend.projection.is_none());
+    }
+}
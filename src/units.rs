@@ -0,0 +1,400 @@
+#[cfg(feature = "database")]
+use diesel::sql_types::Text;
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+const KG_PER_LB: f32 = 0.453_592_37;
+const CM_PER_IN: f32 = 2.54;
+const ML_PER_FL_OZ: f32 = 29.573_53;
+const KM_PER_MI: f32 = 1.609_344;
+
+/// Grams in one of each kitchen unit of weight
+const GRAMS: [(&str, f32); 4] = [
+    ("g", 1.0),
+    ("kg", 1000.0),
+    ("oz", KG_PER_LB * 1000.0 / 16.0),
+    ("lb", KG_PER_LB * 1000.0),
+];
+
+/// Millilitres in one of each kitchen unit of volume, using US cups and
+/// spoons
+const MILLILITRES: [(&str, f32); 6] = [
+    ("ml", 1.0),
+    ("l", 1000.0),
+    ("tsp", 4.928_922),
+    ("tbsp", 14.786_765),
+    ("fl oz", ML_PER_FL_OZ),
+    ("cup", 236.588_24),
+];
+
+/// Unit a weight was entered in, stored next to the value
+#[cfg_attr(
+    feature = "database",
+    derive(AsExpression, FromSqlRow),
+    sql_type = "Text"
+)]
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WeightUnit {
+    Kg,
+    Lb,
+}
+
+impl WeightUnit {
+    pub fn suffix(self) -> &'static str {
+        match self {
+            WeightUnit::Kg => "kg",
+            WeightUnit::Lb => "lb",
+        }
+    }
+
+    /// Converts `value` from this unit to `to`
+    pub fn convert(self, value: f32, to: WeightUnit) -> f32 {
+        match (self, to) {
+            (WeightUnit::Lb, WeightUnit::Kg) => value * KG_PER_LB,
+            (WeightUnit::Kg, WeightUnit::Lb) => value / KG_PER_LB,
+            _ => value,
+        }
+    }
+}
+
+/// Unit a body circumference was entered in, stored next to the value
+#[cfg_attr(
+    feature = "database",
+    derive(AsExpression, FromSqlRow),
+    sql_type = "Text"
+)]
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LengthUnit {
+    Cm,
+    In,
+}
+
+impl LengthUnit {
+    pub fn suffix(self) -> &'static str {
+        match self {
+            LengthUnit::Cm => "cm",
+            LengthUnit::In => "in",
+        }
+    }
+
+    /// Converts `value` from this unit to `to`
+    pub fn convert(self, value: f32, to: LengthUnit) -> f32 {
+        match (self, to) {
+            (LengthUnit::In, LengthUnit::Cm) => value * CM_PER_IN,
+            (LengthUnit::Cm, LengthUnit::In) => value / CM_PER_IN,
+            _ => value,
+        }
+    }
+}
+
+/// Unit a drink was entered in, stored next to the value.  Fluid ounces
+/// are US ones.
+#[cfg_attr(
+    feature = "database",
+    derive(AsExpression, FromSqlRow),
+    sql_type = "Text"
+)]
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum VolumeUnit {
+    Ml,
+    FlOz,
+}
+
+impl VolumeUnit {
+    pub fn suffix(self) -> &'static str {
+        match self {
+            VolumeUnit::Ml => "ml",
+            VolumeUnit::FlOz => "fl oz",
+        }
+    }
+
+    /// Converts `value` from this unit to `to`
+    pub fn convert(self, value: f32, to: VolumeUnit) -> f32 {
+        match (self, to) {
+            (VolumeUnit::FlOz, VolumeUnit::Ml) => value * ML_PER_FL_OZ,
+            (VolumeUnit::Ml, VolumeUnit::FlOz) => value / ML_PER_FL_OZ,
+            _ => value,
+        }
+    }
+}
+
+/// Unit a workout's distance was entered in, stored next to the value
+#[cfg_attr(
+    feature = "database",
+    derive(AsExpression, FromSqlRow),
+    sql_type = "Text"
+)]
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DistanceUnit {
+    Km,
+    Mi,
+}
+
+impl DistanceUnit {
+    pub fn suffix(self) -> &'static str {
+        match self {
+            DistanceUnit::Km => "km",
+            DistanceUnit::Mi => "mi",
+        }
+    }
+
+    /// Converts `value` from this unit to `to`
+    pub fn convert(self, value: f32, to: DistanceUnit) -> f32 {
+        match (self, to) {
+            (DistanceUnit::Mi, DistanceUnit::Km) => value * KM_PER_MI,
+            (DistanceUnit::Km, DistanceUnit::Mi) => value / KM_PER_MI,
+            _ => value,
+        }
+    }
+}
+
+/// The units a reader prefers values to be shown in
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum UnitSystem {
+    #[default]
+    Metric,
+    Imperial,
+}
+
+impl UnitSystem {
+    pub fn weight(self) -> WeightUnit {
+        match self {
+            UnitSystem::Metric => WeightUnit::Kg,
+            UnitSystem::Imperial => WeightUnit::Lb,
+        }
+    }
+
+    pub fn length(self) -> LengthUnit {
+        match self {
+            UnitSystem::Metric => LengthUnit::Cm,
+            UnitSystem::Imperial => LengthUnit::In,
+        }
+    }
+
+    pub fn volume(self) -> VolumeUnit {
+        match self {
+            UnitSystem::Metric => VolumeUnit::Ml,
+            UnitSystem::Imperial => VolumeUnit::FlOz,
+        }
+    }
+
+    pub fn distance(self) -> DistanceUnit {
+        match self {
+            UnitSystem::Metric => DistanceUnit::Km,
+            UnitSystem::Imperial => DistanceUnit::Mi,
+        }
+    }
+}
+
+impl FromStr for UnitSystem {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "metric" => Ok(UnitSystem::Metric),
+            "imperial" => Ok(UnitSystem::Imperial),
+            _ => Err(format!("unknown unit system {}", s)),
+        }
+    }
+}
+
+impl FromStr for WeightUnit {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "kg" => Ok(WeightUnit::Kg),
+            "lb" => Ok(WeightUnit::Lb),
+            _ => Err(format!("unknown weight unit {}", s)),
+        }
+    }
+}
+
+impl FromStr for LengthUnit {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "cm" => Ok(LengthUnit::Cm),
+            "in" => Ok(LengthUnit::In),
+            _ => Err(format!("unknown length unit {}", s)),
+        }
+    }
+}
+
+impl FromStr for VolumeUnit {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "ml" => Ok(VolumeUnit::Ml),
+            "fl oz" | "fl_oz" => Ok(VolumeUnit::FlOz),
+            _ => Err(format!("unknown volume unit {}", s)),
+        }
+    }
+}
+
+impl FromStr for DistanceUnit {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "km" => Ok(DistanceUnit::Km),
+            "mi" => Ok(DistanceUnit::Mi),
+            _ => Err(format!("unknown distance unit {}", s)),
+        }
+    }
+}
+
+impl fmt::Display for UnitSystem {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            UnitSystem::Metric => write!(f, "metric"),
+            UnitSystem::Imperial => write!(f, "imperial"),
+        }
+    }
+}
+
+impl fmt::Display for WeightUnit {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.suffix())
+    }
+}
+
+impl fmt::Display for LengthUnit {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.suffix())
+    }
+}
+
+impl fmt::Display for VolumeUnit {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.suffix())
+    }
+}
+
+impl fmt::Display for DistanceUnit {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.suffix())
+    }
+}
+
+/// An amount of food in a kitchen unit such as `g` or `tbsp`.  Only
+/// amounts of the same kind, by weight or by volume, can be compared.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Quantity {
+    Grams(f32),
+    Millilitres(f32),
+}
+
+impl Quantity {
+    /// `None` for units that are neither a weight nor a volume, e.g. `slice`
+    pub fn new(amount: f32, unit: &str) -> Option<Quantity> {
+        let unit = unit.trim().to_lowercase();
+        let per = |units: &[(&str, f32)]| {
+            units
+                .iter()
+                .find(|(name, _)| *name == unit)
+                .map(|(_, per)| amount * per)
+        };
+        per(&GRAMS)
+            .map(Quantity::Grams)
+            .or_else(|| per(&MILLILITRES).map(Quantity::Millilitres))
+    }
+
+    /// How many times `other` fits in this, if they are the same kind
+    pub fn ratio(self, other: Quantity) -> Option<f32> {
+        match (self, other) {
+            (Quantity::Grams(a), Quantity::Grams(b)) => Some(a / b),
+            (Quantity::Millilitres(a), Quantity::Millilitres(b)) => Some(a / b),
+            _ => None,
+        }
+    }
+}
+
+/// A value followed by its unit, rounded for display
+pub fn with_suffix<U: fmt::Display>(value: f32, unit: U) -> String {
+    format!("{:.1} {}", value, unit)
+}
+
+/// Units are stored as their suffix
+#[cfg(feature = "database")]
+mod sql {
+    use super::*;
+
+    text_sql!(WeightUnit, suffix);
+    text_sql!(LengthUnit, suffix);
+    text_sql!(VolumeUnit, suffix);
+    text_sql!(DistanceUnit, suffix);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(a: f32, b: f32) {
+        assert!((a - b).abs() < 1e-4, "{} is not {}", a, b);
+    }
+
+    #[test]
+    fn converts_weights_both_ways() {
+        assert_close(WeightUnit::Lb.convert(1.0, WeightUnit::Kg), 0.453_592_37);
+        assert_close(WeightUnit::Kg.convert(1.0, WeightUnit::Lb), 2.204_622_6);
+        let lb = WeightUnit::Kg.convert(80.0, WeightUnit::Lb);
+        assert_close(WeightUnit::Lb.convert(lb, WeightUnit::Kg), 80.0);
+        assert_eq!(WeightUnit::Kg.convert(80.0, WeightUnit::Kg), 80.0);
+    }
+
+    #[test]
+    fn converts_lengths_both_ways() {
+        assert_close(LengthUnit::In.convert(1.0, LengthUnit::Cm), 2.54);
+        let inches = LengthUnit::Cm.convert(180.0, LengthUnit::In);
+        assert_close(inches, 70.866_14);
+        assert_close(LengthUnit::In.convert(inches, LengthUnit::Cm), 180.0);
+    }
+
+    #[test]
+    fn converts_volumes_both_ways() {
+        assert_close(VolumeUnit::FlOz.convert(1.0, VolumeUnit::Ml), 29.573_53);
+        let fl_oz = VolumeUnit::Ml.convert(500.0, VolumeUnit::FlOz);
+        assert_close(fl_oz, 16.907_01);
+        assert_close(VolumeUnit::FlOz.convert(fl_oz, VolumeUnit::Ml), 500.0);
+    }
+
+    #[test]
+    fn converts_distances_both_ways() {
+        assert_close(DistanceUnit::Mi.convert(1.0, DistanceUnit::Km), 1.609_344);
+        let miles = DistanceUnit::Km.convert(5.0, DistanceUnit::Mi);
+        assert_close(miles, 3.106_856);
+        assert_close(DistanceUnit::Mi.convert(miles, DistanceUnit::Km), 5.0);
+    }
+
+    #[test]
+    fn reads_kitchen_units() {
+        assert_eq!(Quantity::new(2.0, "kg"), Some(Quantity::Grams(2000.0)));
+        assert_eq!(
+            Quantity::new(1.5, " L "),
+            Some(Quantity::Millilitres(1500.0))
+        );
+        assert_eq!(Quantity::new(1.0, "slice"), None);
+    }
+
+    #[test]
+    fn compares_quantities_of_one_kind() {
+        let lb = Quantity::new(1.0, "lb").unwrap();
+        let oz = Quantity::new(1.0, "oz").unwrap();
+        assert_close(lb.ratio(oz).unwrap(), 16.0);
+        let cup = Quantity::new(1.0, "cup").unwrap();
+        let tbsp = Quantity::new(1.0, "tbsp").unwrap();
+        assert_close(cup.ratio(tbsp).unwrap(), 16.0);
+        assert_eq!(lb.ratio(cup), None);
+        assert_eq!(cup.ratio(lb), None);
+    }
+}
@@ -0,0 +1,147 @@
+use chrono::naive::NaiveDate;
+use chrono::{Duration, Local};
+use serde::{Deserialize, Serialize};
+use std::fmt::{self, Display, Formatter};
+
+/// Longest text the `VARCHAR(100)` columns hold
+pub const MAX_TEXT: usize = 100;
+
+/// Rules a `New*` row must follow before it is written.  The api enforces
+/// them and the web forms check them before submitting.
+pub trait Validate {
+    fn validate(&self) -> Result<(), Vec<FieldError>>;
+}
+
+/// A field that broke a rule, named as it is in the json body
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct FieldError {
+    pub field: String,
+    pub message: String,
+}
+
+impl FieldError {
+    pub fn new(field: &str, message: &str) -> Self {
+        FieldError {
+            field: field.to_string(),
+            message: message.to_string(),
+        }
+    }
+}
+
+impl Display for FieldError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "{} {}", self.field, self.message)
+    }
+}
+
+/// Collects every rule a row breaks, rather than stopping at the first
+#[derive(Debug, Default)]
+pub struct Rules {
+    errors: Vec<FieldError>,
+}
+
+impl Rules {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records `message` against `field` unless `ok`
+    pub fn check(&mut self, ok: bool, field: &str, message: &str) {
+        if !ok {
+            self.errors.push(FieldError::new(field, message));
+        }
+    }
+
+    pub fn range<T: PartialOrd + Display>(&mut self, field: &str, value: T, min: T, max: T) {
+        let message = format!("must be between {} and {}", min, max);
+        self.check(min <= value && value <= max, field, &message);
+    }
+
+    pub fn positive(&mut self, field: &str, value: f32) {
+        self.check(value > 0.0, field, "must be greater than 0");
+    }
+
+    pub fn not_negative(&mut self, field: &str, value: f32) {
+        self.check(value >= 0.0, field, "cannot be negative");
+    }
+
+    /// Dates up to tomorrow are allowed, so that a client ahead of the
+    /// server's time zone can still enter today
+    pub fn not_future(&mut self, field: &str, date: NaiveDate) {
+        let latest = Local::now().naive_local().date() + Duration::days(1);
+        self.check(date <= latest, field, "cannot be in the future");
+    }
+
+    pub fn text(&mut self, field: &str, value: &str) {
+        self.check(!value.trim().is_empty(), field, "cannot be blank");
+        let message = format!("cannot be longer than {} characters", MAX_TEXT);
+        self.check(value.chars().count() <= MAX_TEXT, field, &message);
+    }
+
+    pub fn finish(self) -> Result<(), Vec<FieldError>> {
+        if self.errors.is_empty() {
+            Ok(())
+        } else {
+            Err(self.errors)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The fields `rule` records an error against
+    fn broken(rule: impl FnOnce(&mut Rules)) -> Vec<String> {
+        let mut rules = Rules::new();
+        rule(&mut rules);
+        match rules.finish() {
+            Ok(()) => Vec::new(),
+            Err(errors) => errors.into_iter().map(|x| x.field).collect(),
+        }
+    }
+
+    #[test]
+    fn range_includes_its_bounds() {
+        assert!(broken(|x| x.range("quality", 1, 1, 5)).is_empty());
+        assert!(broken(|x| x.range("quality", 5, 1, 5)).is_empty());
+        assert_eq!(broken(|x| x.range("quality", 0, 1, 5)), vec!["quality"]);
+        assert_eq!(broken(|x| x.range("quality", 6, 1, 5)), vec!["quality"]);
+    }
+
+    #[test]
+    fn zero_is_not_positive_but_not_negative() {
+        assert_eq!(broken(|x| x.positive("value", 0.0)), vec!["value"]);
+        assert!(broken(|x| x.positive("value", 0.1)).is_empty());
+        assert!(broken(|x| x.not_negative("value", 0.0)).is_empty());
+        assert_eq!(broken(|x| x.not_negative("value", -0.1)), vec!["value"]);
+    }
+
+    #[test]
+    fn not_future_allows_up_to_tomorrow() {
+        let today = Local::now().naive_local().date();
+        assert!(broken(|x| x.not_future("date", today)).is_empty());
+        let tomorrow = today + Duration::days(1);
+        assert!(broken(|x| x.not_future("date", tomorrow)).is_empty());
+        let after = today + Duration::days(2);
+        assert_eq!(broken(|x| x.not_future("date", after)), vec!["date"]);
+    }
+
+    #[test]
+    fn text_holds_up_to_the_column_length() {
+        let full = "é".repeat(MAX_TEXT);
+        assert!(broken(|x| x.text("name", &full)).is_empty());
+        let over = "é".repeat(MAX_TEXT + 1);
+        assert_eq!(broken(|x| x.text("name", &over)), vec!["name"]);
+        assert_eq!(broken(|x| x.text("name", "  ")), vec!["name"]);
+    }
+
+    #[test]
+    fn every_broken_rule_is_kept() {
+        let fields = broken(|x| {
+            x.positive("value", -1.0);
+            x.range("quality", 9, 1, 5);
+        });
+        assert_eq!(fields, vec!["value", "quality"]);
+    }
+}
@@ -0,0 +1,357 @@
+#[cfg(feature = "database")]
+use crate::schema::water;
+#[cfg(feature = "database")]
+use diesel::sql_types::Text;
+
+use chrono::naive::{NaiveDate, NaiveTime};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+
+use crate::column::{common, Cell, Column, Value, ValueType};
+use crate::import::{key_float, key_timestamp, Fields, Importable, Record};
+use crate::timestamp::{Timestamp, Timestamped};
+use crate::units::{with_suffix, UnitSystem, VolumeUnit};
+use crate::validate::{FieldError, Rules, Validate};
+use crate::Tabular;
+
+/// What was drunk, stored as its lowercase name
+#[cfg_attr(
+    feature = "database",
+    derive(AsExpression, FromSqlRow),
+    sql_type = "Text"
+)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Beverage {
+    Water,
+    Coffee,
+    Tea,
+    Milk,
+    Juice,
+    Soda,
+    Other,
+}
+
+impl Beverage {
+    pub const ALL: [Beverage; 7] = [
+        Beverage::Water,
+        Beverage::Coffee,
+        Beverage::Tea,
+        Beverage::Milk,
+        Beverage::Juice,
+        Beverage::Soda,
+        Beverage::Other,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Beverage::Water => "water",
+            Beverage::Coffee => "coffee",
+            Beverage::Tea => "tea",
+            Beverage::Milk => "milk",
+            Beverage::Juice => "juice",
+            Beverage::Soda => "soda",
+            Beverage::Other => "other",
+        }
+    }
+}
+
+impl FromStr for Beverage {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.to_lowercase();
+        Beverage::ALL
+            .iter()
+            .copied()
+            .find(|x| x.name() == s)
+            .ok_or_else(|| format!("unknown beverage {}", s))
+    }
+}
+
+impl fmt::Display for Beverage {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Beverage::Water => write!(f, "Water"),
+            Beverage::Coffee => write!(f, "Coffee"),
+            Beverage::Tea => write!(f, "Tea"),
+            Beverage::Milk => write!(f, "Milk"),
+            Beverage::Juice => write!(f, "Juice"),
+            Beverage::Soda => write!(f, "Soda"),
+            Beverage::Other => write!(f, "Other"),
+        }
+    }
+}
+
+#[cfg_attr(
+    feature = "database",
+    derive(Insertable, AsChangeset),
+    table_name = "water",
+    changeset_options(treat_none_as_null = "true")
+)]
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct NewWater {
+    pub date: NaiveDate,
+    pub time: Option<NaiveTime>,
+    pub volume: f32,
+    pub unit: VolumeUnit,
+    pub beverage: Beverage,
+}
+
+impl Validate for NewWater {
+    fn validate(&self) -> Result<(), Vec<FieldError>> {
+        let mut rules = Rules::new();
+        rules.not_future("date", self.date);
+        rules.positive("volume", self.volume);
+        rules.finish()
+    }
+}
+
+#[cfg_attr(feature = "database", derive(Queryable))]
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct Water {
+    pub id: i32,
+    pub date: NaiveDate,
+    pub time: Option<NaiveTime>,
+    pub volume: f32,
+    pub unit: VolumeUnit,
+    pub beverage: Beverage,
+}
+
+impl Water {
+    /// The same drink converted to `units`
+    pub fn in_units(self, units: UnitSystem) -> Self {
+        let unit = units.volume();
+        Water {
+            volume: self.unit.convert(self.volume, unit),
+            unit,
+            ..self
+        }
+    }
+}
+
+impl Timestamped for NewWater {
+    fn timestamp(&self) -> Timestamp {
+        Timestamp::new(self.date, self.time)
+    }
+}
+
+impl Timestamped for Water {
+    fn timestamp(&self) -> Timestamp {
+        Timestamp::new(self.date, self.time)
+    }
+}
+
+impl From<Water> for NewWater {
+    fn from(water: Water) -> Self {
+        NewWater {
+            date: water.date,
+            time: water.time,
+            volume: water.volume,
+            unit: water.unit,
+            beverage: water.beverage,
+        }
+    }
+}
+
+impl Importable for NewWater {
+    fn from_record(record: &Record) -> Result<Self, Vec<FieldError>> {
+        let mut fields = Fields::new(record);
+        let date = fields.date("date");
+        let time = fields.time_option("time");
+        let volume = fields.required("volume");
+        let unit = fields.unit("unit", &["volume"]);
+        let beverage = fields.optional("beverage");
+        fields.finish(|| {
+            Some(NewWater {
+                date: date?,
+                time: time?,
+                volume: volume?,
+                unit: unit?,
+                beverage: beverage?.unwrap_or(Beverage::Water),
+            })
+        })
+    }
+
+    /// Volumes are compared in millilitres
+    fn duplicate_key(&self) -> String {
+        let ml = self.unit.convert(self.volume, VolumeUnit::Ml);
+        format!(
+            "{} {} {}",
+            key_timestamp(self.timestamp()),
+            key_float(Some(ml)),
+            self.beverage.name()
+        )
+    }
+}
+
+impl Tabular for Vec<Water> {
+    fn columns(&self) -> Vec<Column> {
+        let unit = common(self.iter().map(|x| x.unit));
+        [
+            Timestamp::columns(),
+            vec![
+                Column::new("volume", "Volume", ValueType::Float)
+                    .with_unit(unit.map(|x| x.suffix())),
+                Column::new("beverage", "Beverage", ValueType::Text),
+            ],
+        ]
+        .concat()
+    }
+
+    fn rows(&self) -> Vec<Vec<Cell>> {
+        self.iter()
+            .map(|item| {
+                [
+                    item.timestamp().cells(),
+                    vec![
+                        Cell::float(Some(item.volume), |x| with_suffix(x, item.unit)),
+                        Cell::new(
+                            item.beverage.to_string(),
+                            Some(Value::Text(item.beverage.name().to_string())),
+                        ),
+                    ],
+                ]
+                .concat()
+            })
+            .collect()
+    }
+}
+
+/// Everything drunk on one day
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct DailyWater {
+    pub date: NaiveDate,
+    pub entries: i32,
+    pub volume: f32,
+    pub unit: VolumeUnit,
+    /// The share of the daily target drunk, if there is one
+    pub of_target: Option<f32>,
+}
+
+/// The totals in `unit` of each day `entries` were logged on, oldest first.
+/// `target` is in `unit` too.
+pub fn daily_totals(entries: &[Water], unit: VolumeUnit, target: Option<f32>) -> Vec<DailyWater> {
+    let mut days: BTreeMap<NaiveDate, DailyWater> = BTreeMap::new();
+    for entry in entries {
+        let day = days.entry(entry.date).or_insert(DailyWater {
+            date: entry.date,
+            entries: 0,
+            volume: 0.0,
+            unit,
+            of_target: None,
+        });
+        day.entries += 1;
+        day.volume += entry.unit.convert(entry.volume, unit);
+    }
+    days.values()
+        .map(|day| DailyWater {
+            of_target: target.map(|target| day.volume / target),
+            ..*day
+        })
+        .collect()
+}
+
+impl Tabular for Vec<DailyWater> {
+    fn columns(&self) -> Vec<Column> {
+        let unit = common(self.iter().map(|x| x.unit));
+        vec![
+            Column::new("date", "Date", ValueType::Date),
+            Column::new("entries", "Entries", ValueType::Int),
+            Column::new("volume", "Total", ValueType::Float).with_unit(unit.map(|x| x.suffix())),
+            Column::new("of_target", "Of Target", ValueType::Float).with_unit(Some("%")),
+        ]
+    }
+
+    fn rows(&self) -> Vec<Vec<Cell>> {
+        self.iter()
+            .map(|day| {
+                vec![
+                    Cell::date(day.date),
+                    Cell::int(day.entries, day.entries.to_string()),
+                    Cell::float(Some(day.volume), |x| with_suffix(x, day.unit)),
+                    Cell::float(day.of_target.map(|x| x * 100.0), |x| format!("{:.0}", x)),
+                ]
+            })
+            .collect()
+    }
+}
+
+/// Beverages are stored as their lowercase name
+#[cfg(feature = "database")]
+mod sql {
+    use super::*;
+
+    text_sql!(Beverage);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn drink(id: i32, date: &str, volume: f32, unit: VolumeUnit) -> Water {
+        Water {
+            id,
+            date: date.parse().unwrap(),
+            time: None,
+            volume,
+            unit,
+            beverage: Beverage::Water,
+        }
+    }
+
+    fn close(a: f32, b: f32) -> bool {
+        (a - b).abs() < 1e-3
+    }
+
+    #[test]
+    fn daily_totals_groups_by_day_oldest_first() {
+        let entries = vec![
+            drink(1, "2021-02-02", 500.0, VolumeUnit::Ml),
+            drink(2, "2021-02-01", 250.0, VolumeUnit::Ml),
+            drink(3, "2021-02-02", 750.0, VolumeUnit::Ml),
+        ];
+        let days = daily_totals(&entries, VolumeUnit::Ml, None);
+        assert_eq!(days.len(), 2);
+        assert_eq!(days[0].date, "2021-02-01".parse().unwrap());
+        assert_eq!(days[0].entries, 1);
+        assert!(close(days[0].volume, 250.0));
+        assert_eq!(days[1].date, "2021-02-02".parse().unwrap());
+        assert_eq!(days[1].entries, 2);
+        assert!(close(days[1].volume, 1250.0));
+        assert!(days.iter().all(|x| x.of_target.is_none()));
+    }
+
+    #[test]
+    fn daily_totals_sums_millilitres_and_fluid_ounces() {
+        let entries = vec![
+            drink(1, "2021-02-01", 500.0, VolumeUnit::Ml),
+            drink(2, "2021-02-01", 8.0, VolumeUnit::FlOz),
+        ];
+        let ml = daily_totals(&entries, VolumeUnit::Ml, None);
+        assert!(close(ml[0].volume, 500.0 + 8.0 * 29.573_53));
+        assert_eq!(ml[0].unit, VolumeUnit::Ml);
+
+        let fl_oz = daily_totals(&entries, VolumeUnit::FlOz, None);
+        assert!(close(fl_oz[0].volume, 500.0 / 29.573_53 + 8.0));
+        assert_eq!(fl_oz[0].unit, VolumeUnit::FlOz);
+    }
+
+    #[test]
+    fn daily_totals_compares_each_day_with_the_target() {
+        let entries = vec![
+            drink(1, "2021-02-01", 1000.0, VolumeUnit::Ml),
+            drink(2, "2021-02-02", 3000.0, VolumeUnit::Ml),
+        ];
+        let days = daily_totals(&entries, VolumeUnit::Ml, Some(2000.0));
+        assert!(close(days[0].of_target.unwrap(), 0.5));
+        assert!(close(days[1].of_target.unwrap(), 1.5));
+
+        let rows = days.rows();
+        assert_eq!(rows[0][3].display, "50");
+        assert_eq!(rows[1][3].display, "150");
+        assert_eq!(days.columns()[3].unit.as_deref(), Some("%"));
+    }
+}
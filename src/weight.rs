@@ -4,14 +4,34 @@ use crate::schema::weight;
 use chrono::naive::{NaiveDate, NaiveTime};
 use serde::{Deserialize, Serialize};
 
+use crate::column::{common, Cell, Column, ValueType};
+use crate::import::{key_float, key_timestamp, Fields, Importable, Record};
+use crate::timestamp::{Timestamp, Timestamped};
+use crate::units::{with_suffix, UnitSystem, WeightUnit};
+use crate::validate::{FieldError, Rules, Validate};
 use crate::Tabular;
 
-#[cfg_attr(feature = "database", derive(Insertable), table_name = "weight")]
+#[cfg_attr(
+    feature = "database",
+    derive(Insertable, AsChangeset),
+    table_name = "weight",
+    changeset_options(treat_none_as_null = "true")
+)]
 #[derive(Clone, Copy, Debug, Serialize, Deserialize)]
 pub struct NewWeight {
     pub date: NaiveDate,
     pub time: Option<NaiveTime>,
     pub value: f32,
+    pub unit: WeightUnit,
+}
+
+impl Validate for NewWeight {
+    fn validate(&self) -> Result<(), Vec<FieldError>> {
+        let mut rules = Rules::new();
+        rules.not_future("date", self.date);
+        rules.positive("value", self.value);
+        rules.finish()
+    }
 }
 
 #[cfg_attr(feature = "database", derive(Queryable))]
@@ -21,31 +41,92 @@ pub struct Weight {
     pub date: NaiveDate,
     pub time: Option<NaiveTime>,
     pub value: f32,
+    pub unit: WeightUnit,
+}
+
+impl Weight {
+    /// The same weight converted to `units`
+    pub fn in_units(self, units: UnitSystem) -> Self {
+        let unit = units.weight();
+        Weight {
+            value: self.unit.convert(self.value, unit),
+            unit,
+            ..self
+        }
+    }
+}
+
+impl Timestamped for NewWeight {
+    fn timestamp(&self) -> Timestamp {
+        Timestamp::new(self.date, self.time)
+    }
+}
+
+impl Timestamped for Weight {
+    fn timestamp(&self) -> Timestamp {
+        Timestamp::new(self.date, self.time)
+    }
+}
+
+impl From<Weight> for NewWeight {
+    fn from(weight: Weight) -> Self {
+        NewWeight {
+            date: weight.date,
+            time: weight.time,
+            value: weight.value,
+            unit: weight.unit,
+        }
+    }
+}
+
+impl Importable for NewWeight {
+    fn from_record(record: &Record) -> Result<Self, Vec<FieldError>> {
+        let mut fields = Fields::new(record);
+        let date = fields.date("date");
+        let time = fields.time_option("time");
+        let value = fields.required("value");
+        let unit = fields.unit("unit", &["value"]);
+        fields.finish(|| {
+            Some(NewWeight {
+                date: date?,
+                time: time?,
+                value: value?,
+                unit: unit?,
+            })
+        })
+    }
+
+    /// Weights are compared in kilograms
+    fn duplicate_key(&self) -> String {
+        let kg = self.unit.convert(self.value, WeightUnit::Kg);
+        format!(
+            "{} {}",
+            key_timestamp(self.timestamp()),
+            key_float(Some(kg))
+        )
+    }
 }
 
 impl Tabular for Vec<Weight> {
-    fn headers(&self) -> Vec<String> {
-        let v = vec![
-            "Date",
-            "Time",
-            "Weight",
-        ];
-        v.iter().map(|x| x.to_string()).collect()
+    fn columns(&self) -> Vec<Column> {
+        let unit = common(self.iter().map(|x| x.unit));
+        [
+            Timestamp::columns(),
+            vec![Column::new("value", "Weight", ValueType::Float)
+                .with_unit(unit.map(|x| x.suffix()))],
+        ]
+        .concat()
     }
 
-    fn matrix(&self) -> Vec<Vec<String>> {
+    fn rows(&self) -> Vec<Vec<Cell>> {
         self.iter()
             .map(|item| {
-                let time = match item.time {
-                    None => String::new(),
-                    Some(t) => t.format("%-I:%M %p").to_string(),
-                };
-                vec![
-                    item.date.format("%b %d %Y").to_string(),
-                    time,
-                    item.value.to_string(),
+                [
+                    item.timestamp().cells(),
+                    vec![Cell::float(Some(item.value), |x| with_suffix(x, item.unit))],
                 ]
+                .concat()
             })
-            .collect::<Vec<Vec<String>>>()
+            .collect()
     }
 }
@@ -1,44 +1,99 @@
-use convert_case::{Case, Casing};
+use diet_database::api_error::ApiError;
+use diet_database::dependents::Dependents;
+use diet_database::listing::Listing;
+use diet_database::units::UnitSystem;
+use diet_database::TableType;
 use seed::prelude::*;
 use serde::{Deserialize, Serialize};
 
 const API_URL: &str = "http://localhost:8000";
+const UNITS_KEY: &str = "units";
+
+/// The units the user chose to see measurements in, if any
+pub fn preferred_units() -> Option<UnitSystem> {
+    LocalStorage::get(UNITS_KEY).ok()
+}
+
+pub fn set_preferred_units(units: Option<UnitSystem>) {
+    match units {
+        Some(units) => LocalStorage::insert(UNITS_KEY, &units).ok(),
+        None => LocalStorage::remove(UNITS_KEY).ok(),
+    };
+}
 
 #[derive(Debug, Clone, Copy)]
 pub enum ApiCall {
-    Bowel,
-    Store,
-    GroceryTrip,
-    Metric,
-    Weight,
-    GroceryItem,
+    Table(TableType),
+    Composition,
+    /// Calories burned each day next to those eaten
+    ExerciseDaily,
 }
 
 impl ApiCall {
-    fn lower(&self) -> String {
-        format!("{:?}", self).to_case(Case::Snake)
+    /// Relative to the api's root
+    fn path(&self) -> String {
+        match self {
+            ApiCall::Table(table) => table.path(),
+            ApiCall::Composition => "/composition".to_string(),
+            ApiCall::ExerciseDaily => "/exercise/daily".to_string(),
+        }
     }
 
-    pub async fn get<T: 'static + for<'de> Deserialize<'de>>(&self) -> fetch::Result<T> {
-        fetch(format!("{}/{}", API_URL, self.lower()))
-            .await?
-            .json()
-            .await
+    pub async fn get<T: 'static + for<'de> Deserialize<'de>>(&self) -> fetch::Result<Vec<T>> {
+        let mut url = format!("{}{}", API_URL, self.path());
+        if let Some(units) = preferred_units() {
+            url.push_str(&format!("?units={}", units));
+        }
+        let listing: Listing<T> = fetch(url).await?.json().await?;
+        Ok(listing.items)
     }
 
     pub async fn post<NEW: Serialize>(&self, item: NEW) -> fetch::Result<Response> {
-        fetch::Request::new(format!("{}/{}", API_URL, self.lower()))
+        fetch::Request::new(format!("{}{}", API_URL, self.path()))
             .method(Method::Post)
             .json(&item)?
             .fetch()
             .await
     }
 
-    pub async fn delete<ITEM: Serialize>(&self, item: ITEM) -> fetch::Result<Response> {
-        fetch::Request::new(format!("{}/{}", API_URL, self.lower()))
-            .method(Method::Delete)
+    pub async fn put<NEW: Serialize>(&self, id: i32, item: NEW) -> fetch::Result<Response> {
+        fetch::Request::new(format!("{}{}/{}", API_URL, self.path(), id))
+            .method(Method::Put)
             .json(&item)?
             .fetch()
             .await
     }
+
+    pub async fn delete(&self, id: i32) -> fetch::Result<Response> {
+        fetch::Request::new(format!("{}{}/{}", API_URL, self.path(), id))
+            .method(Method::Delete)
+            .fetch()
+            .await
+    }
+
+    /// Rows that would be deleted along with a row
+    pub async fn dependents(&self, id: i32) -> fetch::Result<Dependents> {
+        fetch(format!("{}{}/{}/dependents", API_URL, self.path(), id))
+            .await?
+            .json()
+            .await
+    }
+
+    /// Deletes a row along with its dependents
+    pub async fn delete_cascade(&self, id: i32) -> fetch::Result<Response> {
+        fetch::Request::new(format!("{}{}/{}?cascade=true", API_URL, self.path(), id))
+            .method(Method::Delete)
+            .fetch()
+            .await
+    }
+}
+
+/// Passes a successful response through, otherwise reads the error the api
+/// sent back, if there is one
+pub async fn check(response: fetch::Result<Response>) -> Result<Response, Option<ApiError>> {
+    match response {
+        Ok(response) if response.status().is_ok() => Ok(response),
+        Ok(response) => Err(response.json().await.ok()),
+        Err(_) => Err(None),
+    }
 }
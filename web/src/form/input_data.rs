@@ -1,11 +1,12 @@
 use crate::page::PageError;
-use chrono::naive::{NaiveDate, NaiveTime};
+use chrono::naive::{NaiveDate, NaiveDateTime, NaiveTime};
+use diet_database::validate::Validate;
 
 pub enum InputData {
     Date(NaiveDate),
     Time(NaiveTime),
+    DateTime(NaiveDateTime),
     TimeOption(Option<NaiveTime>),
-    Byte(i8),
     Int(i32),
     IntOption(Option<i32>),
     Text(String),
@@ -27,15 +28,15 @@ impl InputData {
             _ => Err(PageError::Developer),
         }
     }
-    pub fn try_time_option(&self) -> Result<Option<NaiveTime>, PageError> {
+    pub fn try_datetime(&self) -> Result<NaiveDateTime, PageError> {
         match self {
-            InputData::TimeOption(d) => Ok(*d),
+            InputData::DateTime(d) => Ok(*d),
             _ => Err(PageError::Developer),
         }
     }
-    pub fn try_byte(&self) -> Result<i8, PageError> {
+    pub fn try_time_option(&self) -> Result<Option<NaiveTime>, PageError> {
         match self {
-            InputData::Byte(d) => Ok(*d),
+            InputData::TimeOption(d) => Ok(*d),
             _ => Err(PageError::Developer),
         }
     }
@@ -82,3 +83,34 @@ pub trait FromInputData {
     where
         Self: Sized;
 }
+
+/// Reads a row from the form and checks it against the same rules the api
+/// enforces
+pub fn validated<T: FromInputData + Validate>(inputs: Vec<InputData>) -> Result<T, PageError> {
+    let item = T::from_input_data(inputs)?;
+    item.validate().map_err(PageError::Invalid)?;
+    Ok(item)
+}
+
+/// Inverse of `FromInputData`: the raw values that fill a form with an
+/// existing row, in the same order as the form's inputs.
+pub trait ToFormValues {
+    fn to_form_values(&self) -> Vec<String>;
+}
+
+pub fn date_value(date: NaiveDate) -> String {
+    date.format("%Y-%m-%d").to_string()
+}
+
+pub fn datetime_value(datetime: NaiveDateTime) -> String {
+    datetime.format("%Y-%m-%dT%H:%M").to_string()
+}
+
+pub fn time_option_value(time: Option<NaiveTime>) -> String {
+    time.map(|t| t.format("%H:%M").to_string())
+        .unwrap_or_default()
+}
+
+pub fn option_value<T: ToString>(value: Option<T>) -> String {
+    value.map(|x| x.to_string()).unwrap_or_default()
+}
@@ -1,5 +1,5 @@
 use crate::page::{get_event_value, PageError};
-use chrono::naive::{NaiveDate, NaiveTime};
+use chrono::naive::{NaiveDate, NaiveDateTime, NaiveTime};
 use seed::{prelude::*, *};
 
 use super::*;
@@ -13,13 +13,19 @@ pub enum FormMsg {
 pub enum InputType {
     Date,
     Time,
+    /// A date and a time of day, e.g. when a night's sleep began
+    DateTime,
     TimeOption,
-    Range(usize, usize),
     Int,
     Text,
     TextOption,
     IntOption,
     DropDown(Vec<(i32, String)>),
+    /// One of a fixed set of values, read as text
+    Select(Vec<String>),
+    /// A row picked by typing its label, with matching labels suggested.
+    /// Read as the row's id, or `None` when left blank.
+    Search(Vec<(i32, String)>),
     Float,
     FloatOption,
 }
@@ -34,13 +40,13 @@ impl InputType {
             Time => NaiveTime::parse_from_str(s, "%H:%M")
                 .map(InputData::Time)
                 .map_err(|_| PageError::form("time")),
+            DateTime => NaiveDateTime::parse_from_str(s, "%Y-%m-%dT%H:%M")
+                .or_else(|_| NaiveDateTime::parse_from_str(s, "%Y-%m-%dT%H:%M:%S"))
+                .map(InputData::DateTime)
+                .map_err(|_| PageError::form("date and time")),
             TimeOption => Ok(InputData::TimeOption(
                 NaiveTime::parse_from_str(s, "%H:%M").ok(),
             )),
-            Range(_, _) => s
-                .parse::<i8>()
-                .map(InputData::Byte)
-                .map_err(|_| PageError::form("date")),
             Int => s
                 .parse::<i32>()
                 .map(InputData::Int)
@@ -66,12 +72,31 @@ impl InputType {
                     Err(PageError::form("foreign key"))
                 }
             }
+            Select(options) => {
+                if options.iter().any(|option| option == s) {
+                    Ok(InputData::Text(s.to_string()))
+                } else {
+                    Err(PageError::form("option"))
+                }
+            }
+            Search(options) => {
+                if s.is_empty() {
+                    Ok(InputData::IntOption(None))
+                } else {
+                    options
+                        .iter()
+                        .find(|(_, label)| label == s)
+                        .map(|(id, _)| InputData::IntOption(Some(*id)))
+                        .ok_or_else(|| PageError::form("search"))
+                }
+            }
         }
     }
 
     pub fn default_value(&self) -> String {
         match self {
             InputType::Date => chrono::Local::now().format("%Y-%m-%d").to_string(),
+            InputType::Select(options) => options.first().cloned().unwrap_or_default(),
             _ => String::new(),
         }
     }
@@ -81,22 +106,58 @@ impl InputType {
         let attrs = match self {
             Date => attrs!(At::Type => "date"),
             Time | TimeOption => attrs!(At::Type => "time"),
-            Range(min, max) => attrs!(At::Type => "range", At::Min => min, At::Max => max),
+            DateTime => attrs!(At::Type => "datetime-local"),
             Int | IntOption => attrs!(At::Type => "number"),
-            Text | TextOption | Float | FloatOption => attrs!(At::Type => "text"),
-            DropDown(_) => attrs!(),
+            Text | TextOption | Float | FloatOption | Search(_) => attrs!(At::Type => "text"),
+            DropDown(_) | Select(_) => attrs!(),
         };
         match self {
             DropDown(options) => select![
                 option![],
-                options
-                    .iter()
-                    .map(|option| { option![attrs!(At::Value => option.0), &option.1] }),
+                options.iter().map(|option| {
+                    let selected = option.0.to_string() == *value;
+                    option![
+                        attrs!(At::Value => option.0, At::Selected => selected.as_at_value()),
+                        &option.1
+                    ]
+                }),
                 ev(Ev::Change, move |ev| FormMsg::UpdateValue(
                     i,
                     get_event_value(ev)
                 )),
             ],
+            Select(options) => select![
+                options.iter().map(|option| {
+                    let selected = option == value;
+                    option![
+                        attrs!(At::Value => option, At::Selected => selected.as_at_value()),
+                        option
+                    ]
+                }),
+                ev(Ev::Change, move |ev| FormMsg::UpdateValue(
+                    i,
+                    get_event_value(ev)
+                )),
+            ],
+            Search(options) => {
+                let list = format!("input-{}-options", i);
+                div![
+                    input![
+                        attrs,
+                        attrs!(At::Value => value, At::List => &list),
+                        ev(Ev::Input, move |ev| FormMsg::UpdateValue(
+                            i,
+                            get_event_value(ev)
+                        )),
+                    ],
+                    datalist![
+                        id!(list),
+                        options
+                            .iter()
+                            .map(|(_, label)| option![attrs!(At::Value => label)]),
+                    ],
+                ]
+            }
             TimeOption => div![
                 button!["Clear", ev(Ev::Click, move |_| FormMsg::Clear(i))],
                 input![
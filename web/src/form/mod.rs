@@ -18,7 +18,6 @@ impl Form {
                 self.inputs[i].set(s);
             }
             FormMsg::Clear(i) => {
-                log!("clarning");
                 self.inputs[i].set(String::new());
             }
         }
@@ -38,6 +37,19 @@ impl Form {
         })
     }
 
+    /// Fills the `Search` input at `i` with the label of the row `id`
+    pub fn set_choice(&mut self, i: usize, id: Option<i32>) {
+        let input = &mut self.inputs[i];
+        let label = match (&input.typ, id) {
+            (InputType::Search(options), Some(id)) => options
+                .iter()
+                .find(|(option, _)| *option == id)
+                .map(|(_, label)| label.clone()),
+            _ => None,
+        };
+        input.set(label.unwrap_or_default());
+    }
+
     pub fn get_input_data(&self) -> Result<Vec<InputData>, PageError> {
         self.inputs.iter().map(|input| input.get_data()).collect() // shouldn't work?!?!?
     }
@@ -69,11 +81,10 @@ impl Input {
     }
 
     fn view(&self, i: usize) -> Node<FormMsg> {
-        let label = match self.typ {
-            InputType::Range(_, _) => format!("{}: {}", self.name, self.value),
-            _ => format!("{}: ", self.name),
-        };
-        div![label![label], self.typ.view(i, &self.value),]
+        div![
+            label![format!("{}: ", self.name)],
+            self.typ.view(i, &self.value),
+        ]
     }
 
     fn get_data(&self) -> Result<InputData, PageError> {
@@ -1,3 +1,5 @@
+use diet_database::units::UnitSystem;
+use diet_database::TableType;
 use seed::{prelude::*, *};
 
 mod api_call;
@@ -16,38 +18,40 @@ struct Model {
     page: Page,
 }
 
+/// A page for each table, followed by pages derived from them
 #[derive(Clone, Copy, Debug)]
 pub enum PageName {
-    Bowel,
-    Store,
-    GroceryTrip,
-    Metric,
-    Weight,
-    GroceryItem,
+    Table(TableType),
+    Composition,
 }
 
 impl PageName {
     fn init_with_data(self) -> Page {
-        use PageName::*;
+        use TableType::*;
         match self {
-            Bowel => Page::Bowel(page::bowel::init()),
-            Store => Page::Store(page::store::init()),
-            GroceryTrip => Page::GroceryTrip(page::grocery_trip::init()),
-            GroceryItem => Page::GroceryItem(page::grocery_item::init()),
-            Metric => Page::Metric(page::metric::init()),
-            Weight => Page::Weight(page::weight::init()),
+            PageName::Table(Bowel) => Page::Bowel(page::bowel::init()),
+            PageName::Table(Exercise) => Page::Exercise(page::exercise::init()),
+            PageName::Table(Food) => Page::Food(page::food::init()),
+            PageName::Table(FoodLog) => Page::FoodLog(page::food_log::init()),
+            PageName::Table(Store) => Page::Store(page::store::init()),
+            PageName::Table(GroceryTrip) => Page::GroceryTrip(page::grocery_trip::init()),
+            PageName::Table(GroceryItem) => Page::GroceryItem(page::grocery_item::init()),
+            PageName::Table(Recipe) => Page::Recipe(page::recipe::init()),
+            PageName::Table(RecipeIngredient) => {
+                Page::RecipeIngredient(page::recipe_ingredient::init())
+            }
+            PageName::Table(Metric) => Page::Metric(page::metric::init()),
+            PageName::Table(Sleep) => Page::Sleep(page::sleep::init()),
+            PageName::Table(Water) => Page::Water(page::water::init()),
+            PageName::Table(Weight) => Page::Weight(page::weight::init()),
+            PageName::Composition => Page::Composition(page::composition::init()),
         }
     }
 
     fn display_name(&self) -> String {
-        use PageName::*;
         match self {
-            Bowel => "Bowel Movements",
-            Store => "Grocery Stores",
-            GroceryTrip => "Grocery Trips",
-            GroceryItem => "Grocery Items",
-            Metric => "Body Metrics",
-            Weight => "Weight",
+            PageName::Table(table) => table.display_name(),
+            PageName::Composition => "Body Composition",
         }
         .to_string()
     }
@@ -55,49 +59,92 @@ impl PageName {
 
 pub enum Page {
     Bowel(page::bowel::Model),
+    Exercise(page::exercise::Model),
+    Food(page::food::Model),
+    FoodLog(page::food_log::Model),
     Store(page::store::Model),
     GroceryTrip(page::grocery_trip::Model),
     GroceryItem(page::grocery_item::Model),
+    Recipe(page::recipe::Model),
+    RecipeIngredient(page::recipe_ingredient::Model),
     Metric(page::metric::Model),
+    Sleep(page::sleep::Model),
+    Water(page::water::Model),
     Weight(page::weight::Model),
+    Composition(page::composition::Model),
 }
 
 pub enum Msg {
     LoadPage(Page),
     BowelPageUpdate(page::bowel::Msg),
+    ExercisePageUpdate(page::exercise::Msg),
+    FoodPageUpdate(page::food::Msg),
+    FoodLogPageUpdate(page::food_log::Msg),
     StorePageUpdate(page::store::Msg),
     GroceryTripPageUpdate(page::grocery_trip::Msg),
     GroceryItemPageUpdate(page::grocery_item::Msg),
+    RecipePageUpdate(page::recipe::Msg),
+    RecipeIngredientPageUpdate(page::recipe_ingredient::Msg),
     MetricPageUpdate(page::metric::Msg),
+    SleepPageUpdate(page::sleep::Msg),
+    WaterPageUpdate(page::water::Msg),
     WeightPageUpdate(page::weight::Msg),
+    CompositionPageUpdate(page::composition::Msg),
+    SetUnits(Option<UnitSystem>),
+}
+
+/// The msg that fetches the rows shown on `page`
+fn load_msg(current: &Page) -> Msg {
+    match current {
+        Page::Bowel(_) => Msg::BowelPageUpdate(page::bowel::Msg::load()),
+        Page::Exercise(_) => Msg::ExercisePageUpdate(page::exercise::Msg::load()),
+        Page::Food(_) => Msg::FoodPageUpdate(page::food::Msg::load()),
+        Page::FoodLog(_) => Msg::FoodLogPageUpdate(page::food_log::Msg::load()),
+        Page::Store(_) => Msg::StorePageUpdate(page::store::Msg::load()),
+        Page::GroceryTrip(_) => Msg::GroceryTripPageUpdate(page::grocery_trip::Msg::load()),
+        Page::GroceryItem(_) => Msg::GroceryItemPageUpdate(page::grocery_item::Msg::load()),
+        Page::Recipe(_) => Msg::RecipePageUpdate(page::recipe::Msg::load()),
+        Page::RecipeIngredient(_) => {
+            Msg::RecipeIngredientPageUpdate(page::recipe_ingredient::Msg::load())
+        }
+        Page::Metric(_) => Msg::MetricPageUpdate(page::metric::Msg::load()),
+        Page::Sleep(_) => Msg::SleepPageUpdate(page::sleep::Msg::load()),
+        Page::Water(_) => Msg::WaterPageUpdate(page::water::Msg::load()),
+        Page::Weight(_) => Msg::WeightPageUpdate(page::weight::Msg::load()),
+        Page::Composition(_) => Msg::CompositionPageUpdate(page::composition::Msg::load()),
+    }
 }
 
 fn update(msg: Msg, model: &mut Model, orders: &mut impl Orders<Msg>) {
     match msg {
         Msg::LoadPage(page) => {
-            match &page {
-                Page::Bowel(_) => orders.send_msg(Msg::BowelPageUpdate(page::bowel::Msg::load())),
-                Page::Store(_) => orders.send_msg(Msg::StorePageUpdate(page::store::Msg::load())),
-                Page::GroceryTrip(_) => {
-                    orders.send_msg(Msg::GroceryTripPageUpdate(page::grocery_trip::Msg::load()))
-                }
-                Page::GroceryItem(_) => {
-                    orders.send_msg(Msg::GroceryItemPageUpdate(page::grocery_item::Msg::load()))
-                }
-                Page::Metric(_) => {
-                    orders.send_msg(Msg::MetricPageUpdate(page::metric::Msg::load()))
-                }
-                Page::Weight(_) => {
-                    orders.send_msg(Msg::WeightPageUpdate(page::weight::Msg::load()))
-                }
-            };
+            orders.send_msg(load_msg(&page));
             model.page = page;
         }
+        Msg::SetUnits(units) => {
+            api_call::set_preferred_units(units);
+            orders.send_msg(load_msg(&model.page));
+        }
         Msg::BowelPageUpdate(msg) => {
             if let Page::Bowel(model) = &mut model.page {
                 page::bowel::update(msg, model, &mut orders.proxy(Msg::BowelPageUpdate));
             }
         }
+        Msg::ExercisePageUpdate(msg) => {
+            if let Page::Exercise(model) = &mut model.page {
+                page::exercise::update(msg, model, &mut orders.proxy(Msg::ExercisePageUpdate));
+            }
+        }
+        Msg::FoodPageUpdate(msg) => {
+            if let Page::Food(model) = &mut model.page {
+                page::food::update(msg, model, &mut orders.proxy(Msg::FoodPageUpdate));
+            }
+        }
+        Msg::FoodLogPageUpdate(msg) => {
+            if let Page::FoodLog(model) = &mut model.page {
+                page::food_log::update(msg, model, &mut orders.proxy(Msg::FoodLogPageUpdate));
+            }
+        }
         Msg::StorePageUpdate(msg) => {
             if let Page::Store(model) = &mut model.page {
                 page::store::update(msg, model, &mut orders.proxy(Msg::StorePageUpdate));
@@ -121,16 +168,49 @@ fn update(msg: Msg, model: &mut Model, orders: &mut impl Orders<Msg>) {
                 );
             }
         }
+        Msg::RecipePageUpdate(msg) => {
+            if let Page::Recipe(model) = &mut model.page {
+                page::recipe::update(msg, model, &mut orders.proxy(Msg::RecipePageUpdate));
+            }
+        }
+        Msg::RecipeIngredientPageUpdate(msg) => {
+            if let Page::RecipeIngredient(model) = &mut model.page {
+                page::recipe_ingredient::update(
+                    msg,
+                    model,
+                    &mut orders.proxy(Msg::RecipeIngredientPageUpdate),
+                );
+            }
+        }
         Msg::MetricPageUpdate(msg) => {
             if let Page::Metric(model) = &mut model.page {
                 page::metric::update(msg, model, &mut orders.proxy(Msg::MetricPageUpdate));
             }
         }
+        Msg::SleepPageUpdate(msg) => {
+            if let Page::Sleep(model) = &mut model.page {
+                page::sleep::update(msg, model, &mut orders.proxy(Msg::SleepPageUpdate));
+            }
+        }
+        Msg::WaterPageUpdate(msg) => {
+            if let Page::Water(model) = &mut model.page {
+                page::water::update(msg, model, &mut orders.proxy(Msg::WaterPageUpdate));
+            }
+        }
         Msg::WeightPageUpdate(msg) => {
             if let Page::Weight(model) = &mut model.page {
                 page::weight::update(msg, model, &mut orders.proxy(Msg::WeightPageUpdate));
             }
         }
+        Msg::CompositionPageUpdate(msg) => {
+            if let Page::Composition(model) = &mut model.page {
+                page::composition::update(
+                    msg,
+                    model,
+                    &mut orders.proxy(Msg::CompositionPageUpdate),
+                );
+            }
+        }
     }
 }
 
@@ -141,26 +221,30 @@ fn view(model: &Model) -> Node<Msg> {
 fn view_page(model: &Model) -> Node<Msg> {
     match &model.page {
         Page::Bowel(model) => model.view().map_msg(Msg::BowelPageUpdate),
+        Page::Exercise(model) => model.view().map_msg(Msg::ExercisePageUpdate),
+        Page::Food(model) => model.view().map_msg(Msg::FoodPageUpdate),
+        Page::FoodLog(model) => model.view().map_msg(Msg::FoodLogPageUpdate),
         Page::Store(model) => model.view().map_msg(Msg::StorePageUpdate),
         Page::GroceryTrip(model) => model.view().map_msg(Msg::GroceryTripPageUpdate),
         Page::GroceryItem(model) => model.view().map_msg(Msg::GroceryItemPageUpdate),
+        Page::Recipe(model) => model.view().map_msg(Msg::RecipePageUpdate),
+        Page::RecipeIngredient(model) => model.view().map_msg(Msg::RecipeIngredientPageUpdate),
         Page::Metric(model) => model.view().map_msg(Msg::MetricPageUpdate),
+        Page::Sleep(model) => model.view().map_msg(Msg::SleepPageUpdate),
+        Page::Water(model) => model.view().map_msg(Msg::WaterPageUpdate),
         Page::Weight(model) => model.view().map_msg(Msg::WeightPageUpdate),
+        Page::Composition(model) => model.view().map_msg(Msg::CompositionPageUpdate),
     }
 }
 
 fn view_page_selector(_model: &Model) -> Node<Msg> {
-    let page_names = vec![
-        PageName::Bowel,
-        PageName::Store,
-        PageName::GroceryTrip,
-        PageName::GroceryItem,
-        PageName::Metric,
-        PageName::Weight,
-    ];
+    let page_names = TableType::ALL
+        .iter()
+        .map(|&table| PageName::Table(table))
+        .chain(std::iter::once(PageName::Composition));
     nav![
         C!["page-selector"],
-        page_names.into_iter().map(|page_name| {
+        page_names.map(|page_name| {
             div![
                 page_name.display_name(),
                 ev(Ev::Click, move |_| Msg::LoadPage(
@@ -168,6 +252,29 @@ fn view_page_selector(_model: &Model) -> Node<Msg> {
                 ))
             ]
         }),
+        view_units_selector(),
+    ]
+}
+
+fn view_units_selector() -> Node<Msg> {
+    let current = api_call::preferred_units();
+    let choices = vec![
+        (None, "As entered"),
+        (Some(UnitSystem::Metric), "Metric"),
+        (Some(UnitSystem::Imperial), "Imperial"),
+    ];
+    select![
+        C!["units-selector"],
+        choices.into_iter().map(|(units, label)| {
+            let value = units.map(|x| x.to_string()).unwrap_or_default();
+            option![
+                attrs!(At::Value => value, At::Selected => (units == current).as_at_value()),
+                label
+            ]
+        }),
+        ev(Ev::Change, |ev| Msg::SetUnits(
+            page::get_event_value(ev).parse().ok()
+        )),
     ]
 }
 
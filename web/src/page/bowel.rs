@@ -1,6 +1,8 @@
 use crate::api_call::ApiCall;
 use diet_database::bowel::*;
+use diet_database::TableType;
 use seed::{prelude::*, *};
+use std::convert::TryFrom;
 
 use super::*;
 use crate::form::*;
@@ -10,6 +12,7 @@ pub enum Msg {
     Fetched(Result<Vec<Bowel>, PageError>),
     FormUpdate(FormMsg),
     Edit(usize),
+    CancelEdit,
     Delete(usize),
     Deleted(Result<(), PageError>),
     Submit,
@@ -23,6 +26,9 @@ impl PageMsg for Msg {
     fn edit(i: usize) -> Self {
         Msg::Edit(i)
     }
+    fn cancel_edit() -> Self {
+        Msg::CancelEdit
+    }
     fn submit() -> Self {
         Msg::Submit
     }
@@ -36,6 +42,7 @@ pub struct Model {
     bowels: Vec<Bowel>,
     form: Form,
     err: Option<PageError>,
+    editing: Option<i32>,
 }
 
 impl PageModel<Vec<Bowel>, Msg> for Model {
@@ -47,18 +54,31 @@ impl PageModel<Vec<Bowel>, Msg> for Model {
         self.err.as_ref()
     }
 
+    fn is_editing(&self) -> bool {
+        self.editing.is_some()
+    }
+
     fn form_fields(&self) -> Vec<Node<Msg>> {
         self.form.view().map_msg(Msg::FormUpdate)
     }
 }
 
+/// Every Bristol type, labelled with its description
+fn bristol_input() -> InputType {
+    let options = Bristol::ALL
+        .iter()
+        .map(|b| (i32::from(b.number()), b.to_string()))
+        .collect();
+    InputType::DropDown(options)
+}
+
 pub fn init() -> Model {
     Model {
         form: Form {
             inputs: vec![
                 Input::new("Date", InputType::Date),
                 Input::new("Time", InputType::TimeOption),
-                Input::with_initial("Scale", InputType::Range(1, 7), "4"),
+                Input::with_initial("Scale", bristol_input(), "4"),
             ],
         },
         ..Default::default()
@@ -70,23 +90,30 @@ impl FromInputData for NewBowel {
         Ok(NewBowel {
             date: inputs[0].try_date()?,
             time: inputs[1].try_time_option()?,
-            scale: inputs[2].try_byte()?,
+            scale: i8::try_from(inputs[2].try_int()?)
+                .ok()
+                .and_then(|n| Bristol::try_from(n).ok())
+                .ok_or(PageError::Developer)?,
         })
     }
 }
 
+impl ToFormValues for Bowel {
+    fn to_form_values(&self) -> Vec<String> {
+        vec![
+            date_value(self.date),
+            time_option_value(self.time),
+            self.scale.number().to_string(),
+        ]
+    }
+}
+
 pub fn update(msg: Msg, model: &mut Model, orders: &mut impl Orders<Msg>) {
     use Msg::*;
+    let api_call = ApiCall::Table(TableType::Bowel);
     match msg {
         Fetch => {
-            orders.perform_cmd({
-                async move {
-                    match ApiCall::Bowel.get().await {
-                        Ok(s) => Fetched(Ok(s)),
-                        Err(_) => Fetched(Err(PageError::Load)),
-                    }
-                }
-            });
+            fetch_rows(api_call, orders, Fetched);
         }
         Fetched(result) => match result {
             Ok(bowels) => model.bowels = bowels,
@@ -94,21 +121,14 @@ pub fn update(msg: Msg, model: &mut Model, orders: &mut impl Orders<Msg>) {
         },
         FormUpdate(update_msg) => model.form.update(update_msg),
         Edit(idx) => {
-            let b = model.bowels[idx];
-            model.form.set_all(&vec![b].matrix()[0]);
+            let b = &model.bowels[idx];
+            model.editing = Some(b.id);
+            model.form.set_all(&b.to_form_values());
         }
+        CancelEdit => model.editing = None,
         Delete(idx) => {
             let b = model.bowels[idx];
-            if confirm(b) {
-                orders.perform_cmd({
-                    async move {
-                        match ApiCall::Bowel.delete(b).await {
-                            Ok(s) if s.status().is_ok() => Deleted(Ok(())),
-                            _ => Deleted(Err(PageError::Delete)),
-                        }
-                    }
-                });
-            }
+            delete_row(api_call, b, b.id, orders, Deleted);
         }
         Deleted(result) => match result {
             Ok(()) => {
@@ -116,29 +136,23 @@ pub fn update(msg: Msg, model: &mut Model, orders: &mut impl Orders<Msg>) {
             }
             Err(err) => model.err = Some(err),
         },
-        Submit => match model.form.get_input_data() {
-            Ok(inputs) => match NewBowel::from_input_data(inputs) {
-                Ok(nb) => {
-                    model.err = None;
-                    orders.perform_cmd({
-                        async move {
-                            match ApiCall::Bowel.post(nb).await {
-                                Ok(s) if s.status().is_ok() => Submitted(Ok(())),
-                                _ => Submitted(Err(PageError::Submit)),
-                            }
-                        }
-                    });
-                }
-                Err(err) => model.err = Some(err),
-            },
-            Err(err) => model.err = Some(err),
-        },
+        Submit => {
+            let item = model.form.get_input_data().and_then(validated::<NewBowel>);
+            submit_row(
+                api_call,
+                item,
+                model.editing,
+                &mut model.err,
+                orders,
+                Submitted,
+            );
+        }
         Submitted(result) => match result {
             Ok(()) => {
+                model.editing = None;
                 orders.send_msg(Fetch);
             }
             Err(err) => model.err = Some(err),
         },
     }
-    log!(model);
 }
@@ -0,0 +1,61 @@
+use crate::api_call::ApiCall;
+use diet_database::composition::Composition;
+use seed::{prelude::*, *};
+
+use super::*;
+
+/// Fat and lean mass worked out from the weights and body metrics.  There is
+/// nothing to enter here, so unlike the other pages it has no form.
+pub enum Msg {
+    Fetch,
+    Fetched(Result<Vec<Composition>, PageError>),
+}
+
+impl Msg {
+    pub fn load() -> Self {
+        Msg::Fetch
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Model {
+    readings: Vec<Composition>,
+    err: Option<PageError>,
+}
+
+impl Model {
+    pub fn view(&self) -> Node<Msg> {
+        div![
+            C!["page"],
+            view_table(&self.readings, |_| Vec::new()),
+            view_error_msg(self.err.as_ref()),
+        ]
+    }
+}
+
+pub fn init() -> Model {
+    Model::default()
+}
+
+pub fn update(msg: Msg, model: &mut Model, orders: &mut impl Orders<Msg>) {
+    use Msg::*;
+    match msg {
+        Fetch => {
+            orders.perform_cmd({
+                async move {
+                    match ApiCall::Composition.get().await {
+                        Ok(readings) => Fetched(Ok(readings)),
+                        Err(_) => Fetched(Err(PageError::Load)),
+                    }
+                }
+            });
+        }
+        Fetched(result) => match result {
+            Ok(readings) => {
+                model.err = None;
+                model.readings = readings;
+            }
+            Err(err) => model.err = Some(err),
+        },
+    }
+}
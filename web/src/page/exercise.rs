@@ -0,0 +1,204 @@
+use crate::api_call::{preferred_units, ApiCall};
+use diet_database::exercise::*;
+use diet_database::units::DistanceUnit;
+use diet_database::TableType;
+use seed::{prelude::*, *};
+
+use super::*;
+use crate::form::*;
+
+pub enum Msg {
+    Fetch,
+    Fetched(Result<Vec<Exercise>, PageError>),
+    FetchedDaily(Result<Vec<DailyBurn>, PageError>),
+    FormUpdate(FormMsg),
+    Edit(usize),
+    CancelEdit,
+    Delete(usize),
+    Deleted(Result<(), PageError>),
+    Submit,
+    Submitted(Result<(), PageError>),
+}
+
+impl PageMsg for Msg {
+    fn delete(i: usize) -> Self {
+        Msg::Delete(i)
+    }
+    fn edit(i: usize) -> Self {
+        Msg::Edit(i)
+    }
+    fn cancel_edit() -> Self {
+        Msg::CancelEdit
+    }
+    fn submit() -> Self {
+        Msg::Submit
+    }
+    fn load() -> Self {
+        Msg::Fetch
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Model {
+    data: Vec<Exercise>,
+    daily: Vec<DailyBurn>,
+    form: Form,
+    err: Option<PageError>,
+    editing: Option<i32>,
+}
+
+impl PageModel<Vec<Exercise>, Msg> for Model {
+    fn data(&self) -> &Vec<Exercise> {
+        &self.data
+    }
+
+    fn error(&self) -> Option<&PageError> {
+        self.err.as_ref()
+    }
+
+    fn is_editing(&self) -> bool {
+        self.editing.is_some()
+    }
+
+    fn form_fields(&self) -> Vec<Node<Msg>> {
+        self.form.view().map_msg(Msg::FormUpdate)
+    }
+
+    /// The workouts are followed by the calories burned each day, newest
+    /// first, against those eaten
+    fn view(&self) -> Node<Msg> {
+        let mut daily = self.daily.clone();
+        daily.reverse();
+        div![
+            C!["page"],
+            self.view_form(),
+            self.view_table(),
+            h3!["Daily Totals"],
+            view_table(&daily, |_| Vec::new()),
+        ]
+    }
+}
+
+impl FromInputData for NewExercise {
+    fn from_input_data(inputs: Vec<InputData>) -> Result<Self, PageError> {
+        Ok(Self {
+            date: inputs[0].try_date()?,
+            time: inputs[1].try_time_option()?,
+            activity: inputs[2]
+                .try_text()?
+                .parse()
+                .map_err(|_| PageError::form("activity"))?,
+            duration: inputs[3].try_int()?,
+            intensity: inputs[4]
+                .try_text()?
+                .parse()
+                .map_err(|_| PageError::form("intensity"))?,
+            distance: inputs[5].try_float_option()?,
+            distance_unit: inputs[6]
+                .try_text()?
+                .parse()
+                .map_err(|_| PageError::form("distance unit"))?,
+        })
+    }
+}
+
+impl ToFormValues for Exercise {
+    fn to_form_values(&self) -> Vec<String> {
+        vec![
+            date_value(self.date),
+            time_option_value(self.time),
+            self.activity.to_string(),
+            self.duration.to_string(),
+            self.intensity.to_string(),
+            option_value(self.distance),
+            self.distance_unit.to_string(),
+        ]
+    }
+}
+
+pub fn init() -> Model {
+    let units = vec![DistanceUnit::Km, DistanceUnit::Mi];
+    let unit = preferred_units().unwrap_or_default().distance();
+    Model {
+        form: Form {
+            inputs: vec![
+                Input::new("Date", InputType::Date),
+                Input::new("Time", InputType::TimeOption),
+                Input::new(
+                    "Activity",
+                    InputType::Select(Activity::ALL.iter().map(|x| x.to_string()).collect()),
+                ),
+                Input::new("Duration (min)", InputType::Int),
+                Input::with_initial(
+                    "Intensity",
+                    InputType::Select(Intensity::ALL.iter().map(|x| x.to_string()).collect()),
+                    &Intensity::Moderate.to_string(),
+                ),
+                Input::new("Distance", InputType::FloatOption),
+                Input::with_initial(
+                    "Distance unit",
+                    InputType::Select(units.iter().map(|x| x.to_string()).collect()),
+                    unit.suffix(),
+                ),
+            ],
+        },
+        ..Default::default()
+    }
+}
+
+pub fn update(msg: Msg, model: &mut Model, orders: &mut impl Orders<Msg>) {
+    use Msg::*;
+    let api_call = ApiCall::Table(TableType::Exercise);
+    match msg {
+        Fetch => {
+            fetch_rows(api_call, orders, Fetched);
+            fetch_rows(ApiCall::ExerciseDaily, orders, FetchedDaily);
+        }
+        Fetched(result) => match result {
+            Ok(data) => model.data = data,
+            Err(err) => model.err = Some(err),
+        },
+        FetchedDaily(result) => match result {
+            Ok(daily) => model.daily = daily,
+            Err(err) => model.err = Some(err),
+        },
+        FormUpdate(update_msg) => model.form.update(update_msg),
+        Edit(idx) => {
+            let b = &model.data[idx];
+            model.editing = Some(b.id);
+            model.form.set_all(&b.to_form_values());
+        }
+        CancelEdit => model.editing = None,
+        Delete(idx) => {
+            let b = model.data[idx];
+            delete_row(api_call, b, b.id, orders, Deleted);
+        }
+        Deleted(result) => match result {
+            Ok(()) => {
+                orders.send_msg(Fetch);
+            }
+            Err(err) => model.err = Some(err),
+        },
+        Submit => {
+            let item = model
+                .form
+                .get_input_data()
+                .and_then(validated::<NewExercise>);
+            submit_row(
+                api_call,
+                item,
+                model.editing,
+                &mut model.err,
+                orders,
+                Submitted,
+            );
+        }
+        Submitted(result) => match result {
+            Ok(()) => {
+                model.editing = None;
+                orders.send_msg(Fetch);
+            }
+            Err(err) => model.err = Some(err),
+        },
+    }
+}
@@ -0,0 +1,170 @@
+use crate::api_call::ApiCall;
+use diet_database::dependents::Dependents;
+use diet_database::food::*;
+use diet_database::TableType;
+use seed::{prelude::*, *};
+
+use super::*;
+use crate::form::*;
+
+pub enum Msg {
+    Fetch,
+    Fetched(Result<Vec<Food>, PageError>),
+    FormUpdate(FormMsg),
+    Edit(usize),
+    CancelEdit,
+    Delete(usize),
+    FetchedDependents(usize, Result<Dependents, PageError>),
+    Deleted(Result<(), PageError>),
+    Submit,
+    Submitted(Result<(), PageError>),
+}
+
+impl PageMsg for Msg {
+    fn delete(i: usize) -> Self {
+        Msg::Delete(i)
+    }
+    fn edit(i: usize) -> Self {
+        Msg::Edit(i)
+    }
+    fn cancel_edit() -> Self {
+        Msg::CancelEdit
+    }
+    fn submit() -> Self {
+        Msg::Submit
+    }
+    fn load() -> Self {
+        Msg::Fetch
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Model {
+    data: Vec<Food>,
+    form: Form,
+    err: Option<PageError>,
+    editing: Option<i32>,
+}
+
+impl PageModel<Vec<Food>, Msg> for Model {
+    fn data(&self) -> &Vec<Food> {
+        &self.data
+    }
+
+    fn error(&self) -> Option<&PageError> {
+        self.err.as_ref()
+    }
+
+    fn is_editing(&self) -> bool {
+        self.editing.is_some()
+    }
+
+    fn form_fields(&self) -> Vec<Node<Msg>> {
+        self.form.view().map_msg(Msg::FormUpdate)
+    }
+}
+
+impl FromInputData for NewFood {
+    fn from_input_data(inputs: Vec<InputData>) -> Result<Self, PageError> {
+        Ok(NewFood {
+            name: inputs[0].try_text()?,
+            brand: inputs[1].try_text_option()?,
+            serving_size: inputs[2].try_float()?,
+            serving_unit: inputs[3].try_text()?,
+            calories: inputs[4].try_float()?,
+            protein: inputs[5].try_float_option()?,
+            carbs: inputs[6].try_float_option()?,
+            fat: inputs[7].try_float_option()?,
+            fiber: inputs[8].try_float_option()?,
+        })
+    }
+}
+
+impl ToFormValues for Food {
+    fn to_form_values(&self) -> Vec<String> {
+        vec![
+            self.name.clone(),
+            option_value(self.brand.as_ref()),
+            self.serving_size.to_string(),
+            self.serving_unit.clone(),
+            self.calories.to_string(),
+            option_value(self.protein),
+            option_value(self.carbs),
+            option_value(self.fat),
+            option_value(self.fiber),
+        ]
+    }
+}
+
+pub fn init() -> Model {
+    Model {
+        form: Form {
+            inputs: vec![
+                Input::new("Name", InputType::Text),
+                Input::new("Brand", InputType::TextOption),
+                Input::new("Serving size", InputType::Float),
+                Input::with_initial("Serving unit", InputType::Text, "g"),
+                Input::new("Calories per serving", InputType::Float),
+                Input::new("Protein (g)", InputType::FloatOption),
+                Input::new("Carbs (g)", InputType::FloatOption),
+                Input::new("Fat (g)", InputType::FloatOption),
+                Input::new("Fiber (g)", InputType::FloatOption),
+            ],
+        },
+        ..Default::default()
+    }
+}
+
+pub fn update(msg: Msg, model: &mut Model, orders: &mut impl Orders<Msg>) {
+    use Msg::*;
+    let api_call = ApiCall::Table(TableType::Food);
+    match msg {
+        Fetch => {
+            fetch_rows(api_call, orders, Fetched);
+        }
+        Fetched(result) => match result {
+            Ok(data) => model.data = data,
+            Err(err) => model.err = Some(err),
+        },
+        FormUpdate(update_msg) => model.form.update(update_msg),
+        Edit(idx) => {
+            let b = &model.data[idx];
+            model.editing = Some(b.id);
+            model.form.set_all(&b.to_form_values());
+        }
+        CancelEdit => model.editing = None,
+        Delete(idx) => {
+            let id = model.data[idx].id;
+            fetch_dependents(api_call, id, orders, move |x| FetchedDependents(idx, x));
+        }
+        FetchedDependents(idx, Ok(dependents)) => {
+            let b = &model.data[idx];
+            delete_row_cascade(api_call, b.clone(), b.id, dependents, orders, Deleted);
+        }
+        FetchedDependents(_, Err(err)) => model.err = Some(err),
+        Deleted(result) => match result {
+            Ok(()) => {
+                orders.send_msg(Fetch);
+            }
+            Err(err) => model.err = Some(err),
+        },
+        Submit => {
+            let item = model.form.get_input_data().and_then(validated::<NewFood>);
+            submit_row(
+                api_call,
+                item,
+                model.editing,
+                &mut model.err,
+                orders,
+                Submitted,
+            );
+        }
+        Submitted(result) => match result {
+            Ok(()) => {
+                model.editing = None;
+                orders.send_msg(Fetch);
+            }
+            Err(err) => model.err = Some(err),
+        },
+    }
+}
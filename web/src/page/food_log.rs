@@ -0,0 +1,234 @@
+use crate::api_call::ApiCall;
+use diet_database::food::Food;
+use diet_database::food_log::*;
+use diet_database::recipe::Recipe;
+use diet_database::TableType;
+use seed::{prelude::*, *};
+
+use super::*;
+use crate::form::*;
+
+pub enum Msg {
+    Fetch,
+    Fetched(Result<Vec<FoodLog>, PageError>),
+    FetchedFoods(Result<Vec<Food>, PageError>),
+    FetchedRecipes(Result<Vec<Recipe>, PageError>),
+    FormUpdate(FormMsg),
+    Edit(usize),
+    CancelEdit,
+    Delete(usize),
+    Deleted(Result<(), PageError>),
+    Submit,
+    Submitted(Result<(), PageError>),
+}
+
+impl PageMsg for Msg {
+    fn delete(i: usize) -> Self {
+        Msg::Delete(i)
+    }
+    fn edit(i: usize) -> Self {
+        Msg::Edit(i)
+    }
+    fn cancel_edit() -> Self {
+        Msg::CancelEdit
+    }
+    fn submit() -> Self {
+        Msg::Submit
+    }
+    fn load() -> Self {
+        Msg::Fetch
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Model {
+    data: Vec<FoodLog>,
+    form: Form,
+    err: Option<PageError>,
+    editing: Option<i32>,
+}
+
+impl PageModel<Vec<FoodLog>, Msg> for Model {
+    fn data(&self) -> &Vec<FoodLog> {
+        &self.data
+    }
+
+    fn error(&self) -> Option<&PageError> {
+        self.err.as_ref()
+    }
+
+    fn is_editing(&self) -> bool {
+        self.editing.is_some()
+    }
+
+    fn form_fields(&self) -> Vec<Node<Msg>> {
+        self.form.view().map_msg(Msg::FormUpdate)
+    }
+
+    /// The entries are followed by what was eaten each day, newest first
+    fn view(&self) -> Node<Msg> {
+        let mut totals = daily_totals(&self.data);
+        totals.reverse();
+        div![
+            C!["page"],
+            self.view_form(),
+            self.view_table(),
+            h3!["Daily Totals"],
+            view_table(&totals, |_| Vec::new()),
+        ]
+    }
+}
+
+/// Where the catalog food and recipe are picked, filled by label when
+/// editing
+const FOOD_INPUT: usize = 3;
+const RECIPE_INPUT: usize = 4;
+
+impl FromInputData for NewFoodLog {
+    fn from_input_data(inputs: Vec<InputData>) -> Result<Self, PageError> {
+        let food_id = inputs[FOOD_INPUT].try_int_option()?;
+        let recipe_id = inputs[RECIPE_INPUT].try_int_option()?;
+        // The api fills in the calories of a catalog food or recipe
+        let calories = match inputs[7].try_float_option()? {
+            Some(calories) => calories,
+            None if food_id.is_some() || recipe_id.is_some() => 0.0,
+            None => return Err(PageError::form("Calories per serving")),
+        };
+        Ok(NewFoodLog {
+            date: inputs[0].try_date()?,
+            time: inputs[1].try_time_option()?,
+            meal: inputs[2]
+                .try_text()?
+                .parse()
+                .map_err(|_| PageError::form("meal"))?,
+            name: inputs[5].try_text()?,
+            servings: inputs[6].try_float()?,
+            calories,
+            protein: inputs[8].try_float_option()?,
+            carbs: inputs[9].try_float_option()?,
+            fat: inputs[10].try_float_option()?,
+            fiber: inputs[11].try_float_option()?,
+            food_id,
+            recipe_id,
+        })
+    }
+}
+
+impl ToFormValues for FoodLog {
+    fn to_form_values(&self) -> Vec<String> {
+        vec![
+            date_value(self.date),
+            time_option_value(self.time),
+            self.meal.to_string(),
+            String::new(),
+            String::new(),
+            self.name.clone(),
+            self.servings.to_string(),
+            self.calories.to_string(),
+            option_value(self.protein),
+            option_value(self.carbs),
+            option_value(self.fat),
+            option_value(self.fiber),
+        ]
+    }
+}
+
+pub fn init() -> Model {
+    Model {
+        form: Form {
+            inputs: vec![
+                Input::new("Date", InputType::Date),
+                Input::new("Time", InputType::TimeOption),
+                Input::new(
+                    "Meal",
+                    InputType::Select(Meal::ALL.iter().map(|x| x.to_string()).collect()),
+                ),
+                Input::new("Catalog food", InputType::Search(vec![])),
+                Input::new("Recipe", InputType::Search(vec![])),
+                Input::new("Other food", InputType::Text),
+                Input::with_initial("Servings", InputType::Float, "1"),
+                Input::new("Calories per serving", InputType::FloatOption),
+                Input::new("Protein (g)", InputType::FloatOption),
+                Input::new("Carbs (g)", InputType::FloatOption),
+                Input::new("Fat (g)", InputType::FloatOption),
+                Input::new("Fiber (g)", InputType::FloatOption),
+            ],
+        },
+        ..Default::default()
+    }
+}
+
+pub fn update(msg: Msg, model: &mut Model, orders: &mut impl Orders<Msg>) {
+    use Msg::*;
+    let api_call = ApiCall::Table(TableType::FoodLog);
+    match msg {
+        Fetch => {
+            fetch_rows(api_call, orders, Fetched);
+            fetch_rows(ApiCall::Table(TableType::Food), orders, FetchedFoods);
+            fetch_rows(ApiCall::Table(TableType::Recipe), orders, FetchedRecipes);
+        }
+        Fetched(result) => match result {
+            Ok(data) => model.data = data,
+            Err(err) => model.err = Some(err),
+        },
+        FetchedFoods(Ok(foods)) => {
+            model.form.inputs[FOOD_INPUT] = Input::new(
+                "Catalog food",
+                InputType::Search(foods.iter().map(|food| (food.id, food.label())).collect()),
+            )
+        }
+        FetchedFoods(Err(err)) => model.err = Some(err),
+        FetchedRecipes(Ok(recipes)) => {
+            model.form.inputs[RECIPE_INPUT] = Input::new(
+                "Recipe",
+                InputType::Search(
+                    recipes
+                        .into_iter()
+                        .map(|recipe| (recipe.id, recipe.name))
+                        .collect(),
+                ),
+            )
+        }
+        FetchedRecipes(Err(err)) => model.err = Some(err),
+        FormUpdate(update_msg) => model.form.update(update_msg),
+        Edit(idx) => {
+            let b = &model.data[idx];
+            model.editing = Some(b.id);
+            model.form.set_all(&b.to_form_values());
+            model.form.set_choice(FOOD_INPUT, b.food_id);
+            model.form.set_choice(RECIPE_INPUT, b.recipe_id);
+        }
+        CancelEdit => model.editing = None,
+        Delete(idx) => {
+            let b = &model.data[idx];
+            delete_row(api_call, b.clone(), b.id, orders, Deleted);
+        }
+        Deleted(result) => match result {
+            Ok(()) => {
+                orders.send_msg(Fetch);
+            }
+            Err(err) => model.err = Some(err),
+        },
+        Submit => {
+            let item = model
+                .form
+                .get_input_data()
+                .and_then(validated::<NewFoodLog>);
+            submit_row(
+                api_call,
+                item,
+                model.editing,
+                &mut model.err,
+                orders,
+                Submitted,
+            );
+        }
+        Submitted(result) => match result {
+            Ok(()) => {
+                model.editing = None;
+                orders.send_msg(Fetch);
+            }
+            Err(err) => model.err = Some(err),
+        },
+    }
+}
@@ -1,6 +1,9 @@
 use crate::api_call::ApiCall;
+use diet_database::food::Food;
 use diet_database::grocery_item::*;
 use diet_database::grocery_trip::GroceryTrip;
+use diet_database::timestamp::Timestamped;
+use diet_database::TableType;
 use seed::{prelude::*, *};
 
 use super::*;
@@ -10,8 +13,10 @@ pub enum Msg {
     Fetch,
     Fetched(Result<Vec<GroceryItem>, PageError>),
     FetchedTrips(Result<Vec<GroceryTrip>, PageError>),
+    FetchedFoods(Result<Vec<Food>, PageError>),
     FormUpdate(FormMsg),
     Edit(usize),
+    CancelEdit,
     Delete(usize),
     Deleted(Result<(), PageError>),
     Submit,
@@ -31,6 +36,9 @@ impl PageMsg for Msg {
     fn edit(i: usize) -> Self {
         Msg::Edit(i)
     }
+    fn cancel_edit() -> Self {
+        Msg::CancelEdit
+    }
 }
 
 #[derive(Debug, Clone, Default)]
@@ -38,6 +46,7 @@ pub struct Model {
     trips: Vec<GroceryItem>,
     form: Form,
     err: Option<PageError>,
+    editing: Option<i32>,
 }
 
 impl PageModel<Vec<GroceryItem>, Msg> for Model {
@@ -49,6 +58,10 @@ impl PageModel<Vec<GroceryItem>, Msg> for Model {
         self.err.as_ref()
     }
 
+    fn is_editing(&self) -> bool {
+        self.editing.is_some()
+    }
+
     fn form_fields(&self) -> Vec<Node<Msg>> {
         self.form.view().map_msg(Msg::FormUpdate)
     }
@@ -62,6 +75,7 @@ pub fn init() -> Model {
                 Input::new("Name", InputType::Text),
                 Input::new("Amount", InputType::FloatOption),
                 Input::new("Measure", InputType::TextOption),
+                Input::new("Catalog food", InputType::Search(vec![])),
             ],
         },
         ..Default::default()
@@ -75,30 +89,31 @@ impl FromInputData for NewGroceryItem {
             name: inputs[1].try_text()?,
             amount: inputs[2].try_float_option()?,
             measure: inputs[3].try_text_option()?,
+            food_id: inputs[4].try_int_option()?,
         })
     }
 }
 
+impl ToFormValues for GroceryItem {
+    fn to_form_values(&self) -> Vec<String> {
+        vec![
+            self.trip_id.to_string(),
+            self.name.clone(),
+            option_value(self.amount),
+            option_value(self.measure.as_ref()),
+            String::new(),
+        ]
+    }
+}
+
 pub fn update(msg: Msg, model: &mut Model, orders: &mut impl Orders<Msg>) {
     use Msg::*;
+    let api_call = ApiCall::Table(TableType::GroceryItem);
     match msg {
         Fetch => {
-            orders.perform_cmd({
-                async move {
-                    match ApiCall::GroceryItem.get().await {
-                        Ok(s) => Fetched(Ok(s)),
-                        Err(_) => Fetched(Err(PageError::Load)),
-                    }
-                }
-            });
-            orders.perform_cmd({
-                async move {
-                    match ApiCall::GroceryTrip.get().await {
-                        Ok(s) => FetchedTrips(Ok(s)),
-                        Err(_) => FetchedTrips(Err(PageError::Load)),
-                    }
-                }
-            });
+            fetch_rows(api_call, orders, Fetched);
+            fetch_rows(ApiCall::Table(TableType::GroceryTrip), orders, FetchedTrips);
+            fetch_rows(ApiCall::Table(TableType::Food), orders, FetchedFoods);
         }
         Fetched(Ok(trips)) => model.trips = trips,
         Fetched(Err(err)) => model.err = Some(err),
@@ -108,55 +123,53 @@ pub fn update(msg: Msg, model: &mut Model, orders: &mut impl Orders<Msg>) {
                 InputType::DropDown(
                     trips
                         .into_iter()
-                        .map(|trip| (trip.id, trip.date.to_string()))
+                        .map(|trip| (trip.id, trip.timestamp().to_string()))
                         .collect(),
                 ),
             )
         }
         FetchedTrips(Err(err)) => model.err = Some(err),
+        FetchedFoods(Ok(foods)) => {
+            model.form.inputs[4] = Input::new(
+                "Catalog food",
+                InputType::Search(foods.iter().map(|food| (food.id, food.label())).collect()),
+            )
+        }
+        FetchedFoods(Err(err)) => model.err = Some(err),
         FormUpdate(update_msg) => model.form.update(update_msg),
         Edit(idx) => {
-            let b = model.trips[idx].clone();
-            model.form.set_all(&vec![b].matrix()[0]);
+            let b = &model.trips[idx];
+            model.editing = Some(b.id);
+            model.form.set_all(&b.to_form_values());
+            model.form.set_choice(4, b.food_id);
         }
+        CancelEdit => model.editing = None,
         Delete(idx) => {
-            let b = model.trips[idx].clone();
-            if confirm(b.clone()) {
-                orders.perform_cmd({
-                    async move {
-                        match ApiCall::GroceryItem.delete(b).await {
-                            Ok(s) if s.status().is_ok() => Deleted(Ok(())),
-                            _ => Deleted(Err(PageError::Delete)),
-                        }
-                    }
-                });
-            }
+            let b = &model.trips[idx];
+            delete_row(api_call, b.clone(), b.id, orders, Deleted);
         }
         Deleted(Ok(_)) => {
             orders.send_msg(Fetch);
         }
         Deleted(Err(err)) => model.err = Some(err),
-        Submit => match model.form.get_input_data() {
-            Ok(inputs) => match NewGroceryItem::from_input_data(inputs) {
-                Ok(nb) => {
-                    model.err = None;
-                    orders.perform_cmd({
-                        async move {
-                            match ApiCall::GroceryItem.post(nb).await {
-                                Ok(s) if s.status().is_ok() => Submitted(Ok(())),
-                                _ => Submitted(Err(PageError::Submit)),
-                            }
-                        }
-                    });
-                }
-                Err(err) => model.err = Some(err),
-            },
-            Err(err) => model.err = Some(err),
-        },
+        Submit => {
+            let item = model
+                .form
+                .get_input_data()
+                .and_then(validated::<NewGroceryItem>);
+            submit_row(
+                api_call,
+                item,
+                model.editing,
+                &mut model.err,
+                orders,
+                Submitted,
+            );
+        }
         Submitted(Ok(_)) => {
+            model.editing = None;
             orders.send_msg(Fetch);
         }
         Submitted(Err(err)) => model.err = Some(err),
     }
-    log!(model);
 }
@@ -1,6 +1,8 @@
 use crate::api_call::ApiCall;
+use diet_database::dependents::Dependents;
 use diet_database::grocery_trip::*;
 use diet_database::store::Store;
+use diet_database::TableType;
 use seed::{prelude::*, *};
 
 use super::*;
@@ -12,7 +14,9 @@ pub enum Msg {
     FetchedStores(Result<Vec<Store>, PageError>),
     FormUpdate(FormMsg),
     Edit(usize),
+    CancelEdit,
     Delete(usize),
+    FetchedDependents(usize, Result<Dependents, PageError>),
     Deleted(Result<(), PageError>),
     Submit,
     Submitted(Result<(), PageError>),
@@ -31,6 +35,9 @@ impl PageMsg for Msg {
     fn edit(i: usize) -> Self {
         Msg::Edit(i)
     }
+    fn cancel_edit() -> Self {
+        Msg::CancelEdit
+    }
 }
 
 #[derive(Debug, Clone, Default)]
@@ -39,6 +46,7 @@ pub struct Model {
     stores: Vec<Store>,
     form: Form,
     err: Option<PageError>,
+    editing: Option<i32>,
 }
 
 impl PageModel<Vec<GroceryTrip>, Msg> for Model {
@@ -50,6 +58,10 @@ impl PageModel<Vec<GroceryTrip>, Msg> for Model {
         self.err.as_ref()
     }
 
+    fn is_editing(&self) -> bool {
+        self.editing.is_some()
+    }
+
     fn form_fields(&self) -> Vec<Node<Msg>> {
         self.form.view().map_msg(Msg::FormUpdate)
     }
@@ -78,26 +90,23 @@ impl FromInputData for NewGroceryTrip {
     }
 }
 
+impl ToFormValues for GroceryTrip {
+    fn to_form_values(&self) -> Vec<String> {
+        vec![
+            date_value(self.date),
+            time_option_value(self.time),
+            self.store_id.to_string(),
+        ]
+    }
+}
+
 pub fn update(msg: Msg, model: &mut Model, orders: &mut impl Orders<Msg>) {
     use Msg::*;
+    let api_call = ApiCall::Table(TableType::GroceryTrip);
     match msg {
         Fetch => {
-            orders.perform_cmd({
-                async move {
-                    match ApiCall::GroceryTrip.get().await {
-                        Ok(s) => Fetched(Ok(s)),
-                        Err(_) => Fetched(Err(PageError::Load)),
-                    }
-                }
-            });
-            orders.perform_cmd({
-                async move {
-                    match ApiCall::Store.get().await {
-                        Ok(s) => FetchedStores(Ok(s)),
-                        Err(_) => FetchedStores(Err(PageError::Load)),
-                    }
-                }
-            });
+            fetch_rows(api_call, orders, Fetched);
+            fetch_rows(ApiCall::Table(TableType::Store), orders, FetchedStores);
         }
         Fetched(Ok(trips)) => model.trips = trips,
         Fetched(Err(err)) => model.err = Some(err),
@@ -115,47 +124,42 @@ pub fn update(msg: Msg, model: &mut Model, orders: &mut impl Orders<Msg>) {
         FetchedStores(Err(err)) => model.err = Some(err),
         FormUpdate(update_msg) => model.form.update(update_msg),
         Edit(idx) => {
-            let b = model.trips[idx].clone();
-            model.form.set_all(&vec![b].matrix()[0]);
+            let b = &model.trips[idx];
+            model.editing = Some(b.id);
+            model.form.set_all(&b.to_form_values());
         }
+        CancelEdit => model.editing = None,
         Delete(idx) => {
-            let b = model.trips[idx].clone();
-            if confirm(b.clone()) {
-                orders.perform_cmd({
-                    async move {
-                        match ApiCall::GroceryTrip.delete(b).await {
-                            Ok(s) if s.status().is_ok() => Deleted(Ok(())),
-                            _ => Deleted(Err(PageError::Delete)),
-                        }
-                    }
-                });
-            }
+            let id = model.trips[idx].id;
+            fetch_dependents(api_call, id, orders, move |x| FetchedDependents(idx, x));
+        }
+        FetchedDependents(idx, Ok(dependents)) => {
+            let b = &model.trips[idx];
+            delete_row_cascade(api_call, b.clone(), b.id, dependents, orders, Deleted);
         }
+        FetchedDependents(_, Err(err)) => model.err = Some(err),
         Deleted(Ok(_)) => {
             orders.send_msg(Fetch);
         }
         Deleted(Err(err)) => model.err = Some(err),
-        Submit => match model.form.get_input_data() {
-            Ok(inputs) => match NewGroceryTrip::from_input_data(inputs) {
-                Ok(nb) => {
-                    model.err = None;
-                    orders.perform_cmd({
-                        async move {
-                            match ApiCall::GroceryTrip.post(nb).await {
-                                Ok(s) if s.status().is_ok() => Submitted(Ok(())),
-                                _ => Submitted(Err(PageError::Submit)),
-                            }
-                        }
-                    });
-                }
-                Err(err) => model.err = Some(err),
-            },
-            Err(err) => model.err = Some(err),
-        },
+        Submit => {
+            let item = model
+                .form
+                .get_input_data()
+                .and_then(validated::<NewGroceryTrip>);
+            submit_row(
+                api_call,
+                item,
+                model.editing,
+                &mut model.err,
+                orders,
+                Submitted,
+            );
+        }
         Submitted(Ok(_)) => {
+            model.editing = None;
             orders.send_msg(Fetch);
         }
         Submitted(Err(err)) => model.err = Some(err),
     }
-    log!(model);
 }
@@ -1,5 +1,7 @@
-use crate::api_call::ApiCall;
+use crate::api_call::{preferred_units, ApiCall};
 use diet_database::metric::*;
+use diet_database::units::LengthUnit;
+use diet_database::TableType;
 use seed::{prelude::*, *};
 
 use super::*;
@@ -10,6 +12,7 @@ pub enum Msg {
     Fetched(Result<Vec<Metric>, PageError>),
     FormUpdate(FormMsg),
     Edit(usize),
+    CancelEdit,
     Delete(usize),
     Deleted(Result<(), PageError>),
     Submit,
@@ -23,6 +26,9 @@ impl PageMsg for Msg {
     fn edit(i: usize) -> Self {
         Msg::Edit(i)
     }
+    fn cancel_edit() -> Self {
+        Msg::CancelEdit
+    }
     fn submit() -> Self {
         Msg::Submit
     }
@@ -36,6 +42,7 @@ pub struct Model {
     metrics: Vec<Metric>,
     form: Form,
     err: Option<PageError>,
+    editing: Option<i32>,
 }
 
 impl PageModel<Vec<Metric>, Msg> for Model {
@@ -47,6 +54,10 @@ impl PageModel<Vec<Metric>, Msg> for Model {
         self.err.as_ref()
     }
 
+    fn is_editing(&self) -> bool {
+        self.editing.is_some()
+    }
+
     fn form_fields(&self) -> Vec<Node<Msg>> {
         self.form.view().map_msg(Msg::FormUpdate)
     }
@@ -62,11 +73,32 @@ impl FromInputData for NewMetric {
             waist_circum: inputs[4].try_float_option()?,
             chest_circum: inputs[5].try_float_option()?,
             thigh_circum: inputs[6].try_float_option()?,
+            length_unit: inputs[7]
+                .try_text()?
+                .parse()
+                .map_err(|_| PageError::form("unit"))?,
         })
     }
 }
 
+impl ToFormValues for Metric {
+    fn to_form_values(&self) -> Vec<String> {
+        vec![
+            date_value(self.date),
+            time_option_value(self.time),
+            option_value(self.body_fat),
+            option_value(self.gut_circum),
+            option_value(self.waist_circum),
+            option_value(self.chest_circum),
+            option_value(self.thigh_circum),
+            self.length_unit.to_string(),
+        ]
+    }
+}
+
 pub fn init() -> Model {
+    let units = vec![LengthUnit::Cm, LengthUnit::In];
+    let unit = preferred_units().unwrap_or_default().length();
     Model {
         form: Form {
             inputs: vec![
@@ -77,6 +109,11 @@ pub fn init() -> Model {
                 Input::new("Waist", InputType::FloatOption),
                 Input::new("Chest", InputType::FloatOption),
                 Input::new("Thigh", InputType::FloatOption),
+                Input::with_initial(
+                    "Unit",
+                    InputType::Select(units.iter().map(|x| x.to_string()).collect()),
+                    unit.suffix(),
+                ),
             ],
         },
         ..Default::default()
@@ -85,16 +122,10 @@ pub fn init() -> Model {
 
 pub fn update(msg: Msg, model: &mut Model, orders: &mut impl Orders<Msg>) {
     use Msg::*;
+    let api_call = ApiCall::Table(TableType::Metric);
     match msg {
         Fetch => {
-            orders.perform_cmd({
-                async move {
-                    match ApiCall::Metric.get().await {
-                        Ok(s) => Fetched(Ok(s)),
-                        Err(_) => Fetched(Err(PageError::Load)),
-                    }
-                }
-            });
+            fetch_rows(api_call, orders, Fetched);
         }
         Fetched(result) => match result {
             Ok(metrics) => model.metrics = metrics,
@@ -102,21 +133,14 @@ pub fn update(msg: Msg, model: &mut Model, orders: &mut impl Orders<Msg>) {
         },
         FormUpdate(update_msg) => model.form.update(update_msg),
         Edit(idx) => {
-            let b = model.metrics[idx];
-            model.form.set_all(&vec![b].matrix()[0]);
+            let b = &model.metrics[idx];
+            model.editing = Some(b.id);
+            model.form.set_all(&b.to_form_values());
         }
+        CancelEdit => model.editing = None,
         Delete(idx) => {
             let b = model.metrics[idx];
-            if confirm(b) {
-                orders.perform_cmd({
-                    async move {
-                        match ApiCall::Metric.delete(b).await {
-                            Ok(s) if s.status().is_ok() => Deleted(Ok(())),
-                            _ => Deleted(Err(PageError::Delete)),
-                        }
-                    }
-                });
-            }
+            delete_row(api_call, b, b.id, orders, Deleted);
         }
         Deleted(result) => match result {
             Ok(()) => {
@@ -124,29 +148,23 @@ pub fn update(msg: Msg, model: &mut Model, orders: &mut impl Orders<Msg>) {
             }
             Err(err) => model.err = Some(err),
         },
-        Submit => match model.form.get_input_data() {
-            Ok(inputs) => match NewMetric::from_input_data(inputs) {
-                Ok(nb) => {
-                    model.err = None;
-                    orders.perform_cmd({
-                        async move {
-                            match ApiCall::Metric.post(nb).await {
-                                Ok(s) if s.status().is_ok() => Submitted(Ok(())),
-                                _ => Submitted(Err(PageError::Submit)),
-                            }
-                        }
-                    });
-                }
-                Err(err) => model.err = Some(err),
-            },
-            Err(err) => model.err = Some(err),
-        },
+        Submit => {
+            let item = model.form.get_input_data().and_then(validated::<NewMetric>);
+            submit_row(
+                api_call,
+                item,
+                model.editing,
+                &mut model.err,
+                orders,
+                Submitted,
+            );
+        }
         Submitted(result) => match result {
             Ok(()) => {
+                model.editing = None;
                 orders.send_msg(Fetch);
             }
             Err(err) => model.err = Some(err),
         },
     }
-    log!(model);
 }
@@ -1,11 +1,25 @@
+use crate::api_call::{check, ApiCall};
+use diet_database::api_error::ApiError;
+use diet_database::dependents::Dependents;
+use diet_database::validate::FieldError;
 use diet_database::Tabular;
 use seed::{prelude::*, *};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
 
 pub mod bowel;
+pub mod composition;
+pub mod exercise;
+pub mod food;
+pub mod food_log;
 pub mod grocery_trip;
 pub mod grocery_item;
 pub mod metric;
+pub mod recipe;
+pub mod recipe_ingredient;
+pub mod sleep;
 pub mod store;
+pub mod water;
 pub mod weight;
 //pub mod generic;
 
@@ -13,6 +27,7 @@ pub trait PageMsg {
     /// Returns the msg cooresponding to deleting the ith item
     fn delete(_: usize) -> Self;
     fn edit(_: usize) -> Self;
+    fn cancel_edit() -> Self;
     fn submit() -> Self;
     fn load() -> Self;
 }
@@ -21,53 +36,189 @@ pub trait PageModel<T: Tabular, M: 'static + PageMsg> {
     fn data(&self) -> &T;
     fn form_fields(&self) -> Vec<Node<M>>;
     fn error(&self) -> Option<&PageError>;
+    /// Whether the form is editing an existing row rather than adding one
+    fn is_editing(&self) -> bool;
 
     fn view(&self) -> Node<M> {
         div![C!["page"], self.view_form(), self.view_table(),]
     }
 
     fn view_table(&self) -> Node<M> {
-        let headers = self.data().headers();
-        let matrix = self.data().matrix();
-        table![
-            tr![headers.iter().map(|header| { th![header] }),],
-            matrix
-                .iter()
-                .enumerate()
-                .map(|(i, row)| { tr![row.iter().map(|cell| { td![cell] }), edit_button(i), delete_button(i)] }),
-        ]
+        view_table(self.data(), |i| vec![edit_button(i), delete_button(i)])
     }
 
     fn view_form(&self) -> Node<M> {
         div![
             C!["form"],
             self.form_fields(),
-            submit_button(),
+            submit_button(self.is_editing()),
+            IF!(self.is_editing() => cancel_button()),
             view_error_msg(self.error()),
         ]
     }
 }
 
+/// The rows of `data`, each followed by the `actions` for it
+fn view_table<T: Tabular, M>(data: &T, actions: impl Fn(usize) -> Vec<Node<M>>) -> Node<M> {
+    let columns = data.columns();
+    let rows = data.rows();
+    table![
+        tr![columns.iter().map(|column| { th![&column.name] }),],
+        rows.iter().enumerate().map(|(i, row)| {
+            tr![
+                row.iter().zip(columns.iter()).map(|(cell, column)| {
+                    td![
+                        C![IF!(column.value_type.is_numeric() => "number")],
+                        &cell.display
+                    ]
+                }),
+                actions(i)
+            ]
+        }),
+    ]
+}
+
+/// Loads the rows of `api_call`, which come back as `fetched`
+fn fetch_rows<T, M>(
+    api_call: ApiCall,
+    orders: &mut impl Orders<M>,
+    fetched: impl FnOnce(Result<Vec<T>, PageError>) -> M + 'static,
+) where
+    T: 'static + DeserializeOwned,
+    M: 'static,
+{
+    orders.perform_cmd(async move { fetched(api_call.get().await.map_err(|_| PageError::Load)) });
+}
+
+/// Loads the rows deleting row `id` would also delete, which come back as
+/// `fetched`
+fn fetch_dependents<M: 'static>(
+    api_call: ApiCall,
+    id: i32,
+    orders: &mut impl Orders<M>,
+    fetched: impl FnOnce(Result<Dependents, PageError>) -> M + 'static,
+) {
+    orders.perform_cmd(async move {
+        fetched(api_call.dependents(id).await.map_err(|_| PageError::Load))
+    });
+}
+
+/// Deletes row `id` once the user confirms `item`.  The result comes back
+/// as `deleted`.
+fn delete_row<T, M>(
+    api_call: ApiCall,
+    item: T,
+    id: i32,
+    orders: &mut impl Orders<M>,
+    deleted: impl FnOnce(Result<(), PageError>) -> M + 'static,
+) where
+    Vec<T>: Tabular,
+    M: 'static,
+{
+    if confirm(item) {
+        orders.perform_cmd(async move { deleted(deleted_result(api_call.delete(id).await).await) });
+    }
+}
+
+/// Like `delete_row`, but also deletes the row's `dependents`
+fn delete_row_cascade<T, M>(
+    api_call: ApiCall,
+    item: T,
+    id: i32,
+    dependents: Dependents,
+    orders: &mut impl Orders<M>,
+    deleted: impl FnOnce(Result<(), PageError>) -> M + 'static,
+) where
+    Vec<T>: Tabular,
+    M: 'static,
+{
+    if confirm_cascade(item, dependents) {
+        orders.perform_cmd(async move {
+            deleted(deleted_result(api_call.delete_cascade(id).await).await)
+        });
+    }
+}
+
+async fn deleted_result(response: fetch::Result<Response>) -> Result<(), PageError> {
+    check(response)
+        .await
+        .map(|_| ())
+        .map_err(|err| PageError::from_api(err, PageError::Delete))
+}
+
+/// Adds `item`, read from the form, or updates row `editing` with it.  The
+/// result comes back as `submitted`, and an `item` that could not be read
+/// sets `err` instead.
+fn submit_row<N, M>(
+    api_call: ApiCall,
+    item: Result<N, PageError>,
+    editing: Option<i32>,
+    err: &mut Option<PageError>,
+    orders: &mut impl Orders<M>,
+    submitted: impl FnOnce(Result<(), PageError>) -> M + 'static,
+) where
+    N: 'static + Serialize,
+    M: 'static,
+{
+    match item {
+        Ok(item) => {
+            *err = None;
+            orders.perform_cmd(async move {
+                let response = match editing {
+                    Some(id) => api_call.put(id, item).await,
+                    None => api_call.post(item).await,
+                };
+                submitted(
+                    check(response)
+                        .await
+                        .map(|_| ())
+                        .map_err(|err| PageError::from_api(err, PageError::Submit)),
+                )
+            });
+        }
+        Err(e) => *err = Some(e),
+    }
+}
+
 fn confirm<T>(item: T) -> bool
+where
+    Vec<T>: Tabular,
+{
+    confirm_cascade(item, Dependents::default())
+}
+
+/// Like `confirm`, but also warns about the rows deleted along with the item
+fn confirm_cascade<T>(item: T, dependents: Dependents) -> bool
+where
+    Vec<T>: Tabular,
+{
+    let mut message = format!(
+        "Are you sure you want to delete this item?\n{}",
+        describe(item)
+    );
+    if !dependents.is_empty() {
+        message.push_str(&format!("\n\nThis will also delete {}.", dependents));
+    }
+    confirm_message(&message)
+}
+
+fn describe<T>(item: T) -> String
 where
     Vec<T>: Tabular,
 {
     let items = vec![item];
-    let info = items
+    items
         .headers()
         .iter()
         .zip(items.matrix()[0].iter())
         .map(|(head, val)| format!("{}: {}", head, val))
         .collect::<Vec<String>>()
-        .join("\n");
+        .join("\n")
+}
+
+fn confirm_message(message: &str) -> bool {
     web_sys::window()
-        .map(|x| {
-            x.confirm_with_message(&format!(
-                "Are you sure you want to delete this item?\n{}",
-                info
-            ))
-            .ok()
-        })
+        .map(|x| x.confirm_with_message(message).ok())
         .flatten()
         .unwrap()
 }
@@ -80,8 +231,13 @@ fn edit_button<T: 'static + PageMsg>(i: usize) -> Node<T> {
     button!["edit", ev(Ev::Click, move |_| T::edit(i)),]
 }
 
-fn submit_button<T: 'static + PageMsg>() -> Node<T> {
-    button!["Submit", ev(Ev::Click, move |_| T::submit()),]
+fn submit_button<T: 'static + PageMsg>(editing: bool) -> Node<T> {
+    let label = if editing { "Update" } else { "Submit" };
+    button![label, ev(Ev::Click, move |_| T::submit()),]
+}
+
+fn cancel_button<T: 'static + PageMsg>() -> Node<T> {
+    button!["Cancel", ev(Ev::Click, move |_| T::cancel_edit()),]
 }
 
 fn view_error_msg<T>(error: Option<&PageError>) -> Node<T> {
@@ -102,6 +258,8 @@ pub enum PageError {
     Delete,
     Load,
     Form(String),
+    Invalid(Vec<FieldError>),
+    Api(ApiError),
     Developer,
 }
 
@@ -109,6 +267,11 @@ impl PageError {
     pub fn form(s: &str) -> PageError {
         PageError::Form(s.to_string())
     }
+
+    /// The api's own error when it sent one, otherwise `fallback`
+    pub fn from_api(err: Option<ApiError>, fallback: PageError) -> PageError {
+        err.map(PageError::Api).unwrap_or(fallback)
+    }
 }
 
 use std::fmt::{Display, Error, Formatter};
@@ -121,6 +284,12 @@ impl Display for PageError {
             Delete => "Unable to delete item".to_string(),
             Load => "Cannot retrieve data".to_string(),
             Form(s) => format!("Field {} is invalid", s),
+            Invalid(errors) => errors
+                .iter()
+                .map(|x| x.to_string())
+                .collect::<Vec<String>>()
+                .join(", "),
+            Api(err) => err.to_string(),
             Developer => "The developer made a mistake!".to_string(),
         };
         write!(f, "{}", s)
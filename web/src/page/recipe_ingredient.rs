@@ -0,0 +1,173 @@
+use crate::api_call::ApiCall;
+use diet_database::food::Food;
+use diet_database::recipe::Recipe;
+use diet_database::recipe_ingredient::*;
+use diet_database::TableType;
+use seed::{prelude::*, *};
+
+use super::*;
+use crate::form::*;
+
+pub enum Msg {
+    Fetch,
+    Fetched(Result<Vec<RecipeIngredient>, PageError>),
+    FetchedRecipes(Result<Vec<Recipe>, PageError>),
+    FetchedFoods(Result<Vec<Food>, PageError>),
+    FormUpdate(FormMsg),
+    Edit(usize),
+    CancelEdit,
+    Delete(usize),
+    Deleted(Result<(), PageError>),
+    Submit,
+    Submitted(Result<(), PageError>),
+}
+
+impl PageMsg for Msg {
+    fn delete(i: usize) -> Self {
+        Msg::Delete(i)
+    }
+    fn submit() -> Self {
+        Msg::Submit
+    }
+    fn load() -> Self {
+        Msg::Fetch
+    }
+    fn edit(i: usize) -> Self {
+        Msg::Edit(i)
+    }
+    fn cancel_edit() -> Self {
+        Msg::CancelEdit
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Model {
+    data: Vec<RecipeIngredient>,
+    form: Form,
+    err: Option<PageError>,
+    editing: Option<i32>,
+}
+
+impl PageModel<Vec<RecipeIngredient>, Msg> for Model {
+    fn data(&self) -> &Vec<RecipeIngredient> {
+        &self.data
+    }
+
+    fn error(&self) -> Option<&PageError> {
+        self.err.as_ref()
+    }
+
+    fn is_editing(&self) -> bool {
+        self.editing.is_some()
+    }
+
+    fn form_fields(&self) -> Vec<Node<Msg>> {
+        self.form.view().map_msg(Msg::FormUpdate)
+    }
+}
+
+pub fn init() -> Model {
+    Model {
+        form: Form {
+            inputs: vec![
+                Input::new("Recipe", InputType::DropDown(vec![])),
+                Input::new("Food", InputType::Search(vec![])),
+                Input::new("Amount", InputType::Float),
+                Input::with_initial("Unit", InputType::Text, "g"),
+            ],
+        },
+        ..Default::default()
+    }
+}
+
+impl FromInputData for NewRecipeIngredient {
+    fn from_input_data(inputs: Vec<InputData>) -> Result<Self, PageError> {
+        Ok(NewRecipeIngredient {
+            recipe_id: inputs[0].try_int()?,
+            food_id: inputs[1]
+                .try_int_option()?
+                .ok_or_else(|| PageError::form("Food"))?,
+            amount: inputs[2].try_float()?,
+            unit: inputs[3].try_text()?,
+        })
+    }
+}
+
+impl ToFormValues for RecipeIngredient {
+    fn to_form_values(&self) -> Vec<String> {
+        vec![
+            self.recipe_id.to_string(),
+            String::new(),
+            self.amount.to_string(),
+            self.unit.clone(),
+        ]
+    }
+}
+
+pub fn update(msg: Msg, model: &mut Model, orders: &mut impl Orders<Msg>) {
+    use Msg::*;
+    let api_call = ApiCall::Table(TableType::RecipeIngredient);
+    match msg {
+        Fetch => {
+            fetch_rows(api_call, orders, Fetched);
+            fetch_rows(ApiCall::Table(TableType::Recipe), orders, FetchedRecipes);
+            fetch_rows(ApiCall::Table(TableType::Food), orders, FetchedFoods);
+        }
+        Fetched(Ok(data)) => model.data = data,
+        Fetched(Err(err)) => model.err = Some(err),
+        FetchedRecipes(Ok(recipes)) => {
+            model.form.inputs[0] = Input::new(
+                "Recipe",
+                InputType::DropDown(
+                    recipes
+                        .into_iter()
+                        .map(|recipe| (recipe.id, recipe.name))
+                        .collect(),
+                ),
+            )
+        }
+        FetchedRecipes(Err(err)) => model.err = Some(err),
+        FetchedFoods(Ok(foods)) => {
+            model.form.inputs[1] = Input::new(
+                "Food",
+                InputType::Search(foods.iter().map(|food| (food.id, food.label())).collect()),
+            )
+        }
+        FetchedFoods(Err(err)) => model.err = Some(err),
+        FormUpdate(update_msg) => model.form.update(update_msg),
+        Edit(idx) => {
+            let b = &model.data[idx];
+            model.editing = Some(b.id);
+            model.form.set_all(&b.to_form_values());
+            model.form.set_choice(1, Some(b.food_id));
+        }
+        CancelEdit => model.editing = None,
+        Delete(idx) => {
+            let b = &model.data[idx];
+            delete_row(api_call, b.clone(), b.id, orders, Deleted);
+        }
+        Deleted(Ok(_)) => {
+            orders.send_msg(Fetch);
+        }
+        Deleted(Err(err)) => model.err = Some(err),
+        Submit => {
+            let item = model
+                .form
+                .get_input_data()
+                .and_then(validated::<NewRecipeIngredient>);
+            submit_row(
+                api_call,
+                item,
+                model.editing,
+                &mut model.err,
+                orders,
+                Submitted,
+            );
+        }
+        Submitted(Ok(_)) => {
+            model.editing = None;
+            orders.send_msg(Fetch);
+        }
+        Submitted(Err(err)) => model.err = Some(err),
+    }
+}
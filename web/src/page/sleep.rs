@@ -0,0 +1,173 @@
+use crate::api_call::ApiCall;
+use chrono::Duration;
+use diet_database::sleep::*;
+use diet_database::TableType;
+use seed::{prelude::*, *};
+
+use super::*;
+use crate::form::*;
+
+pub enum Msg {
+    Fetch,
+    Fetched(Result<Vec<Sleep>, PageError>),
+    FormUpdate(FormMsg),
+    Edit(usize),
+    CancelEdit,
+    Delete(usize),
+    Deleted(Result<(), PageError>),
+    Submit,
+    Submitted(Result<(), PageError>),
+}
+
+impl PageMsg for Msg {
+    fn delete(i: usize) -> Self {
+        Msg::Delete(i)
+    }
+    fn edit(i: usize) -> Self {
+        Msg::Edit(i)
+    }
+    fn cancel_edit() -> Self {
+        Msg::CancelEdit
+    }
+    fn submit() -> Self {
+        Msg::Submit
+    }
+    fn load() -> Self {
+        Msg::Fetch
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Model {
+    data: Vec<Sleep>,
+    form: Form,
+    err: Option<PageError>,
+    editing: Option<i32>,
+}
+
+impl PageModel<Vec<Sleep>, Msg> for Model {
+    fn data(&self) -> &Vec<Sleep> {
+        &self.data
+    }
+
+    fn error(&self) -> Option<&PageError> {
+        self.err.as_ref()
+    }
+
+    fn is_editing(&self) -> bool {
+        self.editing.is_some()
+    }
+
+    fn form_fields(&self) -> Vec<Node<Msg>> {
+        self.form.view().map_msg(Msg::FormUpdate)
+    }
+
+    /// The nights are followed by the average of each week, newest first
+    fn view(&self) -> Node<Msg> {
+        let mut weeks = weekly_averages(&self.data);
+        weeks.reverse();
+        div![
+            C!["page"],
+            self.view_form(),
+            self.view_table(),
+            h3!["Weekly Averages"],
+            view_table(&weeks, |_| Vec::new()),
+        ]
+    }
+}
+
+/// The night's date is taken from when it began.  Waking at or before that
+/// time of day means waking the next morning.
+impl FromInputData for NewSleep {
+    fn from_input_data(inputs: Vec<InputData>) -> Result<Self, PageError> {
+        let bed = inputs[0].try_datetime()?;
+        Ok(Self {
+            date: bed.date(),
+            bed_time: bed.time(),
+            wake_time: inputs[1].try_time()?,
+            quality: inputs[2]
+                .try_text()?
+                .parse()
+                .map_err(|_| PageError::form("quality"))?,
+        })
+    }
+}
+
+impl ToFormValues for Sleep {
+    fn to_form_values(&self) -> Vec<String> {
+        vec![
+            datetime_value(self.bed()),
+            time_option_value(Some(self.wake_time)),
+            self.quality.to_string(),
+        ]
+    }
+}
+
+/// Last night, from 11 PM yesterday
+fn default_bed_time() -> String {
+    let yesterday = chrono::Local::today().naive_local() - Duration::days(1);
+    datetime_value(yesterday.and_hms(23, 0, 0))
+}
+
+pub fn init() -> Model {
+    let qualities = (1..=5).map(|x| x.to_string()).collect();
+    Model {
+        form: Form {
+            inputs: vec![
+                Input::with_initial("Bed time", InputType::DateTime, &default_bed_time()),
+                Input::new("Wake time", InputType::Time),
+                Input::with_initial("Quality (1-5)", InputType::Select(qualities), "3"),
+            ],
+        },
+        ..Default::default()
+    }
+}
+
+pub fn update(msg: Msg, model: &mut Model, orders: &mut impl Orders<Msg>) {
+    use Msg::*;
+    let api_call = ApiCall::Table(TableType::Sleep);
+    match msg {
+        Fetch => {
+            fetch_rows(api_call, orders, Fetched);
+        }
+        Fetched(result) => match result {
+            Ok(data) => model.data = data,
+            Err(err) => model.err = Some(err),
+        },
+        FormUpdate(update_msg) => model.form.update(update_msg),
+        Edit(idx) => {
+            let b = &model.data[idx];
+            model.editing = Some(b.id);
+            model.form.set_all(&b.to_form_values());
+        }
+        CancelEdit => model.editing = None,
+        Delete(idx) => {
+            let b = model.data[idx];
+            delete_row(api_call, b, b.id, orders, Deleted);
+        }
+        Deleted(result) => match result {
+            Ok(()) => {
+                orders.send_msg(Fetch);
+            }
+            Err(err) => model.err = Some(err),
+        },
+        Submit => {
+            let item = model.form.get_input_data().and_then(validated::<NewSleep>);
+            submit_row(
+                api_call,
+                item,
+                model.editing,
+                &mut model.err,
+                orders,
+                Submitted,
+            );
+        }
+        Submitted(result) => match result {
+            Ok(()) => {
+                model.editing = None;
+                orders.send_msg(Fetch);
+            }
+            Err(err) => model.err = Some(err),
+        },
+    }
+}
@@ -1,5 +1,7 @@
 use crate::api_call::ApiCall;
+use diet_database::dependents::Dependents;
 use diet_database::store::*;
+use diet_database::TableType;
 use seed::{prelude::*, *};
 
 use super::*;
@@ -10,7 +12,9 @@ pub enum Msg {
     Fetched(Result<Vec<Store>, PageError>),
     FormUpdate(FormMsg),
     Edit(usize),
+    CancelEdit,
     Delete(usize),
+    FetchedDependents(usize, Result<Dependents, PageError>),
     Deleted(Result<(), PageError>),
     Submit,
     Submitted(Result<(), PageError>),
@@ -23,6 +27,9 @@ impl PageMsg for Msg {
     fn edit(i: usize) -> Self {
         Msg::Edit(i)
     }
+    fn cancel_edit() -> Self {
+        Msg::CancelEdit
+    }
     fn submit() -> Self {
         Msg::Submit
     }
@@ -36,6 +43,7 @@ pub struct Model {
     stores: Vec<Store>,
     form: Form,
     err: Option<PageError>,
+    editing: Option<i32>,
 }
 
 impl PageModel<Vec<Store>, Msg> for Model {
@@ -46,6 +54,10 @@ impl PageModel<Vec<Store>, Msg> for Model {
         self.err.as_ref()
     }
 
+    fn is_editing(&self) -> bool {
+        self.editing.is_some()
+    }
+
     fn form_fields(&self) -> Vec<Node<Msg>> {
         self.form.view().map_msg(Msg::FormUpdate)
     }
@@ -68,14 +80,20 @@ impl FromInputData for NewStore {
     }
 }
 
+impl ToFormValues for Store {
+    fn to_form_values(&self) -> Vec<String> {
+        vec![self.name.clone()]
+    }
+}
+
 pub fn update(msg: Msg, model: &mut Model, orders: &mut impl Orders<Msg>) {
     use Msg::*;
+    let api_call = ApiCall::Table(TableType::Store);
     match msg {
         Fetch => {
-            log!("Fetching stores");
             orders.perform_cmd({
                 async move {
-                    let stores = ApiCall::Store.get().await.unwrap_or_default();
+                    let stores = api_call.get().await.unwrap_or_default();
                     Msg::Fetched(Ok(stores))
                 }
             });
@@ -86,52 +104,43 @@ pub fn update(msg: Msg, model: &mut Model, orders: &mut impl Orders<Msg>) {
         },
         FormUpdate(update_msg) => model.form.update(update_msg),
         Edit(idx) => {
-            let b = model.stores[idx].clone();
-            model.form.set_all(&vec![b].matrix()[0]);
+            let b = &model.stores[idx];
+            model.editing = Some(b.id);
+            model.form.set_all(&b.to_form_values());
         }
+        CancelEdit => model.editing = None,
         Delete(idx) => {
-            log!("deleting store");
-            let s = model.stores[idx].clone();
-            if confirm(s.clone()) {
-                orders.perform_cmd({
-                    async move {
-                        match ApiCall::Store.delete(s).await {
-                            Ok(s) if s.status().is_ok() => Deleted(Ok(())),
-                            _ => Deleted(Err(PageError::Delete)),
-                        }
-                    }
-                });
-            }
+            let id = model.stores[idx].id;
+            fetch_dependents(api_call, id, orders, move |x| FetchedDependents(idx, x));
+        }
+        FetchedDependents(idx, Ok(dependents)) => {
+            let s = &model.stores[idx];
+            delete_row_cascade(api_call, s.clone(), s.id, dependents, orders, Deleted);
         }
+        FetchedDependents(_, Err(err)) => model.err = Some(err),
         Deleted(result) => match result {
             Ok(()) => {
                 orders.send_msg(Fetch);
             }
             Err(err) => model.err = Some(err),
         },
-        Submit => match model.form.get_input_data() {
-            Ok(inputs) => match NewStore::from_input_data(inputs) {
-                Ok(nb) => {
-                    model.err = None;
-                    orders.perform_cmd({
-                        async move {
-                            match ApiCall::Store.post(nb).await {
-                                Ok(s) if s.status().is_ok() => Submitted(Ok(())),
-                                _ => Submitted(Err(PageError::Submit)),
-                            }
-                        }
-                    });
-                }
-                Err(err) => model.err = Some(err),
-            },
-            Err(err) => model.err = Some(err),
-        },
+        Submit => {
+            let item = model.form.get_input_data().and_then(validated::<NewStore>);
+            submit_row(
+                api_call,
+                item,
+                model.editing,
+                &mut model.err,
+                orders,
+                Submitted,
+            );
+        }
         Submitted(result) => match result {
             Ok(()) => {
+                model.editing = None;
                 orders.send_msg(Fetch);
             }
             Err(err) => model.err = Some(err),
         },
     }
-    log!(model);
 }
@@ -0,0 +1,242 @@
+use crate::api_call::{preferred_units, ApiCall};
+use diet_database::units::VolumeUnit;
+use diet_database::water::*;
+use diet_database::TableType;
+use seed::{prelude::*, *};
+
+use super::*;
+use crate::form::*;
+
+pub enum Msg {
+    Fetch,
+    Fetched(Result<Vec<Water>, PageError>),
+    FormUpdate(FormMsg),
+    Edit(usize),
+    CancelEdit,
+    Delete(usize),
+    Deleted(Result<(), PageError>),
+    Submit,
+    Submitted(Result<(), PageError>),
+    TargetChanged(String),
+    SaveTarget,
+}
+
+impl PageMsg for Msg {
+    fn delete(i: usize) -> Self {
+        Msg::Delete(i)
+    }
+    fn edit(i: usize) -> Self {
+        Msg::Edit(i)
+    }
+    fn cancel_edit() -> Self {
+        Msg::CancelEdit
+    }
+    fn submit() -> Self {
+        Msg::Submit
+    }
+    fn load() -> Self {
+        Msg::Fetch
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Model {
+    data: Vec<Water>,
+    form: Form,
+    err: Option<PageError>,
+    editing: Option<i32>,
+    target_input: String,
+}
+
+/// Where the daily target is kept, in millilitres
+const TARGET_KEY: &str = "water_target";
+
+/// The daily target the user set, in millilitres
+fn target() -> Option<f32> {
+    LocalStorage::get(TARGET_KEY).ok()
+}
+
+/// The unit drinks and totals are shown in
+fn display_unit() -> VolumeUnit {
+    preferred_units().unwrap_or_default().volume()
+}
+
+/// The daily target in `display_unit`, as it is shown in its input
+fn target_value() -> String {
+    target()
+        .map(|ml| format!("{:.0}", VolumeUnit::Ml.convert(ml, display_unit())))
+        .unwrap_or_default()
+}
+
+impl Model {
+    fn view_target(&self) -> Node<Msg> {
+        div![
+            C!["form"],
+            label![format!("Daily target ({}): ", display_unit())],
+            input![
+                attrs!(At::Type => "text", At::Value => self.target_input),
+                ev(Ev::Input, |ev| Msg::TargetChanged(get_event_value(ev))),
+            ],
+            button!["Save target", ev(Ev::Click, |_| Msg::SaveTarget)],
+        ]
+    }
+}
+
+impl PageModel<Vec<Water>, Msg> for Model {
+    fn data(&self) -> &Vec<Water> {
+        &self.data
+    }
+
+    fn error(&self) -> Option<&PageError> {
+        self.err.as_ref()
+    }
+
+    fn is_editing(&self) -> bool {
+        self.editing.is_some()
+    }
+
+    fn form_fields(&self) -> Vec<Node<Msg>> {
+        self.form.view().map_msg(Msg::FormUpdate)
+    }
+
+    /// The drinks are followed by how much was drunk each day, newest
+    /// first, against the daily target
+    fn view(&self) -> Node<Msg> {
+        let unit = display_unit();
+        let target = target().map(|ml| VolumeUnit::Ml.convert(ml, unit));
+        let mut totals = daily_totals(&self.data, unit, target);
+        totals.reverse();
+        div![
+            C!["page"],
+            self.view_form(),
+            self.view_table(),
+            h3!["Daily Totals"],
+            self.view_target(),
+            view_table(&totals, |_| Vec::new()),
+        ]
+    }
+}
+
+impl FromInputData for NewWater {
+    fn from_input_data(inputs: Vec<InputData>) -> Result<Self, PageError> {
+        Ok(Self {
+            date: inputs[0].try_date()?,
+            time: inputs[1].try_time_option()?,
+            volume: inputs[2].try_float()?,
+            unit: inputs[3]
+                .try_text()?
+                .parse()
+                .map_err(|_| PageError::form("unit"))?,
+            beverage: inputs[4]
+                .try_text()?
+                .parse()
+                .map_err(|_| PageError::form("beverage"))?,
+        })
+    }
+}
+
+impl ToFormValues for Water {
+    fn to_form_values(&self) -> Vec<String> {
+        vec![
+            date_value(self.date),
+            time_option_value(self.time),
+            self.volume.to_string(),
+            self.unit.to_string(),
+            self.beverage.to_string(),
+        ]
+    }
+}
+
+pub fn init() -> Model {
+    let units = vec![VolumeUnit::Ml, VolumeUnit::FlOz];
+    let unit = display_unit();
+    Model {
+        form: Form {
+            inputs: vec![
+                Input::new("Date", InputType::Date),
+                Input::new("Time", InputType::TimeOption),
+                Input::new("Volume", InputType::Float),
+                Input::with_initial(
+                    "Unit",
+                    InputType::Select(units.iter().map(|x| x.to_string()).collect()),
+                    unit.suffix(),
+                ),
+                Input::new(
+                    "Beverage",
+                    InputType::Select(Beverage::ALL.iter().map(|x| x.to_string()).collect()),
+                ),
+            ],
+        },
+        target_input: target_value(),
+        ..Default::default()
+    }
+}
+
+pub fn update(msg: Msg, model: &mut Model, orders: &mut impl Orders<Msg>) {
+    use Msg::*;
+    let api_call = ApiCall::Table(TableType::Water);
+    match msg {
+        Fetch => {
+            fetch_rows(api_call, orders, Fetched);
+        }
+        Fetched(result) => match result {
+            Ok(data) => {
+                model.data = data;
+                model.target_input = target_value();
+            }
+            Err(err) => model.err = Some(err),
+        },
+        FormUpdate(update_msg) => model.form.update(update_msg),
+        Edit(idx) => {
+            let b = &model.data[idx];
+            model.editing = Some(b.id);
+            model.form.set_all(&b.to_form_values());
+        }
+        CancelEdit => model.editing = None,
+        Delete(idx) => {
+            let b = model.data[idx];
+            delete_row(api_call, b, b.id, orders, Deleted);
+        }
+        Deleted(result) => match result {
+            Ok(()) => {
+                orders.send_msg(Fetch);
+            }
+            Err(err) => model.err = Some(err),
+        },
+        Submit => {
+            let item = model.form.get_input_data().and_then(validated::<NewWater>);
+            submit_row(
+                api_call,
+                item,
+                model.editing,
+                &mut model.err,
+                orders,
+                Submitted,
+            );
+        }
+        Submitted(result) => match result {
+            Ok(()) => {
+                model.editing = None;
+                orders.send_msg(Fetch);
+            }
+            Err(err) => model.err = Some(err),
+        },
+        TargetChanged(value) => model.target_input = value,
+        SaveTarget => {
+            let value = model.target_input.trim();
+            if value.is_empty() {
+                LocalStorage::remove(TARGET_KEY).ok();
+                model.err = None;
+            } else {
+                match value.parse::<f32>() {
+                    Ok(target) if target > 0.0 => {
+                        let ml = display_unit().convert(target, VolumeUnit::Ml);
+                        LocalStorage::insert(TARGET_KEY, &ml).ok();
+                        model.err = None;
+                    }
+                    _ => model.err = Some(PageError::form("Daily target")),
+                }
+            }
+        }
+    }
+}
@@ -1,5 +1,7 @@
-use crate::api_call::ApiCall;
+use crate::api_call::{preferred_units, ApiCall};
+use diet_database::units::WeightUnit;
 use diet_database::weight::*;
+use diet_database::TableType;
 use seed::{prelude::*, *};
 
 use super::*;
@@ -10,6 +12,7 @@ pub enum Msg {
     Fetched(Result<Vec<Weight>, PageError>),
     FormUpdate(FormMsg),
     Edit(usize),
+    CancelEdit,
     Delete(usize),
     Deleted(Result<(), PageError>),
     Submit,
@@ -23,6 +26,9 @@ impl PageMsg for Msg {
     fn edit(i: usize) -> Self {
         Msg::Edit(i)
     }
+    fn cancel_edit() -> Self {
+        Msg::CancelEdit
+    }
     fn submit() -> Self {
         Msg::Submit
     }
@@ -36,6 +42,7 @@ pub struct Model {
     data: Vec<Weight>,
     form: Form,
     err: Option<PageError>,
+    editing: Option<i32>,
 }
 
 impl PageModel<Vec<Weight>, Msg> for Model {
@@ -47,6 +54,10 @@ impl PageModel<Vec<Weight>, Msg> for Model {
         self.err.as_ref()
     }
 
+    fn is_editing(&self) -> bool {
+        self.editing.is_some()
+    }
+
     fn form_fields(&self) -> Vec<Node<Msg>> {
         self.form.view().map_msg(Msg::FormUpdate)
     }
@@ -58,17 +69,39 @@ impl FromInputData for NewWeight {
             date: inputs[0].try_date()?,
             time: inputs[1].try_time_option()?,
             value: inputs[2].try_float()?,
+            unit: inputs[3]
+                .try_text()?
+                .parse()
+                .map_err(|_| PageError::form("unit"))?,
         })
     }
 }
 
+impl ToFormValues for Weight {
+    fn to_form_values(&self) -> Vec<String> {
+        vec![
+            date_value(self.date),
+            time_option_value(self.time),
+            self.value.to_string(),
+            self.unit.to_string(),
+        ]
+    }
+}
+
 pub fn init() -> Model {
+    let units = vec![WeightUnit::Kg, WeightUnit::Lb];
+    let unit = preferred_units().unwrap_or_default().weight();
     Model {
         form: Form {
             inputs: vec![
                 Input::new("Date", InputType::Date),
                 Input::new("Time", InputType::TimeOption),
                 Input::new("Weight", InputType::Float),
+                Input::with_initial(
+                    "Unit",
+                    InputType::Select(units.iter().map(|x| x.to_string()).collect()),
+                    unit.suffix(),
+                ),
             ],
         },
         ..Default::default()
@@ -77,17 +110,10 @@ pub fn init() -> Model {
 
 pub fn update(msg: Msg, model: &mut Model, orders: &mut impl Orders<Msg>) {
     use Msg::*;
-    let api_call = ApiCall::Weight;
+    let api_call = ApiCall::Table(TableType::Weight);
     match msg {
         Fetch => {
-            orders.perform_cmd({
-                async move {
-                    match api_call.get().await {
-                        Ok(s) => Fetched(Ok(s)),
-                        Err(_) => Fetched(Err(PageError::Load)),
-                    }
-                }
-            });
+            fetch_rows(api_call, orders, Fetched);
         }
         Fetched(result) => match result {
             Ok(data) => model.data = data,
@@ -95,21 +121,14 @@ pub fn update(msg: Msg, model: &mut Model, orders: &mut impl Orders<Msg>) {
         },
         FormUpdate(update_msg) => model.form.update(update_msg),
         Edit(idx) => {
-            let b = model.data[idx];
-            model.form.set_all(&vec![b].matrix()[0]);
+            let b = &model.data[idx];
+            model.editing = Some(b.id);
+            model.form.set_all(&b.to_form_values());
         }
+        CancelEdit => model.editing = None,
         Delete(idx) => {
             let b = model.data[idx];
-            if confirm(b) {
-                orders.perform_cmd({
-                    async move {
-                        match api_call.delete(b).await {
-                            Ok(s) if s.status().is_ok() => Deleted(Ok(())),
-                            _ => Deleted(Err(PageError::Delete)),
-                        }
-                    }
-                });
-            }
+            delete_row(api_call, b, b.id, orders, Deleted);
         }
         Deleted(result) => match result {
             Ok(()) => {
@@ -117,29 +136,23 @@ pub fn update(msg: Msg, model: &mut Model, orders: &mut impl Orders<Msg>) {
             }
             Err(err) => model.err = Some(err),
         },
-        Submit => match model.form.get_input_data() {
-            Ok(inputs) => match NewWeight::from_input_data(inputs) {
-                Ok(nb) => {
-                    model.err = None;
-                    orders.perform_cmd({
-                        async move {
-                            match api_call.post(nb).await {
-                                Ok(s) if s.status().is_ok() => Submitted(Ok(())),
-                                _ => Submitted(Err(PageError::Submit)),
-                            }
-                        }
-                    });
-                }
-                Err(err) => model.err = Some(err),
-            },
-            Err(err) => model.err = Some(err),
-        },
+        Submit => {
+            let item = model.form.get_input_data().and_then(validated::<NewWeight>);
+            submit_row(
+                api_call,
+                item,
+                model.editing,
+                &mut model.err,
+                orders,
+                Submitted,
+            );
+        }
         Submitted(result) => match result {
             Ok(()) => {
+                model.editing = None;
                 orders.send_msg(Fetch);
             }
             Err(err) => model.err = Some(err),
         },
     }
-    log!(model);
 }